@@ -0,0 +1,33 @@
+// Runtime API for querying futarchy markets
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use pallet_futarchy::MarketStats;
+use sp_runtime::FixedU128;
+
+sp_api::decl_runtime_apis! {
+    pub trait FutarchyApi<AccountId, Balance, Hash, BlockNumber> where
+        AccountId: Codec,
+        Balance: Codec,
+        Hash: Codec,
+        BlockNumber: Codec,
+    {
+        // Current marginal price of an outcome, `None` if the market or
+        // outcome does not exist
+        fn market_price(market_id: Hash, outcome: u32) -> Option<FixedU128>;
+        // Current value of all of an account's positions across markets
+        fn account_exposure(who: AccountId) -> Balance;
+        // What claiming the winnings of a market would pay an account, `None`
+        // while the outcome is not final
+        fn winnings_preview(market_id: Hash, who: AccountId) -> Option<Balance>;
+        // Number of markets per status and the liquidity held across all of them
+        fn market_stats() -> MarketStats<Balance>;
+        // Price of an outcome before and after a hypothetical buy of
+        // `trade_amount` shares, `None` if the market could not take it
+        fn price_impact(market_id: Hash, outcome: u32, trade_amount: Balance) -> Option<(FixedU128, FixedU128)>;
+        // Blocks left until a market stops trading, `None` once its trading
+        // end has passed or it is no longer open
+        fn blocks_until_close(market_id: Hash) -> Option<BlockNumber>;
+    }
+}
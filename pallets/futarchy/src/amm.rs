@@ -0,0 +1,224 @@
+// Constant-product market maker
+//
+// The pool holds a reserve r_i of every outcome share and keeps the product
+// k = prod_i r_i constant. Buying s shares of outcome i for x collateral mints
+// x complete sets into the pool and takes s shares of i out:
+//
+//     (r_i + x - s) * prod_{j != i} (r_j + x) >= k
+//
+// Selling s shares of i for x collateral adds the shares to the pool and burns
+// x complete sets out of it:
+//
+//     (r_i + s - x) * prod_{j != i} (r_j - x) >= k
+//
+// Both are solved for x by bisection, rounding in favour of the pool. The
+// invariant is checked as a product of per-outcome ratios so it does not
+// overflow for markets with many outcomes.
+
+use sp_runtime::{
+    helpers_128bit::multiply_by_rational_with_rounding,
+    traits::{CheckedAdd, CheckedMul, One, Zero},
+    FixedPointNumber, FixedU128, Rounding,
+};
+use sp_std::prelude::*;
+
+// a * b / c without intermediate overflow, rounded down
+pub fn mul_div(a: u128, b: u128, c: u128) -> Option<u128> {
+    multiply_by_rational_with_rounding(a, b, c, Rounding::Down)
+}
+
+// Product of after_i / before_i over every outcome
+pub fn invariant_ratio(before: &[u128], after: &[u128]) -> Option<FixedU128> {
+    if before.len() != after.len() {
+        return None;
+    }
+    before
+        .iter()
+        .zip(after.iter())
+        .try_fold(FixedU128::one(), |acc, (&b, &a)| {
+            acc.checked_mul(&FixedU128::checked_from_rational(a, b)?)
+        })
+}
+
+fn preserves_invariant(before: &[u128], after: &[u128]) -> bool {
+    invariant_ratio(before, after).map_or(false, |r| r >= FixedU128::one())
+}
+
+// Reserves after a buy of `amount` shares of `outcome` paid with `cost`
+pub fn apply_buy(reserves: &[u128], outcome: usize, amount: u128, cost: u128) -> Option<Vec<u128>> {
+    let mut after = reserves
+        .iter()
+        .map(|r| r.checked_add(cost))
+        .collect::<Option<Vec<_>>>()?;
+    let r_i = after.get_mut(outcome)?;
+    *r_i = r_i.checked_sub(amount)?;
+    if after.iter().any(|r| r.is_zero()) {
+        return None;
+    }
+    Some(after)
+}
+
+// Reserves after a sell of `amount` shares of `outcome` paying out `proceeds`
+pub fn apply_sell(reserves: &[u128], outcome: usize, amount: u128, proceeds: u128) -> Option<Vec<u128>> {
+    let mut after = reserves.to_vec();
+    let r_i = after.get_mut(outcome)?;
+    *r_i = r_i.checked_add(amount)?;
+    let after = after
+        .iter()
+        .map(|r| r.checked_sub(proceeds))
+        .collect::<Option<Vec<_>>>()?;
+    if after.iter().any(|r| r.is_zero()) {
+        return None;
+    }
+    Some(after)
+}
+
+// Smallest collateral amount buying `amount` shares of `outcome`
+pub fn buy_cost(reserves: &[u128], outcome: usize, amount: u128) -> Option<u128> {
+    if reserves.iter().any(|r| r.is_zero()) || outcome >= reserves.len() {
+        return None;
+    }
+
+    // Outcome prices are below one, so the cost never exceeds the share count
+    let (mut lo, mut hi) = (0u128, amount);
+    if !apply_buy(reserves, outcome, amount, hi).map_or(false, |a| preserves_invariant(reserves, &a)) {
+        return None;
+    }
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        match apply_buy(reserves, outcome, amount, mid) {
+            Some(after) if preserves_invariant(reserves, &after) => hi = mid,
+            _ => lo = mid + 1,
+        }
+    }
+    Some(hi)
+}
+
+// Largest collateral amount paid out for selling `amount` shares of `outcome`
+pub fn sell_proceeds(reserves: &[u128], outcome: usize, amount: u128) -> Option<u128> {
+    if reserves.iter().any(|r| r.is_zero()) || outcome >= reserves.len() {
+        return None;
+    }
+
+    let (mut lo, mut hi) = (0u128, amount);
+    while lo < hi {
+        let mid = lo + (hi - lo + 1) / 2;
+        match apply_sell(reserves, outcome, amount, mid) {
+            Some(after) if preserves_invariant(reserves, &after) => lo = mid,
+            _ => hi = mid - 1,
+        }
+    }
+    Some(lo)
+}
+
+// Marginal price of every outcome, (1 / r_i) / sum_j (1 / r_j), evaluated as
+// 1 / sum_j (r_i / r_j) to keep precision for large reserves
+pub fn prices(reserves: &[u128]) -> Option<Vec<FixedU128>> {
+    reserves
+        .iter()
+        .map(|&r_i| {
+            reserves
+                .iter()
+                .try_fold(FixedU128::zero(), |acc, &r_j| {
+                    acc.checked_add(&FixedU128::checked_from_rational(r_i, r_j)?)
+                })?
+                .reciprocal()
+        })
+        .collect()
+}
+
+// Add `amount` complete sets of funding without moving prices. Each reserve
+// grows in proportion to its share of the largest reserve; the unbalanced
+// remainder of every outcome is returned to the provider.
+pub fn add_funding(reserves: &[u128], amount: u128) -> Option<(Vec<u128>, Vec<u128>)> {
+    let max = reserves.iter().copied().max()?;
+    let mut after = Vec::with_capacity(reserves.len());
+    let mut returned = Vec::with_capacity(reserves.len());
+    for &r in reserves {
+        let added = mul_div(amount, r, max)?;
+        after.push(r.checked_add(added)?);
+        returned.push(amount - added);
+    }
+    Some((after, returned))
+}
+
+// Remove the `part / whole` slice of every reserve, returning the new
+// reserves and the shares taken out per outcome
+pub fn remove_funding(reserves: &[u128], part: u128, whole: u128) -> Option<(Vec<u128>, Vec<u128>)> {
+    let removed = reserves
+        .iter()
+        .map(|&r| mul_div(r, part, whole))
+        .collect::<Option<Vec<_>>>()?;
+    let after = reserves
+        .iter()
+        .zip(removed.iter())
+        .map(|(&r, &x)| r.checked_sub(x))
+        .collect::<Option<Vec<_>>>()?;
+    Some((after, removed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn buy_cost_is_the_least_that_keeps_k() {
+        let reserves = [1_000, 1_000];
+        let cost = buy_cost(&reserves, 0, 100).unwrap();
+        assert!(cost > 0 && cost < 100);
+
+        let after = apply_buy(&reserves, 0, 100, cost).unwrap();
+        assert!(invariant_ratio(&reserves, &after).unwrap() >= FixedU128::one());
+        // One unit less would leave the pool short
+        let short = apply_buy(&reserves, 0, 100, cost - 1).unwrap();
+        assert!(invariant_ratio(&reserves, &short).unwrap() < FixedU128::one());
+
+        assert_eq!(buy_cost(&reserves, 2, 100), None);
+        assert_eq!(buy_cost(&[1_000, 0], 0, 100), None);
+    }
+
+    #[test]
+    fn buy_then_sell_preserves_k() {
+        let reserves = [1_000, 4_000, 2_500];
+        let cost = buy_cost(&reserves, 1, 300).unwrap();
+        let bought = apply_buy(&reserves, 1, 300, cost).unwrap();
+
+        let proceeds = sell_proceeds(&bought, 1, 300).unwrap();
+        let sold = apply_sell(&bought, 1, 300, proceeds).unwrap();
+        let k = |r: &[u128]| r.iter().product::<u128>();
+        assert!(k(&bought) >= k(&reserves));
+        assert!(k(&sold) >= k(&reserves));
+        // The round trip never pays the trader more than they put in
+        assert!(proceeds <= cost);
+    }
+
+    #[test]
+    fn invariant_ratio_multiplies_per_outcome_ratios() {
+        assert_eq!(invariant_ratio(&[10, 10], &[10, 10]), Some(FixedU128::one()));
+        assert_eq!(
+            invariant_ratio(&[10, 20], &[20, 10]),
+            Some(FixedU128::one())
+        );
+        assert_eq!(
+            invariant_ratio(&[10, 10], &[20, 15]),
+            Some(FixedU128::saturating_from_rational(3u128, 1u128))
+        );
+        assert_eq!(invariant_ratio(&[10], &[10, 10]), None);
+        assert_eq!(invariant_ratio(&[0, 10], &[10, 10]), None);
+    }
+
+    #[test]
+    fn funding_leaves_prices_unchanged() {
+        let reserves = vec![1_000, 4_000];
+        let (after, returned) = add_funding(&reserves, 400).unwrap();
+        assert_eq!(after, vec![1_100, 4_400]);
+        assert_eq!(returned, vec![300, 0]);
+        assert_eq!(prices(&after), prices(&reserves));
+
+        // Taking the same slice back out restores the original pool
+        let (back, removed) = remove_funding(&after, 1, 11).unwrap();
+        assert_eq!(back, reserves);
+        assert_eq!(removed, vec![100, 400]);
+        assert_eq!(remove_funding(&after, 12, 11), None);
+    }
+}
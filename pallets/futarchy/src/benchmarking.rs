@@ -0,0 +1,1006 @@
+// Benchmarks for the futarchy pallet
+
+use super::*;
+use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite};
+use frame_support::traits::{OnInitialize, UnfilteredDispatchable};
+use frame_system::RawOrigin;
+use sp_runtime::traits::Bounded;
+
+const SEED: u32 = 0;
+// Blocks the benchmark markets stay open for trading
+const TRADING_PERIOD: u32 = 10;
+
+// Account holding enough balance for any benchmarked call
+fn funded_account<T: Config>(name: &'static str, index: u32) -> T::AccountId {
+    let who: T::AccountId = account(name, index, SEED);
+    T::Currency::make_free_balance_be(&who, BalanceOf::<T>::max_value() / 4u32.into());
+    who
+}
+
+// Oracle of every benchmark market, distinct from its creator and funded
+// for the resolution bond
+fn oracle<T: Config>() -> T::AccountId {
+    funded_account::<T>("oracle", 0)
+}
+
+// Seed liquidity used for every benchmark market
+fn seed_liquidity<T: Config>() -> BalanceOf<T> {
+    T::Currency::minimum_balance().saturating_mul(1_000u32.into())
+}
+
+// Create a categorical constant-product market with `outcomes` outcomes
+fn create_categorical<T: Config>(creator: &T::AccountId, outcomes: u32) -> T::Hash {
+    let trading_end = system::Module::<T>::block_number() + TRADING_PERIOD.into();
+    Module::<T>::create_market(
+        RawOrigin::Signed(creator.clone()).into(),
+        MarketType::Categorical,
+        MarketMechanism::ConstantProduct,
+        trading_end,
+        seed_liquidity::<T>(),
+        Zero::zero(),
+        None,
+        Some(outcomes),
+        sp_std::vec![0u8; T::MaxMetadataLen::get() as usize],
+        Some(oracle::<T>()),
+        None,
+        MarketCategory::Other,
+        Vec::new(),
+        Vec::new(),
+    )
+    .expect("market creation succeeds");
+    // Lift the creator cooldown so setups may create several markets
+    LastCreation::<T>::remove(creator);
+    *MarketsByCreator::<T>::get(creator).last().expect("market was indexed")
+}
+
+// Create a binary LMSR market, which other markets may be conditioned on
+fn create_binary<T: Config>(creator: &T::AccountId) -> T::Hash {
+    let trading_end = system::Module::<T>::block_number() + TRADING_PERIOD.into();
+    Module::<T>::create_market(
+        RawOrigin::Signed(creator.clone()).into(),
+        MarketType::Binary,
+        MarketMechanism::Lmsr,
+        trading_end,
+        seed_liquidity::<T>(),
+        Zero::zero(),
+        None,
+        None,
+        Vec::new(),
+        Some(oracle::<T>()),
+        None,
+        MarketCategory::Other,
+        Vec::new(),
+        Vec::new(),
+    )
+    .expect("market creation succeeds");
+    LastCreation::<T>::remove(creator);
+    *MarketsByCreator::<T>::get(creator).last().expect("market was indexed")
+}
+
+// Create and activate a categorical market, skipping `MinCreatedPeriod`
+fn create_active<T: Config>(creator: &T::AccountId, outcomes: u32) -> T::Hash {
+    let market_id = create_categorical::<T>(creator, outcomes);
+    Module::<T>::do_activate_market(creator, market_id)
+        .expect("activation succeeds");
+    market_id
+}
+
+// Buy a tenth of the seed in shares of `outcome`
+fn buy<T: Config>(who: &T::AccountId, market_id: T::Hash, outcome: u32) {
+    Module::<T>::buy_shares(
+        RawOrigin::Signed(who.clone()).into(),
+        market_id,
+        outcome,
+        seed_liquidity::<T>() / 10u32.into(),
+        None,
+    )
+    .expect("purchase succeeds");
+}
+
+// Move past the trading window of a market
+fn close_trading<T: Config>(market_id: T::Hash) {
+    let market = Markets::<T>::get(market_id).expect("market exists");
+    system::Module::<T>::set_block_number(market.trading_end);
+}
+
+// Create an active market with a traded position and report outcome 0
+fn create_proposed<T: Config>(creator: &T::AccountId, trader: &T::AccountId, outcomes: u32) -> T::Hash {
+    let market_id = create_active::<T>(creator, outcomes);
+    buy::<T>(trader, market_id, 0);
+    close_trading::<T>(market_id);
+    Module::<T>::resolve_market(
+        RawOrigin::Signed(oracle::<T>()).into(),
+        market_id,
+        MarketOutcome::Categorical(0),
+    )
+    .expect("report succeeds");
+    market_id
+}
+
+// Move past the dispute window of a reported market
+fn close_disputes<T: Config>() {
+    let now = system::Module::<T>::block_number();
+    system::Module::<T>::set_block_number(now + T::DisputePeriod::get());
+}
+
+benchmarks! {
+    create_market {
+        let o in 2 .. T::MaxOutcomes::get();
+        let caller = funded_account::<T>("caller", 0);
+        let trading_end = system::Module::<T>::block_number() + TRADING_PERIOD.into();
+        let metadata = sp_std::vec![0u8; T::MaxMetadataLen::get() as usize];
+    }: _(
+        RawOrigin::Signed(caller.clone()),
+        MarketType::Categorical,
+        MarketMechanism::ConstantProduct,
+        trading_end,
+        seed_liquidity::<T>(),
+        // Worst case: the seed is added as liquidity on top of the subsidy
+        seed_liquidity::<T>(),
+        None,
+        Some(o),
+        metadata,
+        Some(oracle::<T>()),
+        None,
+        MarketCategory::Other,
+        sp_std::vec![0u8; T::MaxDenomLen::get() as usize],
+        sp_std::vec![sp_std::vec![0u8; T::MaxLabelLen::get() as usize]; o as usize]
+    )
+    verify {
+        assert_eq!(MarketsByCreator::<T>::get(&caller).len(), 1);
+    }
+
+    activate_market {
+        let caller = funded_account::<T>("caller", 0);
+        let market_id = create_categorical::<T>(&caller, 2);
+        let now = system::Module::<T>::block_number();
+        system::Module::<T>::set_block_number(now + T::MinCreatedPeriod::get());
+    }: _(RawOrigin::Signed(caller), market_id)
+    verify {
+        assert!(ActiveMarkets::<T>::contains_key(market_id));
+    }
+
+    set_oracle {
+        let caller = funded_account::<T>("caller", 0);
+        let new_oracle: T::AccountId = account("oracle", 1, SEED);
+        let market_id = create_categorical::<T>(&caller, 2);
+    }: _(RawOrigin::Signed(caller), market_id, new_oracle.clone())
+    verify {
+        assert_eq!(Markets::<T>::get(market_id).unwrap().oracle, new_oracle);
+    }
+
+    cancel_market {
+        let caller = funded_account::<T>("caller", 0);
+        let market_id = create_active::<T>(&caller, T::MaxOutcomes::get());
+    }: _(RawOrigin::Signed(caller), market_id)
+    verify {
+        assert_eq!(Markets::<T>::get(market_id).unwrap().status, MarketStatus::Cancelled);
+    }
+
+    buy_shares {
+        let o in 2 .. T::MaxOutcomes::get();
+        let creator = funded_account::<T>("creator", 0);
+        let caller = funded_account::<T>("caller", 0);
+        let market_id = create_active::<T>(&creator, o);
+        let amount = seed_liquidity::<T>() / 10u32.into();
+    }: _(RawOrigin::Signed(caller.clone()), market_id, 0, amount, Some(BalanceOf::<T>::max_value()))
+    verify {
+        assert_eq!(Positions::<T>::get(market_id, &caller).balance_of(0), amount);
+    }
+
+    sell_shares {
+        let o in 2 .. T::MaxOutcomes::get();
+        let creator = funded_account::<T>("creator", 0);
+        let caller = funded_account::<T>("caller", 0);
+        let market_id = create_active::<T>(&creator, o);
+        buy::<T>(&caller, market_id, 0);
+        let amount = seed_liquidity::<T>() / 10u32.into();
+    }: _(RawOrigin::Signed(caller.clone()), market_id, 0, amount, Some(Zero::zero()))
+    verify {
+        assert!(Positions::<T>::get(market_id, &caller).is_empty());
+    }
+
+    add_liquidity {
+        let o in 2 .. T::MaxOutcomes::get();
+        let creator = funded_account::<T>("creator", 0);
+        let caller = funded_account::<T>("caller", 0);
+        let market_id = create_active::<T>(&creator, o);
+        // Skew the pool so funding hands back unbalanced shares
+        buy::<T>(&creator, market_id, 0);
+    }: _(RawOrigin::Signed(caller.clone()), market_id, seed_liquidity::<T>())
+    verify {
+        assert!(!LpShares::<T>::get(market_id, &caller).is_zero());
+    }
+
+    remove_liquidity {
+        let o in 2 .. T::MaxOutcomes::get();
+        let creator = funded_account::<T>("creator", 0);
+        let trader = funded_account::<T>("trader", 0);
+        let market_id = create_active::<T>(&creator, o);
+        // Skew the pool so the withdrawal hands back unbalanced shares
+        buy::<T>(&trader, market_id, 0);
+        let lp_amount = LpShares::<T>::get(market_id, &creator) / 2u32.into();
+    }: _(RawOrigin::Signed(creator.clone()), market_id, lp_amount)
+    verify {
+        assert!(!Positions::<T>::get(market_id, &creator).is_empty());
+    }
+
+    resolve_market {
+        let caller = funded_account::<T>("caller", 0);
+        let market_id = create_active::<T>(&caller, 2);
+        close_trading::<T>(market_id);
+    }: _(RawOrigin::Signed(oracle::<T>()), market_id, MarketOutcome::Categorical(0))
+    verify {
+        assert_eq!(Markets::<T>::get(market_id).unwrap().status, MarketStatus::Proposed);
+    }
+
+    dispute_market {
+        let creator = funded_account::<T>("creator", 0);
+        let caller = funded_account::<T>("caller", 0);
+        let market_id = create_proposed::<T>(&creator, &caller, 2);
+    }: _(RawOrigin::Signed(caller), market_id)
+    verify {
+        assert_eq!(Markets::<T>::get(market_id).unwrap().status, MarketStatus::Disputed);
+    }
+
+    resolve_dispute {
+        let creator = funded_account::<T>("creator", 0);
+        let disputer = funded_account::<T>("disputer", 0);
+        let market_id = create_proposed::<T>(&creator, &disputer, 2);
+        Module::<T>::dispute_market(RawOrigin::Signed(disputer).into(), market_id)?;
+        let origin = T::DisputeOrigin::successful_origin();
+        let call = Call::<T>::resolve_dispute(market_id, MarketOutcome::Categorical(1));
+    }: { call.dispatch_bypass_filter(origin)? }
+    verify {
+        assert_eq!(Markets::<T>::get(market_id).unwrap().status, MarketStatus::Resolved);
+    }
+
+    finalize_market {
+        let creator = funded_account::<T>("creator", 0);
+        let caller = funded_account::<T>("caller", 0);
+        let market_id = create_proposed::<T>(&creator, &caller, 2);
+        close_disputes::<T>();
+    }: _(RawOrigin::Signed(caller), market_id)
+    verify {
+        assert_eq!(Markets::<T>::get(market_id).unwrap().status, MarketStatus::Resolved);
+    }
+
+    claim_winnings {
+        let o in 2 .. T::MaxOutcomes::get();
+        let creator = funded_account::<T>("creator", 0);
+        let caller = funded_account::<T>("caller", 0);
+        let market_id = create_proposed::<T>(&creator, &caller, o);
+        // Worst case: the claim also finalizes the market
+        close_disputes::<T>();
+    }: _(RawOrigin::Signed(caller.clone()), market_id)
+    verify {
+        assert!(Positions::<T>::get(market_id, &caller).is_empty());
+    }
+
+    settle_position {
+        let o in 2 .. T::MaxOutcomes::get();
+        let creator = funded_account::<T>("creator", 0);
+        let caller = funded_account::<T>("caller", 0);
+        let market_id = create_active::<T>(&creator, o);
+        buy::<T>(&caller, market_id, 0);
+        close_trading::<T>(market_id);
+        Module::<T>::resolve_market(
+            RawOrigin::Signed(oracle::<T>()).into(),
+            market_id,
+            MarketOutcome::Categorical(1),
+        )
+        .expect("report succeeds");
+        // Worst case: settling also finalizes the market
+        close_disputes::<T>();
+    }: _(RawOrigin::Signed(caller.clone()), market_id)
+    verify {
+        assert!(Positions::<T>::get(market_id, &caller).is_empty());
+    }
+
+    create_decision_market {
+        let caller = funded_account::<T>("caller", 0);
+        let trading_end = system::Module::<T>::block_number() + TRADING_PERIOD.into();
+        let metadata = sp_std::vec![0u8; T::MaxMetadataLen::get() as usize];
+    }: _(
+        RawOrigin::Signed(caller.clone()),
+        metadata,
+        (0, 1_000),
+        MarketMechanism::ConstantProduct,
+        trading_end,
+        seed_liquidity::<T>(),
+        Some(oracle::<T>()),
+        Some(Box::new(frame_system::Call::<T>::remark(Vec::new()).into()))
+    )
+    verify {
+        assert_eq!(MarketsByCreator::<T>::get(&caller).len(), 2);
+    }
+
+    settle_decision {
+        let caller = funded_account::<T>("caller", 0);
+        let trading_end = system::Module::<T>::block_number() + TRADING_PERIOD.into();
+        let metadata = sp_std::vec![0u8; T::MaxMetadataLen::get() as usize];
+        Module::<T>::create_decision_market(
+            RawOrigin::Signed(caller.clone()).into(),
+            metadata,
+            (0, 1_000),
+            MarketMechanism::ConstantProduct,
+            trading_end,
+            seed_liquidity::<T>(),
+            Some(oracle::<T>()),
+            None
+        )?;
+        let decision_id = DecisionMarkets::<T>::iter_keys().next().expect("decision was stored");
+        system::Module::<T>::set_block_number(trading_end);
+    }: _(RawOrigin::Signed(caller), decision_id)
+    verify {
+        assert!(DecisionMarkets::<T>::get(decision_id).unwrap().recommendation.is_some());
+    }
+
+    execute_decision {
+        let caller = funded_account::<T>("caller", 0);
+        let trader = funded_account::<T>("trader", 0);
+        let trading_end = system::Module::<T>::block_number() + TRADING_PERIOD.into();
+        let metadata = sp_std::vec![0u8; T::MaxMetadataLen::get() as usize];
+        Module::<T>::create_decision_market(
+            RawOrigin::Signed(caller.clone()).into(),
+            metadata,
+            (0, 1_000),
+            MarketMechanism::ConstantProduct,
+            trading_end,
+            seed_liquidity::<T>(),
+            Some(oracle::<T>()),
+            Some(Box::new(frame_system::Call::<T>::remark(Vec::new()).into()))
+        )?;
+        let decision_id = DecisionMarkets::<T>::iter_keys().next().expect("decision was stored");
+        let enacted = DecisionMarkets::<T>::get(decision_id).unwrap().enacted_market;
+        buy::<T>(&trader, enacted, 1);
+        system::Module::<T>::set_block_number(trading_end);
+        Module::<T>::settle_decision(RawOrigin::Signed(caller.clone()).into(), decision_id)?;
+        close_disputes::<T>();
+    }: _(RawOrigin::Signed(caller), decision_id)
+    verify {
+        assert!(DecisionMarkets::<T>::get(decision_id).unwrap().bound_call.is_none());
+    }
+
+    create_markets_batch {
+        let n in 1 .. T::MaxBatchSize::get();
+        let caller = funded_account::<T>("caller", 0);
+        let trading_end = system::Module::<T>::block_number() + TRADING_PERIOD.into();
+        let spec = MarketSpec {
+            market_type: MarketType::Categorical,
+            mechanism: MarketMechanism::ConstantProduct,
+            trading_end,
+            liquidity_parameter: seed_liquidity::<T>(),
+            scalar_bounds: None,
+            outcome_count: Some(T::MaxOutcomes::get()),
+            metadata: sp_std::vec![0u8; T::MaxMetadataLen::get() as usize],
+            oracle: Some(oracle::<T>()),
+            collateral_asset: None,
+            category: MarketCategory::Other,
+            denom: Vec::new(),
+            outcome_labels: Vec::new(),
+        };
+        let specs: BoundedVec<_, T::MaxBatchSize> = sp_std::vec![spec; n as usize]
+            .try_into()
+            .expect("batch fits the bound");
+    }: _(RawOrigin::Signed(caller.clone()), specs)
+    verify {
+        assert_eq!(MarketsByCreator::<T>::get(&caller).len(), n as usize);
+    }
+
+    extend_trading {
+        let caller = funded_account::<T>("caller", 0);
+        let market_id = create_active::<T>(&caller, 2);
+        let new_trading_end = system::Module::<T>::block_number() + T::MaxExtension::get();
+    }: _(RawOrigin::Signed(caller), market_id, new_trading_end)
+    verify {
+        assert_eq!(Markets::<T>::get(market_id).unwrap().trading_end, new_trading_end);
+    }
+
+
+    expire_markets {
+        let n in 1 .. T::MaxAutoResolvePerBlock::get();
+        let trader = funded_account::<T>("trader", 0);
+        let mut market_ids = Vec::new();
+        for i in 0 .. n {
+            let creator = funded_account::<T>("creator", i);
+            let market_id = create_active::<T>(&creator, T::MaxOutcomes::get());
+            buy::<T>(&trader, market_id, 0);
+            market_ids.push(market_id);
+        }
+        let market = Markets::<T>::get(market_ids[0]).expect("market exists");
+        let expiry = market.trading_end + T::AutoResolveGrace::get();
+        system::Module::<T>::set_block_number(expiry);
+    }: {
+        Module::<T>::on_initialize(expiry);
+    }
+    verify {
+        for market_id in market_ids {
+            assert_eq!(Markets::<T>::get(market_id).unwrap().status, MarketStatus::Cancelled);
+        }
+    }
+
+    claim_refund {
+        let creator = funded_account::<T>("creator", 0);
+        let caller = funded_account::<T>("caller", 0);
+        let market_id = create_active::<T>(&creator, 2);
+        buy::<T>(&caller, market_id, 0);
+        let market = Markets::<T>::get(market_id).expect("market exists");
+        let expiry = market.trading_end + T::AutoResolveGrace::get();
+        system::Module::<T>::set_block_number(expiry);
+        Module::<T>::on_initialize(expiry);
+    }: _(RawOrigin::Signed(caller.clone()), market_id)
+    verify {
+        assert!(CostBasis::<T>::get(market_id, &caller).is_zero());
+    }
+
+
+    transfer_shares {
+        let creator = funded_account::<T>("creator", 0);
+        let caller = funded_account::<T>("caller", 0);
+        let recipient: T::AccountId = account("recipient", 0, SEED);
+        let market_id = create_active::<T>(&creator, T::MaxOutcomes::get());
+        buy::<T>(&caller, market_id, T::MaxOutcomes::get() - 1);
+        let amount = seed_liquidity::<T>() / 20u32.into();
+    }: _(RawOrigin::Signed(caller), market_id, recipient.clone(), T::MaxOutcomes::get() - 1, amount)
+    verify {
+        let index = (T::MaxOutcomes::get() - 1) as usize;
+        assert_eq!(Positions::<T>::get(market_id, &recipient).balance_of(index), amount);
+    }
+
+
+    close_market {
+        let caller = funded_account::<T>("caller", 0);
+        let market_id = create_active::<T>(&caller, 2);
+    }: _(RawOrigin::Signed(caller), market_id)
+    verify {
+        assert_eq!(Markets::<T>::get(market_id).unwrap().status, MarketStatus::Closed);
+    }
+
+
+    reap_market {
+        let creator = funded_account::<T>("creator", 0);
+        let caller = funded_account::<T>("caller", 0);
+        let market_id = create_active::<T>(&creator, T::MaxOutcomes::get());
+        Module::<T>::cancel_market(RawOrigin::Signed(creator).into(), market_id)?;
+        let market = Markets::<T>::get(market_id).expect("market exists");
+        system::Module::<T>::set_block_number(market.creation_block + T::MaxMarketLifetime::get());
+    }: _(RawOrigin::Signed(caller), market_id)
+    verify {
+        assert!(Markets::<T>::get(market_id).is_none());
+    }
+
+
+    set_position_cap {
+        let caller = funded_account::<T>("caller", 0);
+        let market_id = create_categorical::<T>(&caller, 2);
+        let cap = seed_liquidity::<T>();
+    }: _(RawOrigin::Signed(caller), market_id, Some(cap))
+    verify {
+        assert_eq!(Markets::<T>::get(market_id).unwrap().position_cap, Some(cap));
+    }
+
+
+    force_cancel_market {
+        let creator = funded_account::<T>("creator", 0);
+        let trader = funded_account::<T>("trader", 0);
+        let market_id = create_active::<T>(&creator, T::MaxOutcomes::get());
+        buy::<T>(&trader, market_id, 0);
+        let origin = T::ForceOrigin::successful_origin();
+        let call = Call::<T>::force_cancel_market(market_id);
+    }: { call.dispatch_bypass_filter(origin)? }
+    verify {
+        assert_eq!(Markets::<T>::get(market_id).unwrap().status, MarketStatus::Cancelled);
+    }
+
+
+    split_collateral {
+        let o in 2 .. T::MaxOutcomes::get();
+        let creator = funded_account::<T>("creator", 0);
+        let caller = funded_account::<T>("caller", 0);
+        let market_id = create_active::<T>(&creator, o);
+        let amount = seed_liquidity::<T>() / 10u32.into();
+    }: _(RawOrigin::Signed(caller.clone()), market_id, amount)
+    verify {
+        assert_eq!(Positions::<T>::get(market_id, &caller).balance_of((o - 1) as usize), amount);
+    }
+
+    merge_shares {
+        let o in 2 .. T::MaxOutcomes::get();
+        let creator = funded_account::<T>("creator", 0);
+        let caller = funded_account::<T>("caller", 0);
+        let market_id = create_active::<T>(&creator, o);
+        let amount = seed_liquidity::<T>() / 10u32.into();
+        Module::<T>::split_collateral(RawOrigin::Signed(caller.clone()).into(), market_id, amount)?;
+    }: _(RawOrigin::Signed(caller.clone()), market_id, amount)
+    verify {
+        assert!(Positions::<T>::get(market_id, &caller).is_empty());
+    }
+
+    set_oracle_committee {
+        let n in 1 .. T::MaxOracles::get();
+        let caller = funded_account::<T>("caller", 0);
+        let market_id = create_categorical::<T>(&caller, 2);
+        let oracles: BoundedVec<_, T::MaxOracles> = (0..n)
+            .map(|i| (account::<T::AccountId>("member", i, SEED), 1u32))
+            .collect::<Vec<_>>()
+            .try_into()
+            .expect("committee fits the bound");
+    }: _(RawOrigin::Signed(caller), market_id, oracles, n)
+    verify {
+        assert!(OracleCommittees::<T>::contains_key(market_id));
+    }
+
+    submit_resolution {
+        let n in 1 .. T::MaxOracles::get();
+        let caller = funded_account::<T>("caller", 0);
+        let market_id = create_categorical::<T>(&caller, 2);
+        let members: Vec<T::AccountId> = (0..n).map(|i| account("member", i, SEED)).collect();
+        let oracles: BoundedVec<_, T::MaxOracles> = members
+            .iter()
+            .map(|member| (member.clone(), 1u32))
+            .collect::<Vec<_>>()
+            .try_into()
+            .expect("committee fits the bound");
+        Module::<T>::set_oracle_committee(RawOrigin::Signed(caller.clone()).into(), market_id, oracles, n)
+            .expect("committee is valid");
+        Module::<T>::do_activate_market(&caller, market_id)
+            .expect("activation succeeds");
+        close_trading::<T>(market_id);
+        // Worst case: the last vote reaches the quorum
+        for member in &members[1..] {
+            Module::<T>::submit_resolution(
+                RawOrigin::Signed(member.clone()).into(),
+                market_id,
+                MarketOutcome::Categorical(0),
+            )
+            .expect("vote succeeds");
+        }
+    }: _(RawOrigin::Signed(members[0].clone()), market_id, MarketOutcome::Categorical(0))
+    verify {
+        assert_eq!(Markets::<T>::get(market_id).unwrap().status, MarketStatus::Resolved);
+    }
+
+    set_paused {
+        let origin = T::PauseOrigin::successful_origin();
+        let call = Call::<T>::set_paused(true);
+    }: { call.dispatch_bypass_filter(origin)? }
+    verify {
+        assert!(Paused::get());
+    }
+
+    clone_market {
+        let o in 2 .. T::MaxOutcomes::get();
+        let caller = funded_account::<T>("caller", 0);
+        let trader = funded_account::<T>("trader", 0);
+        let source_id = create_proposed::<T>(&caller, &trader, o);
+        close_disputes::<T>();
+        Module::<T>::finalize_market(RawOrigin::Signed(caller.clone()).into(), source_id)
+            .expect("finalization succeeds");
+        let trading_end = system::Module::<T>::block_number() + TRADING_PERIOD.into();
+    }: _(RawOrigin::Signed(caller.clone()), source_id, trading_end)
+    verify {
+        let market_id = *MarketsByCreator::<T>::get(&caller).last().expect("clone was indexed");
+        assert_eq!(Markets::<T>::get(market_id).unwrap().cloned_from, Some(source_id));
+    }
+
+    set_scalar_scale {
+        let caller = funded_account::<T>("caller", 0);
+        let trading_end = system::Module::<T>::block_number() + TRADING_PERIOD.into();
+        Module::<T>::create_market(
+            RawOrigin::Signed(caller.clone()).into(),
+            MarketType::Scalar,
+            MarketMechanism::Lmsr,
+            trading_end,
+            seed_liquidity::<T>(),
+            Zero::zero(),
+            // One whole unit fits an i128 at any allowed scale
+            Some((0, 1)),
+            None,
+            Vec::new(),
+            Some(oracle::<T>()),
+            None,
+            MarketCategory::Other,
+            Vec::new(),
+            Vec::new(),
+        )
+        .expect("market creation succeeds");
+        let market_id = *MarketsByCreator::<T>::get(&caller).last().expect("market was indexed");
+        let scale = T::MaxScalarScale::get();
+    }: _(RawOrigin::Signed(caller), market_id, scale)
+    verify {
+        assert_eq!(Markets::<T>::get(market_id).unwrap().scale, scale);
+    }
+
+    subsidize_market {
+        let caller = funded_account::<T>("caller", 0);
+        let trading_end = system::Module::<T>::block_number() + TRADING_PERIOD.into();
+        Module::<T>::create_market(
+            RawOrigin::Signed(caller.clone()).into(),
+            MarketType::Categorical,
+            MarketMechanism::Lmsr,
+            trading_end,
+            seed_liquidity::<T>(),
+            Zero::zero(),
+            None,
+            Some(T::MaxOutcomes::get()),
+            Vec::new(),
+            Some(oracle::<T>()),
+            None,
+            MarketCategory::Other,
+            Vec::new(),
+            Vec::new(),
+        )
+        .expect("market creation succeeds");
+        let market_id = *MarketsByCreator::<T>::get(&caller).last().expect("market was indexed");
+        T::Currency::make_free_balance_be(&T::TreasuryAccount::get(), BalanceOf::<T>::max_value() / 4u32.into());
+        let origin = T::SubsidyOrigin::successful_origin();
+        let call = Call::<T>::subsidize_market(market_id, seed_liquidity::<T>());
+    }: { call.dispatch_bypass_filter(origin)? }
+    verify {
+        assert_eq!(Subsidies::<T>::get(market_id), seed_liquidity::<T>());
+    }
+
+    add_approved_creator {
+        let creator: T::AccountId = account("creator", 0, SEED);
+        let origin = T::CreatorApprovalOrigin::successful_origin();
+        let call = Call::<T>::add_approved_creator(creator.clone());
+    }: { call.dispatch_bypass_filter(origin)? }
+    verify {
+        assert!(ApprovedCreators::<T>::contains_key(&creator));
+    }
+
+    remove_approved_creator {
+        let creator: T::AccountId = account("creator", 0, SEED);
+        ApprovedCreators::<T>::insert(&creator, ());
+        let origin = T::CreatorApprovalOrigin::successful_origin();
+        let call = Call::<T>::remove_approved_creator(creator.clone());
+    }: { call.dispatch_bypass_filter(origin)? }
+    verify {
+        assert!(!ApprovedCreators::<T>::contains_key(&creator));
+    }
+
+    create_and_activate_market {
+        let o in 2 .. T::MaxOutcomes::get();
+        let caller = funded_account::<T>("caller", 0);
+        let trading_end = system::Module::<T>::block_number() + TRADING_PERIOD.into();
+        let metadata = sp_std::vec![0u8; T::MaxMetadataLen::get() as usize];
+    }: _(
+        RawOrigin::Signed(caller.clone()),
+        MarketType::Categorical,
+        MarketMechanism::ConstantProduct,
+        trading_end,
+        seed_liquidity::<T>(),
+        seed_liquidity::<T>(),
+        None,
+        Some(o),
+        metadata,
+        Some(oracle::<T>()),
+        None,
+        MarketCategory::Other,
+        sp_std::vec![0u8; T::MaxDenomLen::get() as usize],
+        sp_std::vec![sp_std::vec![0u8; T::MaxLabelLen::get() as usize]; o as usize]
+    )
+    verify {
+        let market_id = *MarketsByCreator::<T>::get(&caller).last().expect("market was indexed");
+        assert!(ActiveMarkets::<T>::contains_key(market_id));
+    }
+
+    set_creator_fee {
+        let caller = funded_account::<T>("caller", 0);
+        let market_id = create_categorical::<T>(&caller, 2);
+        let fee = T::MaxCreatorFee::get();
+    }: _(RawOrigin::Signed(caller), market_id, fee)
+    verify {
+        assert_eq!(Markets::<T>::get(market_id).unwrap().creator_fee, fee);
+    }
+
+    claim_creator_fees {
+        let creator = funded_account::<T>("creator", 0);
+        let trader = funded_account::<T>("trader", 0);
+        let market_id = create_categorical::<T>(&creator, 2);
+        Module::<T>::set_creator_fee(
+            RawOrigin::Signed(creator.clone()).into(),
+            market_id,
+            T::MaxCreatorFee::get(),
+        )
+        .expect("fee is within the cap");
+        Module::<T>::do_activate_market(&creator, market_id)
+            .expect("activation succeeds");
+        buy::<T>(&trader, market_id, 0);
+        close_trading::<T>(market_id);
+        Module::<T>::resolve_market(
+            RawOrigin::Signed(oracle::<T>()).into(),
+            market_id,
+            MarketOutcome::Categorical(0),
+        )
+        .expect("report succeeds");
+        // Worst case: the claim also finalizes the market
+        close_disputes::<T>();
+    }: _(RawOrigin::Signed(creator), market_id)
+    verify {
+        assert!(CreatorFees::<T>::get(market_id).is_zero());
+    }
+
+    flag_stale_market {
+        let creator = funded_account::<T>("creator", 0);
+        let market_id = create_active::<T>(&creator, 2);
+        let market = Markets::<T>::get(market_id).expect("market exists");
+        let stale_at = market.trading_end + T::AutoResolveGrace::get() + One::one();
+        system::Module::<T>::set_block_number(stale_at);
+    }: _(RawOrigin::None, market_id)
+    verify {
+        assert!(StaleMarkets::<T>::contains_key(market_id));
+    }
+
+    eliminate_outcome {
+        let creator = funded_account::<T>("creator", 0);
+        let market_id = create_active::<T>(&creator, T::MaxOutcomes::get());
+    }: _(RawOrigin::Signed(oracle::<T>()), market_id, 0)
+    verify {
+        assert!(Module::<T>::is_eliminated(&Markets::<T>::get(market_id).unwrap(), 0));
+    }
+
+    set_collateral_cap {
+        let caller = funded_account::<T>("caller", 0);
+        let market_id = create_categorical::<T>(&caller, 2);
+        let cap = Some(seed_liquidity::<T>().saturating_mul(10u32.into()));
+    }: _(RawOrigin::Signed(caller), market_id, cap)
+    verify {
+        assert_eq!(Markets::<T>::get(market_id).unwrap().max_collateral, cap);
+    }
+
+    add_trading_delegate {
+        let caller = funded_account::<T>("caller", 0);
+        let delegate = funded_account::<T>("delegate", 0);
+    }: _(RawOrigin::Signed(caller.clone()), delegate.clone())
+    verify {
+        assert!(TradingDelegates::<T>::contains_key(&caller, &delegate));
+    }
+
+    remove_trading_delegate {
+        let caller = funded_account::<T>("caller", 0);
+        let delegate = funded_account::<T>("delegate", 0);
+        TradingDelegates::<T>::insert(&caller, &delegate, ());
+    }: _(RawOrigin::Signed(caller.clone()), delegate.clone())
+    verify {
+        assert!(!TradingDelegates::<T>::contains_key(&caller, &delegate));
+    }
+
+    buy_shares_for {
+        let o in 2 .. T::MaxOutcomes::get();
+        let creator = funded_account::<T>("creator", 0);
+        let owner = funded_account::<T>("owner", 0);
+        let caller = funded_account::<T>("caller", 0);
+        TradingDelegates::<T>::insert(&owner, &caller, ());
+        let market_id = create_active::<T>(&creator, o);
+        let amount = seed_liquidity::<T>() / 10u32.into();
+    }: _(RawOrigin::Signed(caller), owner.clone(), market_id, 0, amount, Some(BalanceOf::<T>::max_value()))
+    verify {
+        assert_eq!(Positions::<T>::get(market_id, &owner).balance_of(0), amount);
+    }
+
+    sell_shares_for {
+        let o in 2 .. T::MaxOutcomes::get();
+        let creator = funded_account::<T>("creator", 0);
+        let owner = funded_account::<T>("owner", 0);
+        let caller = funded_account::<T>("caller", 0);
+        TradingDelegates::<T>::insert(&owner, &caller, ());
+        let market_id = create_active::<T>(&creator, o);
+        buy::<T>(&owner, market_id, 0);
+        let amount = seed_liquidity::<T>() / 10u32.into();
+    }: _(RawOrigin::Signed(caller), owner.clone(), market_id, 0, amount, Some(Zero::zero()))
+    verify {
+        assert!(Positions::<T>::get(market_id, &owner).is_empty());
+    }
+
+    update_metadata {
+        let b in 0 .. T::MaxMetadataLen::get();
+        let caller = funded_account::<T>("caller", 0);
+        let market_id = create_categorical::<T>(&caller, 2);
+        let metadata = sp_std::vec![0u8; b as usize];
+    }: _(RawOrigin::Signed(caller), market_id, metadata.clone())
+    verify {
+        assert_eq!(Markets::<T>::get(market_id).unwrap().metadata.into_inner(), metadata);
+    }
+
+    set_payout_curve {
+        let caller = funded_account::<T>("caller", 0);
+        let trading_end = system::Module::<T>::block_number() + TRADING_PERIOD.into();
+        Module::<T>::create_market(
+            RawOrigin::Signed(caller.clone()).into(),
+            MarketType::Scalar,
+            MarketMechanism::Lmsr,
+            trading_end,
+            seed_liquidity::<T>(),
+            Zero::zero(),
+            Some((0, 1_000)),
+            None,
+            Vec::new(),
+            Some(oracle::<T>()),
+            None,
+            MarketCategory::Other,
+            Vec::new(),
+            Vec::new(),
+        )
+        .expect("market creation succeeds");
+        let market_id = *MarketsByCreator::<T>::get(&caller).last().expect("market was indexed");
+    }: _(RawOrigin::Signed(caller), market_id, ScalarCurve::Sigmoid)
+    verify {
+        assert_eq!(Markets::<T>::get(market_id).unwrap().payout_curve, ScalarCurve::Sigmoid);
+    }
+
+    set_parent_conditions {
+        let c in 1 .. T::MaxConditions::get();
+        let caller = funded_account::<T>("caller", 0);
+        let market_id = create_categorical::<T>(&caller, 2);
+        let trading_end = system::Module::<T>::block_number() + TRADING_PERIOD.into();
+        let conditions: BoundedVec<_, T::MaxConditions> = (0..c)
+            .map(|i| {
+                let creator = funded_account::<T>("parent", i);
+                Module::<T>::create_market(
+                    RawOrigin::Signed(creator.clone()).into(),
+                    MarketType::Binary,
+                    MarketMechanism::Lmsr,
+                    trading_end,
+                    seed_liquidity::<T>(),
+                    Zero::zero(),
+                    None,
+                    None,
+                    Vec::new(),
+                    Some(oracle::<T>()),
+                    None,
+                    MarketCategory::Other,
+                    Vec::new(),
+                    Vec::new(),
+                )
+                .expect("market creation succeeds");
+                (*MarketsByCreator::<T>::get(&creator).last().expect("market was indexed"), true)
+            })
+            .collect::<Vec<_>>()
+            .try_into()
+            .expect("conditions fit the bound");
+    }: _(RawOrigin::Signed(caller), market_id, conditions.clone())
+    verify {
+        assert_eq!(Markets::<T>::get(market_id).unwrap().parent_conditions, conditions);
+    }
+
+    set_backup_oracles {
+        let n in 1 .. T::MaxOracles::get();
+        let caller = funded_account::<T>("caller", 0);
+        let market_id = create_categorical::<T>(&caller, 2);
+        let oracles: BoundedVec<_, T::MaxOracles> = (0..n)
+            .map(|i| account::<T::AccountId>("backup", i, SEED))
+            .collect::<Vec<_>>()
+            .try_into()
+            .expect("backups fit the bound");
+    }: _(RawOrigin::Signed(caller), market_id, oracles.clone())
+    verify {
+        assert_eq!(BackupOracles::<T>::get(market_id), oracles);
+    }
+
+    redeem_all {
+        let n in 1 .. T::MaxRedeemBatch::get();
+        let caller = funded_account::<T>("caller", 0);
+        let market_ids: BoundedVec<_, T::MaxRedeemBatch> = (0..n)
+            .map(|i| create_proposed::<T>(&funded_account::<T>("creator", i), &caller, T::MaxOutcomes::get()))
+            .collect::<Vec<_>>()
+            .try_into()
+            .expect("markets fit the bound");
+        // Worst case: every claim also finalizes its market
+        close_disputes::<T>();
+    }: _(RawOrigin::Signed(caller.clone()), market_ids.clone())
+    verify {
+        for market_id in market_ids {
+            assert!(Positions::<T>::get(market_id, &caller).is_empty());
+        }
+    }
+
+    migrate_market {
+        let h in 0 .. 100;
+        let p in 1 .. 100;
+        let d in 0 .. 100;
+        let creator = funded_account::<T>("creator", 0);
+        let from_id = create_binary::<T>(&creator);
+        Module::<T>::do_activate_market(&creator, from_id).expect("activation succeeds");
+        for i in 0..h {
+            buy::<T>(&funded_account::<T>("trader", i), from_id, i % 2);
+        }
+        // The creator's seed is the first stake
+        for i in 1..p {
+            Module::<T>::add_liquidity(
+                RawOrigin::Signed(funded_account::<T>("provider", i)).into(),
+                from_id,
+                seed_liquidity::<T>(),
+            )
+            .expect("funding succeeds");
+        }
+        for i in 0..d {
+            let child_creator = funded_account::<T>("child", i);
+            let child_id = create_categorical::<T>(&child_creator, 2);
+            Module::<T>::set_parent_conditions(
+                RawOrigin::Signed(child_creator).into(),
+                child_id,
+                sp_std::vec![(from_id, true)].try_into().expect("one condition fits"),
+            )
+            .expect("condition is valid");
+        }
+        let market = Markets::<T>::get(from_id).unwrap();
+        let spec = MarketSpec {
+            market_type: MarketType::Binary,
+            mechanism: MarketMechanism::Lmsr,
+            trading_end: market.trading_end + TRADING_PERIOD.into(),
+            liquidity_parameter: market.liquidity_parameter,
+            scalar_bounds: None,
+            outcome_count: None,
+            metadata: Vec::new(),
+            oracle: None,
+            collateral_asset: None,
+            category: MarketCategory::Other,
+            denom: Vec::new(),
+            outcome_labels: Vec::new(),
+        };
+        let origin = T::ForceOrigin::successful_origin();
+        let call = Call::<T>::migrate_market(from_id, spec, h, p, d);
+    }: { call.dispatch_bypass_filter(origin)? }
+    verify {
+        assert_eq!(Markets::<T>::get(from_id).unwrap().status, MarketStatus::Cancelled);
+        let market_id = *MarketsByCreator::<T>::get(&creator).last().expect("replacement was indexed");
+        assert_eq!(Positions::<T>::iter_prefix(market_id).count() as u32, h);
+        assert_eq!(DependentMarkets::<T>::iter_prefix(market_id).count() as u32, d);
+    }
+
+    process_refunds {
+        let n in 1 .. T::MaxRefundsPerCall::get();
+        let creator = funded_account::<T>("creator", 0);
+        let market_id = create_active::<T>(&creator, 2);
+        // Cancelling pays the first page, leaving `n` holders to refund
+        for i in 0 .. T::MaxRefundsPerCall::get() + n {
+            buy::<T>(&funded_account::<T>("trader", i), market_id, 0);
+        }
+        let origin = T::ForceOrigin::successful_origin();
+        Call::<T>::force_cancel_market(market_id).dispatch_bypass_filter(origin)?;
+        let caller = funded_account::<T>("caller", 0);
+    }: _(RawOrigin::Signed(caller), market_id)
+    verify {
+        assert!(!PendingRefunds::<T>::contains_key(market_id));
+    }
+
+    set_featured {
+        let creator = funded_account::<T>("creator", 0);
+        let market_id = create_active::<T>(&creator, 2);
+        let origin = T::CurationOrigin::successful_origin();
+        let call = Call::<T>::set_featured(market_id, true);
+    }: { call.dispatch_bypass_filter(origin)? }
+    verify {
+        assert!(Featured::<T>::contains_key(market_id));
+    }
+
+    set_auto_activate {
+        let caller = funded_account::<T>("caller", 0);
+        let market_id = create_categorical::<T>(&caller, 2);
+    }: _(RawOrigin::Signed(caller), market_id, true)
+    verify {
+        assert!(Markets::<T>::get(market_id).unwrap().auto_activate);
+    }
+
+    close_and_resolve {
+        let caller = funded_account::<T>("caller", 0);
+        let market_id = create_active::<T>(&caller, 2);
+        close_trading::<T>(market_id);
+    }: _(RawOrigin::Signed(oracle::<T>()), market_id, MarketOutcome::Categorical(0))
+    verify {
+        assert_eq!(Markets::<T>::get(market_id).unwrap().status, MarketStatus::Proposed);
+    }
+
+    set_share_cap {
+        let caller = funded_account::<T>("caller", 0);
+        let market_id = create_categorical::<T>(&caller, 2);
+        let cap = Some(seed_liquidity::<T>().saturating_mul(10u32.into()));
+    }: _(RawOrigin::Signed(caller), market_id, cap)
+    verify {
+        assert_eq!(Markets::<T>::get(market_id).unwrap().max_shares, cap);
+    }
+}
+
+impl_benchmark_test_suite!(Module, crate::mock::new_test_ext(), crate::mock::Test);
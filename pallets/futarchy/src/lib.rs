@@ -2,59 +2,588 @@
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
+pub mod amm;
+pub mod lmsr;
+pub mod migrations;
+pub mod weights;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+pub use weights::WeightInfo;
+
 use frame_support::{
     decl_module, 
     decl_storage, 
     decl_event, 
     decl_error, 
     ensure,
-    dispatch::DispatchResult,
-    traits::{Get, Currency, ReservableCurrency}
+    transactional,
+    dispatch::{DispatchError, DispatchResult},
+    storage::{
+        with_transaction, StorageValue, StorageMap, StorageDoubleMap,
+        IterableStorageMap, IterableStorageDoubleMap
+    },
+    traits::{
+        fungibles, BalanceStatus, Get, Currency, ReservableCurrency, ExistenceRequirement, EnsureOrigin
+    },
+    weights::{GetDispatchInfo, Weight},
+    BoundedVec,
+    PalletId,
+    Parameter
 };
 use frame_system::{
     self as system, 
-    ensure_signed
+    ensure_none,
+    ensure_root,
+    ensure_signed,
+    offchain::{SendTransactionTypes, SubmitTransaction}
 };
 use sp_runtime::{
-    traits::{Hash, Zero, CheckedAdd, CheckedSub},
-    RuntimeDebug
+    traits::{Hash, Zero, One, Saturating, CheckedAdd, CheckedSub, AccountIdConversion, Dispatchable},
+    transaction_validity::{
+        InvalidTransaction, TransactionPriority, TransactionSource, TransactionValidity, ValidTransaction
+    },
+    TransactionOutcome,
+    RuntimeDebug,
+    FixedPointNumber,
+    FixedU128,
+    Permill,
+    SaturatedConversion
 };
 use sp_std::prelude::*;
+use codec::{Encode, Decode};
+#[cfg(feature = "std")]
+use serde::{Serialize, Deserialize};
+
+// Balance type of the configured currency
+pub type BalanceOf<T> = <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+// Market as stored for a given runtime
+pub type MarketOf<T> = PredictionMarket<
+    <T as frame_system::Config>::AccountId,
+    <T as Config>::AssetId,
+    BalanceOf<T>,
+    <T as frame_system::Config>::BlockNumber,
+    <T as frame_system::Config>::Hash,
+    <T as Config>::MaxMetadataLen,
+    <T as Config>::MaxConditions,
+    <T as Config>::MaxDenomLen,
+    <T as Config>::MaxLabelLen,
+    <T as Config>::MaxOutcomes,
+>;
+
+// Outcome labels of a market as stored for a given runtime
+pub type OutcomeLabelsOf<T> = BoundedVec<BoundedVec<u8, <T as Config>::MaxLabelLen>, <T as Config>::MaxOutcomes>;
+
+// Market spec as accepted by a given runtime
+pub type MarketSpecOf<T> = MarketSpec<
+    <T as frame_system::Config>::AccountId,
+    <T as Config>::AssetId,
+    BalanceOf<T>,
+    <T as frame_system::Config>::BlockNumber,
+>;
+
+// Decision market as stored for a given runtime
+pub type DecisionMarketOf<T> = DecisionMarket<
+    <T as frame_system::Config>::AccountId,
+    <T as frame_system::Config>::BlockNumber,
+    <T as frame_system::Config>::Hash,
+    <T as Config>::Call,
+    <T as Config>::MaxMetadataLen,
+>;
+
+// Storage layout versions, see `migrations`
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum Releases {
+    // Original prototype layout
+    V0,
+    // Markets with pricing, resolution and oracle data
+    V1
+}
+
+impl Default for Releases {
+    fn default() -> Self {
+        Releases::V0
+    }
+}
 
 // Market Types
 #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 pub enum MarketType {
     Binary,
     Scalar,
     Categorical
 }
 
+// Topic a market is listed under, for discovery
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum MarketCategory {
+    Sports,
+    Politics,
+    Crypto,
+    Other
+}
+
+impl Default for MarketCategory {
+    fn default() -> Self {
+        MarketCategory::Other
+    }
+}
+
+// How the reported value of a scalar market maps to the LONG payout, given
+// as a function of where the value falls between the bounds
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum ScalarCurve {
+    // Pays the position of the value in the range
+    Linear,
+    // Pays nothing in the bottom quarter of the range and in full in the top
+    // quarter, linearly in between
+    Clamped,
+    // Smoothstep: flat near the bounds and steepest mid-range
+    Sigmoid
+}
+
+impl Default for ScalarCurve {
+    fn default() -> Self {
+        ScalarCurve::Linear
+    }
+}
+
+impl ScalarCurve {
+    // LONG payout per share for a value `position` of the way through the
+    // range, from zero to one
+    pub fn long_payout(&self, position: FixedU128) -> FixedU128 {
+        let one = FixedU128::one();
+        let position = position.min(one);
+        match self {
+            ScalarCurve::Linear => position,
+            ScalarCurve::Clamped => position
+                .saturating_mul(FixedU128::saturating_from_integer(2))
+                .saturating_sub(FixedU128::saturating_from_rational(1, 2))
+                .min(one),
+            ScalarCurve::Sigmoid => {
+                // 3x^2 - 2x^3, which stays within [0, 1] on [0, 1]
+                let square = position.saturating_mul(position);
+                square
+                    .saturating_mul(FixedU128::saturating_from_integer(3))
+                    .saturating_sub(square.saturating_mul(position).saturating_mul(FixedU128::saturating_from_integer(2)))
+                    .min(one)
+            }
+        }
+    }
+}
+
+// Outcome a market resolves to
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub enum MarketOutcome {
+    // Winner of a binary market
+    Binary(bool),
+    // Reported value of a scalar market
+    Scalar(i128),
+    // Winning index of a categorical market
+    Categorical(u32),
+    // The question cannot be answered; traders are refunded instead
+    Invalid,
+    // Outcome indices tied in a committee vote, sharing the payout equally
+    Tie(Vec<u32>)
+}
+
+// Pricing mechanism backing a market
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum MarketMechanism {
+    // Logarithmic market scoring rule, see `lmsr`
+    Lmsr,
+    // Constant-product pool over outcome reserves, see `amm`
+    ConstantProduct
+}
+
 // Market Status
 #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
 pub enum MarketStatus {
     Created,
     Active,
+    // Outcome reported by the oracle, open to disputes
+    Proposed,
+    // Reported outcome challenged, awaiting a final ruling
+    Disputed,
     Resolved,
-    Cancelled
+    Cancelled,
+    // Trading halted ahead of the report, funds held pending resolution
+    Closed
+}
+
+// Chain-wide market figures, see `market_stats`
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct MarketStats<Balance> {
+    // Number of stored markets in each status
+    pub status_counts: Vec<(MarketStatus, u64)>,
+    // Sum of `total_liquidity` over every stored market
+    pub total_liquidity: Balance,
 }
 
 // Prediction Market Structure
 #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
-pub struct PredictionMarket<AccountId, Balance, BlockNumber> {
+pub struct PredictionMarket<
+    AccountId,
+    AssetId,
+    Balance,
+    BlockNumber,
+    Hash,
+    MaxMetadataLen: Get<u32>,
+    MaxConditions: Get<u32>,
+    MaxDenomLen: Get<u32>,
+    MaxLabelLen: Get<u32>,
+    MaxOutcomes: Get<u32>
+> {
     id: Hash,
     creator: AccountId,
+    // Account allowed to resolve the market
+    oracle: AccountId,
+    // Question being asked, e.g. an IPFS CID or a short description
+    metadata: BoundedVec<u8, MaxMetadataLen>,
     market_type: MarketType,
+    mechanism: MarketMechanism,
+    // Topic the market is listed under in `MarketsByCategory`
+    category: MarketCategory,
+    // Asset trades, liquidity and payouts are denominated in
+    collateral_asset: AssetId,
+    // Label front-ends render amounts of the collateral with, e.g. "DOT"
+    denom: BoundedVec<u8, MaxDenomLen>,
     status: MarketStatus,
     total_liquidity: Balance,
+    // LMSR liquidity parameter `b`, or the per-outcome seed reserve of a
+    // constant-product pool; larger values mean deeper markets
+    liquidity_parameter: Balance,
     creation_block: BlockNumber,
+    // Last block on which trading is allowed; resolution may happen from here on
+    trading_end: BlockNumber,
     resolution_block: Option<BlockNumber>,
+    // Outcome the market resolved to
+    resolved_outcome: Option<MarketOutcome>,
+    // Lower and upper bound of a scalar market's range
+    scalar_bounds: Option<(i128, i128)>,
+    // Implied decimal places of a scalar market's reported value; bounds stay
+    // in whole units, so a range of (0, 10) at scale 2 takes reports from
+    // 0.00 to 10.00 given as 0 to 1000
+    scale: u8,
+    // Response of a scalar market's LONG payout to the reported value
+    payout_curve: ScalarCurve,
+    // Number of tradable outcomes; two for binary and scalar markets
+    outcome_count: u32,
+    // Name of each outcome by index, e.g. "Alice"; empty if unlabelled
+    outcome_labels: BoundedVec<BoundedVec<u8, MaxLabelLen>, MaxOutcomes>,
+    // Whether the creation deposit has been returned or slashed
+    deposit_refunded: bool,
+    // Most shares of one outcome a single account may buy, overriding
+    // `MaxPositionPerAccount`
+    position_cap: Option<Balance>,
+    // Bond reserved from the oracle for the reported outcome, zero once
+    // released or slashed
+    resolution_bond: Balance,
+    // Bond reserved from the disputer, sized to the market when disputed and
+    // zero once released or slashed
+    dispute_bond: Balance,
+    // Market this one re-runs the question of, see `clone_market`
+    cloned_from: Option<Hash>,
+    // Binary markets that must resolve to the given side for this one to pay
+    // out; traders are refunded if any does not
+    parent_conditions: BoundedVec<(Hash, bool), MaxConditions>,
+    // Cut of trading volume accruing to the creator, see `CreatorFees`
+    creator_fee: Permill,
+    // Bitset of categorical outcomes the oracle ruled out ahead of resolution
+    eliminated: u32,
+    // Most collateral the pot may take in from trades and liquidity
+    max_collateral: Option<Balance>,
+    // Most shares of any one outcome that may be outstanding, bounding the
+    // collateral the market must hold to pay out
+    max_shares: Option<Balance>,
+    // Whether `add_liquidity` opens the market once it is deep enough
+    auto_activate: bool,
+}
+
+// Parameters of one market created by `create_markets_batch`
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct MarketSpec<AccountId, AssetId, Balance, BlockNumber> {
+    pub market_type: MarketType,
+    pub mechanism: MarketMechanism,
+    pub trading_end: BlockNumber,
+    pub liquidity_parameter: Balance,
+    pub scalar_bounds: Option<(i128, i128)>,
+    pub outcome_count: Option<u32>,
+    pub metadata: Vec<u8>,
+    pub oracle: Option<AccountId>,
+    pub collateral_asset: Option<AssetId>,
+    pub category: MarketCategory,
+    pub denom: Vec<u8>,
+    pub outcome_labels: Vec<Vec<u8>>,
+}
+
+// Futarchy decision: a pair of scalar markets pricing the same metric
+// conditional on a policy being enacted or not
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct DecisionMarket<AccountId, BlockNumber, Hash, Call, MaxMetadataLen: Get<u32>> {
+    creator: AccountId,
+    // Policy being decided on
+    policy_metadata: BoundedVec<u8, MaxMetadataLen>,
+    // Market on the metric if the policy is enacted
+    enacted_market: Hash,
+    // Market on the metric if the policy is not enacted
+    rejected_market: Hash,
+    // Whether enacting was recommended, once settled
+    recommendation: Option<bool>,
+    // Block the decision was settled on
+    settled_at: Option<BlockNumber>,
+    // Call dispatched when the policy is enacted; taken once executed
+    bound_call: Option<Box<Call>>,
+}
+
+// Outcome shares held by a single account in a single market.
+//
+// `shares[i]` is the balance of outcome `i`. Binary markets use index 0 for
+// NO and index 1 for YES; scalar markets use index 0 for SHORT and index 1
+// for LONG. SCALE-encoded as a compact length prefix followed by
+// one balance per outcome; an account with no position decodes to the empty
+// default.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Default, RuntimeDebug)]
+pub struct OutcomeShares<Balance> {
+    shares: Vec<Balance>,
+}
+
+impl<Balance: Copy + Zero> OutcomeShares<Balance> {
+    // Balance held of a given outcome
+    pub fn balance_of(&self, outcome: usize) -> Balance {
+        self.shares.get(outcome).copied().unwrap_or_else(Zero::zero)
+    }
+
+    // Whether the account holds no shares of any outcome
+    pub fn is_empty(&self) -> bool {
+        self.shares.iter().all(|s| s.is_zero())
+    }
+
+    // LONG balance of a scalar position
+    pub fn long_balance(&self) -> Balance {
+        self.balance_of(scalar_outcome_index(true))
+    }
+
+    // SHORT balance of a scalar position
+    pub fn short_balance(&self) -> Balance {
+        self.balance_of(scalar_outcome_index(false))
+    }
+}
+
+impl<Balance: Copy + Zero + Saturating + CheckedSub> OutcomeShares<Balance> {
+    // Shares held across every outcome
+    pub fn total(&self) -> Balance {
+        self.shares.iter().fold(Zero::zero(), |total, s| total.saturating_add(*s))
+    }
+
+    // Credit `amount` shares of an outcome, growing the vector as needed
+    pub fn mint(&mut self, outcome: usize, amount: Balance) {
+        if self.shares.len() <= outcome {
+            self.shares.resize(outcome + 1, Zero::zero());
+        }
+        self.shares[outcome] = self.shares[outcome].saturating_add(amount);
+    }
+
+    // Debit `amount` shares of an outcome; `None` if the balance is too low
+    pub fn burn(&mut self, outcome: usize, amount: Balance) -> Option<()> {
+        if amount.is_zero() {
+            return Some(());
+        }
+        let balance = self.shares.get_mut(outcome)?;
+        *balance = balance.checked_sub(&amount)?;
+        Some(())
+    }
+}
+
+// Index of a binary outcome within `OutcomeShares`
+pub fn binary_outcome_index(outcome: bool) -> usize {
+    if outcome { 1 } else { 0 }
+}
+
+// Index of a scalar side within `OutcomeShares`, LONG or SHORT
+pub fn scalar_outcome_index(long: bool) -> usize {
+    if long { 1 } else { 0 }
+}
+
+// How a committee vote tied between outcomes is settled
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum TieBreak {
+    // The lowest tied outcome wins (NO before YES)
+    LowestIndex,
+    // The market resolves invalid and traders get their cost basis back
+    Refund,
+    // Every tied outcome pays an equal share, see `MarketOutcome::Tie`
+    SplitEqually
+}
+
+// Where the collateral lost to rounding payouts down goes
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum PayoutRounding {
+    // Swept to the treasury by the last winning claim
+    DustToTreasury,
+    // Added to the last winning claim
+    DustToLastClaimant
+}
+
+// Who may create markets
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum CreationPolicy {
+    // Any signed account
+    Permissionless,
+    // Accounts in `ApprovedCreators` only
+    WhitelistOnly,
+    // Root only, creating markets on behalf of the treasury account
+    RootOnly
+}
+
+// Notified once a market's outcome is final, so other pallets can react
+// to it, e.g. by rewarding accurate forecasters
+pub trait OnMarketResolved<Hash, Outcome> {
+    fn on_market_resolved(market_id: Hash, outcome: &Outcome);
+}
+
+impl<Hash, Outcome> OnMarketResolved<Hash, Outcome> for () {
+    fn on_market_resolved(_: Hash, _: &Outcome) {}
 }
 
 // Pallet Configuration Trait
-pub trait Config: frame_system::Config {
+pub trait Config: frame_system::Config + SendTransactionTypes<Call<Self>> {
     type Event: From<Event<Self>> + Into<<Self as frame_system::Config>::Event>;
     type Currency: ReservableCurrency<Self::AccountId>;
+    // Identifier of a collateral asset
+    type AssetId: Parameter + Copy + Default;
+    // Non-native collateral, e.g. `pallet-assets`; bonds stay in `Currency`
+    type Assets: fungibles::Transfer<Self::AccountId, AssetId = Self::AssetId, Balance = BalanceOf<Self>>;
+    // Asset id standing for `Currency` itself
+    type NativeAssetId: Get<Self::AssetId>;
     type MarketCreationDeposit: Get<BalanceOf<Self>>;
+    // Used to derive the pot account holding each market's collateral
+    type PalletId: Get<PalletId>;
+    // Maximum number of outcomes of a categorical market
+    type MaxOutcomes: Get<u32>;
+    // Most implied decimal places a scalar market may use
+    type MaxScalarScale: Get<u8>;
+    // Widest `upper - lower` a scalar market may span, keeping the payout
+    // of a complete set within what the fixed-point payout math can take
+    type MaxScalarRange: Get<u128>;
+    // Maximum length in bytes of a market's metadata
+    type MaxMetadataLen: Get<u32>;
+    // Maximum length in bytes of a market's collateral label
+    type MaxDenomLen: Get<u32>;
+    // Maximum length in bytes of the label of one outcome
+    type MaxLabelLen: Get<u32>;
+    // Blocks after a reported outcome during which it can be disputed
+    type DisputePeriod: Get<Self::BlockNumber>;
+    // Blocks a resolved market keeps its pot locked for so winners can
+    // claim before liquidity providers withdraw
+    type LpWithdrawalDelay: Get<Self::BlockNumber>;
+    // Smallest deposit reserved from an account disputing a reported outcome
+    type MinDisputeBond: Get<BalanceOf<Self>>;
+    // Share of a market's pot reserved from a disputer when that is more
+    // than `MinDisputeBond`
+    type DisputeBondPercent: Get<Permill>;
+    // Bond an oracle reserves when reporting, lost if a dispute overturns it
+    type ResolutionBond: Get<BalanceOf<Self>>;
+    // Maximum number of oracles on a market's resolution committee
+    type MaxOracles: Get<u32>;
+    // Most other markets a combinatorial market may be conditioned on
+    type MaxConditions: Get<u32>;
+    // Most markets `redeem_all` claims from in one call
+    type MaxRedeemBatch: Get<u32>;
+    // Number of traders kept on the realized profit leaderboard
+    type MaxLeaderboard: Get<u32>;
+    // Origin ruling on disputed outcomes, e.g. root or a council
+    type DisputeOrigin: EnsureOrigin<Self::Origin>;
+    // Origin allowed to cancel fraudulent or illegal markets
+    type ForceOrigin: EnsureOrigin<Self::Origin>;
+    // Origin allowed to pause trading in an emergency
+    type PauseOrigin: EnsureOrigin<Self::Origin>;
+    // Origin allowed to subsidize markets out of the treasury
+    type SubsidyOrigin: EnsureOrigin<Self::Origin>;
+    // Origin choosing which markets front-ends promote, see `Featured`
+    type CurationOrigin: EnsureOrigin<Self::Origin>;
+    // Who may create markets
+    type MarketCreationPolicy: Get<CreationPolicy>;
+    // Origin managing `ApprovedCreators`
+    type CreatorApprovalOrigin: EnsureOrigin<Self::Origin>;
+    // Fee charged on the collateral of every trade, paid to liquidity providers
+    type TradingFee: Get<Permill>;
+    // Fee charged on the collateral of every trade, paid to the treasury
+    type ProtocolFee: Get<Permill>;
+    // Highest cut of trading volume a creator may set for their market
+    type MaxCreatorFee: Get<Permill>;
+    // Account receiving protocol fees
+    type TreasuryAccount: Get<Self::AccountId>;
+    // Who receives the rounding dust of a market's payouts
+    type PayoutRounding: Get<PayoutRounding>;
+    // Settles committee votes tied between outcomes
+    type TieBreak: Get<TieBreak>;
+    // Called whenever a market's outcome becomes final
+    type OnMarketResolved: OnMarketResolved<Self::Hash, MarketOutcome>;
+    // Maximum number of live markets indexed per creator
+    type MaxMarketsPerCreator: Get<u32>;
+    // Maximum number of live markets indexed per category
+    type MaxPerCategory: Get<u32>;
+    // Whether a creator may act as the oracle of their own market
+    type AllowCreatorResolution: Get<bool>;
+    // Liquidity a market must hold before it can open for trading
+    type MinActivationLiquidity: Get<BalanceOf<Self>>;
+    // Blocks a market sits in `Created` before `activate_market` may open
+    // it. Markets created and opened in one call skip the wait.
+    type MinCreatedPeriod: Get<Self::BlockNumber>;
+    // Blocks an account must wait between creation calls; a batch or
+    // decision market counts as one
+    type CreatorCooldown: Get<Self::BlockNumber>;
+    // Maximum number of markets created by one `create_markets_batch` call
+    type MaxBatchSize: Get<u32>;
+    // Furthest ahead of the current block a trading window may be extended
+    type MaxExtension: Get<Self::BlockNumber>;
+    // Longest a market may stay open for trading, counted from its creation;
+    // settled markets may be reaped once this much time has passed
+    type MaxMarketLifetime: Get<Self::BlockNumber>;
+    // Native amount a creator leaves in the market pot for whoever reaps it
+    type ReapIncentive: Get<BalanceOf<Self>>;
+    // Most shares of one outcome a single account may buy into, unless the
+    // market sets its own cap
+    type MaxPositionPerAccount: Get<BalanceOf<Self>>;
+    // Fewest shares a single buy or sell may trade
+    type MinTradeAmount: Get<BalanceOf<Self>>;
+    // Number of price points kept per market
+    type MaxHistoryPoints: Get<u32>;
+    // Smallest price move worth a new history point
+    type PriceHistoryEpsilon: Get<FixedU128>;
+    // Blocks after the end of trading an unreported market is cancelled
+    type AutoResolveGrace: Get<Self::BlockNumber>;
+    // Blocks each oracle in line gets to report before the next backup
+    // oracle takes over, see `BackupOracles`
+    type OracleSlaWindow: Get<Self::BlockNumber>;
+    // Maximum number of markets expired in a single block
+    type MaxAutoResolvePerBlock: Get<u32>;
+    // Most holders of a cancelled market refunded by one call
+    type MaxRefundsPerCall: Get<u32>;
+    // Runtime call a decision market can enact
+    type Call: Parameter + Dispatchable<Origin = Self::Origin> + GetDispatchInfo + From<frame_system::Call<Self>>;
+    // Origin enacted decisions dispatch their bound call with, e.g. root
+    type ExecutionOrigin: Get<Self::Origin>;
+    // Maximum weight of a call bound to a decision market
+    type MaxExecutionWeight: Get<Weight>;
+    // Priority of the unsigned transactions the off-chain worker submits
+    type UnsignedPriority: Get<TransactionPriority>;
+    // Most markets open for trading at once, bounding the per-block scans
+    // over active markets
+    type MaxActiveMarkets: Get<u32>;
+    // Most collateral all markets together may take in within one block
+    type MaxCollateralInflowPerBlock: Get<BalanceOf<Self>>;
+    // Weight information for extrinsics in this pallet
+    type WeightInfo: WeightInfo;
 }
 
 // Pallet Declaration
@@ -63,108 +592,4733 @@ decl_module! {
         type Error = Error<T>;
         fn deposit_event() = default;
 
+        fn on_runtime_upgrade() -> Weight {
+            migrations::migrate::<T>()
+        }
+
+        // Cancel markets whose oracle let the grace period pass without a
+        // report, and reopen the per-block inflow budget
+        fn on_initialize(now: T::BlockNumber) -> Weight {
+            BlockInflow::<T>::kill();
+            let expiring = ExpiringMarkets::<T>::take(now);
+            let weight = T::WeightInfo::expire_markets(expiring.len() as u32)
+                .saturating_add(T::DbWeight::get().writes(1));
+
+            for market_id in expiring {
+                let mut market = match Markets::<T>::get(market_id) {
+                    Some(market) => market,
+                    None => continue,
+                };
+                // Entries left behind by resolved or extended markets are skipped
+                let unreported = matches!(
+                    market.status,
+                    MarketStatus::Created | MarketStatus::Active | MarketStatus::Closed
+                );
+                if !unreported || now < market.trading_end.saturating_add(T::AutoResolveGrace::get()) {
+                    continue;
+                }
+                with_transaction(|| match Self::expire(market_id, &mut market) {
+                    Ok(()) => {
+                        Self::put_market(market_id, market);
+                        TransactionOutcome::Commit(())
+                    }
+                    Err(_) => TransactionOutcome::Rollback(()),
+                });
+            }
+
+            weight
+        }
+
+        // Report markets left unresolved past their grace period, which the
+        // block hook should already have expired
+        fn offchain_worker(now: T::BlockNumber) {
+            for market_id in Self::stale_market_ids(now) {
+                let call = Call::flag_stale_market(market_id);
+                // A rejected submission is retried by the next block's worker
+                let _ = SubmitTransaction::<T, Call<T>>::submit_unsigned_transaction(call.into());
+            }
+        }
+
+        fn integrity_test() {
+            assert!(T::MaxAutoResolvePerBlock::get() > 0, "at least one market must expire per block");
+            assert!(T::MaxScalarScale::get() <= 38, "one whole scalar unit must fit in an i128");
+            assert!(T::MaxOutcomes::get() <= 32, "eliminated outcomes must fit in a u32 bitset");
+
+            // LP and protocol fees are both taken from the same trade
+            assert!(
+                T::TradingFee::get().deconstruct()
+                    .saturating_add(T::ProtocolFee::get().deconstruct())
+                    <= Permill::one().deconstruct(),
+                "trading and protocol fees together must not exceed 100%"
+            );
+        }
+
+        // Flag a market as stale for operators to follow up on; submitted
+        // unsigned by the off-chain worker
+        #[weight = T::WeightInfo::flag_stale_market()]
+        pub fn flag_stale_market(
+            origin,
+            market_id: T::Hash
+        ) -> DispatchResult {
+            ensure_none(origin)?;
+
+            let market = Markets::<T>::get(market_id)
+                .ok_or(Error::<T>::MarketDoesNotExist)?;
+            let now = system::Module::<T>::block_number();
+            ensure!(Self::is_stale(&market, now), Error::<T>::MarketNotStale);
+            ensure!(!StaleMarkets::<T>::contains_key(market_id), Error::<T>::MarketNotStale);
+
+            StaleMarkets::<T>::insert(market_id, ());
+
+            // Emit event
+            Self::deposit_event(RawEvent::MarketStale(market_id));
+
+            Ok(())
+        }
+
         // Create a new prediction market
-        #[weight = 10_000]
+        #[weight = T::WeightInfo::create_market(outcome_count.unwrap_or(2))]
+        #[transactional]
         pub fn create_market(
             origin, 
-            market_type: MarketType
+            market_type: MarketType,
+            mechanism: MarketMechanism,
+            trading_end: T::BlockNumber,
+            liquidity_parameter: BalanceOf<T>,
+            initial_liquidity: BalanceOf<T>,
+            scalar_bounds: Option<(i128, i128)>,
+            outcome_count: Option<u32>,
+            metadata: Vec<u8>,
+            oracle: Option<T::AccountId>,
+            collateral_asset: Option<T::AssetId>,
+            category: MarketCategory,
+            denom: Vec<u8>,
+            outcome_labels: Vec<Vec<u8>>
         ) -> DispatchResult {
-            let who = ensure_signed(origin)?;
-            
-            // Ensure minimum deposit is paid
-            let deposit = T::MarketCreationDeposit::get();
-            T::Currency::reserve(&who, deposit)?;
+            let who = Self::ensure_creator(origin)?;
+            Self::note_creation(&who)?;
+
+            let metadata: BoundedVec<u8, T::MaxMetadataLen> = metadata
+                .try_into()
+                .map_err(|_| Error::<T>::MetadataTooLong)?;
+            let denom: BoundedVec<u8, T::MaxDenomLen> = denom
+                .try_into()
+                .map_err(|_| Error::<T>::DenomTooLong)?;
+            let outcome_labels = Self::bound_outcome_labels(outcome_labels)?;
 
             // Generate unique market ID
-            let market_id = (system::Module::<T>::block_number(), who.clone(), market_type.clone()).using_encoded(T::Hashing::hash);
+            let market_id = Self::next_market_id(&who);
 
-            // Create market
-            let market = PredictionMarket {
-                id: market_id,
-                creator: who.clone(),
+            Self::do_create_market(
+                &who,
+                market_id,
+                market_type,
+                mechanism,
+                trading_end,
+                liquidity_parameter,
+                initial_liquidity,
+                scalar_bounds,
+                outcome_count,
+                metadata,
+                oracle,
+                collateral_asset.unwrap_or_else(T::NativeAssetId::get),
+                category,
+                denom,
+                outcome_labels
+            )
+        }
+
+        // Create a market, seed it with `initial_liquidity` and open it for
+        // trading in one go; nothing is created unless it can open
+        #[weight = T::WeightInfo::create_and_activate_market(outcome_count.unwrap_or(2))]
+        #[transactional]
+        pub fn create_and_activate_market(
+            origin,
+            market_type: MarketType,
+            mechanism: MarketMechanism,
+            trading_end: T::BlockNumber,
+            liquidity_parameter: BalanceOf<T>,
+            initial_liquidity: BalanceOf<T>,
+            scalar_bounds: Option<(i128, i128)>,
+            outcome_count: Option<u32>,
+            metadata: Vec<u8>,
+            oracle: Option<T::AccountId>,
+            collateral_asset: Option<T::AssetId>,
+            category: MarketCategory,
+            denom: Vec<u8>,
+            outcome_labels: Vec<Vec<u8>>
+        ) -> DispatchResult {
+            let who = Self::ensure_creator(origin)?;
+            Self::note_creation(&who)?;
+
+            let metadata: BoundedVec<u8, T::MaxMetadataLen> = metadata
+                .try_into()
+                .map_err(|_| Error::<T>::MetadataTooLong)?;
+            let denom: BoundedVec<u8, T::MaxDenomLen> = denom
+                .try_into()
+                .map_err(|_| Error::<T>::DenomTooLong)?;
+            let outcome_labels = Self::bound_outcome_labels(outcome_labels)?;
+
+            // Generate unique market ID
+            let market_id = Self::next_market_id(&who);
+
+            Self::do_create_market(
+                &who,
+                market_id,
                 market_type,
-                status: MarketStatus::Created,
-                total_liquidity: Zero::zero(),
-                creation_block: system::Module::<T>::block_number(),
-                resolution_block: None,
+                mechanism,
+                trading_end,
+                liquidity_parameter,
+                initial_liquidity,
+                scalar_bounds,
+                outcome_count,
+                metadata,
+                oracle,
+                collateral_asset.unwrap_or_else(T::NativeAssetId::get),
+                category,
+                denom,
+                outcome_labels
+            )?;
+            Self::do_activate_market(&who, market_id)
+        }
+
+        // Create several markets at once; either all of them are created or none
+        #[weight = T::WeightInfo::create_markets_batch(specs.len() as u32)]
+        #[transactional]
+        pub fn create_markets_batch(
+            origin,
+            specs: BoundedVec<MarketSpecOf<T>, T::MaxBatchSize>
+        ) -> DispatchResult {
+            let who = Self::ensure_creator(origin)?;
+            Self::note_creation(&who)?;
+
+            // Every creation deposit must be reservable before anything is created
+            let deposits = T::MarketCreationDeposit::get().saturating_mul((specs.len() as u32).into());
+            ensure!(T::Currency::can_reserve(&who, deposits), Error::<T>::InsufficientFunds);
+
+            let mut market_ids = Vec::with_capacity(specs.len());
+            for spec in specs.into_inner() {
+                let metadata: BoundedVec<u8, T::MaxMetadataLen> = spec.metadata
+                    .try_into()
+                    .map_err(|_| Error::<T>::MetadataTooLong)?;
+                let denom: BoundedVec<u8, T::MaxDenomLen> = spec.denom
+                    .try_into()
+                    .map_err(|_| Error::<T>::DenomTooLong)?;
+                let outcome_labels = Self::bound_outcome_labels(spec.outcome_labels)?;
+
+                let market_id = Self::next_market_id(&who);
+
+                Self::do_create_market(
+                    &who,
+                    market_id,
+                    spec.market_type,
+                    spec.mechanism,
+                    spec.trading_end,
+                    spec.liquidity_parameter,
+                    Zero::zero(),
+                    spec.scalar_bounds,
+                    spec.outcome_count,
+                    metadata,
+                    spec.oracle,
+                    spec.collateral_asset.unwrap_or_else(T::NativeAssetId::get),
+                    spec.category,
+                    denom,
+                    outcome_labels
+                )?;
+                market_ids.push(market_id);
+            }
+
+            // Emit event
+            Self::deposit_event(RawEvent::MarketsBatchCreated(who, market_ids));
+
+            Ok(())
+        }
+
+        // Re-run the question of a resolved market for a new trading period:
+        // a fresh market with the same parameters, funded and bonded anew
+        #[weight = T::WeightInfo::clone_market(T::MaxOutcomes::get())]
+        #[transactional]
+        pub fn clone_market(
+            origin,
+            source_id: T::Hash,
+            new_trading_end: T::BlockNumber
+        ) -> DispatchResult {
+            let who = Self::ensure_creator(origin)?;
+            Self::note_creation(&who)?;
+
+            // Only the creator of a resolved market may re-run it
+            let source = Markets::<T>::get(source_id)
+                .ok_or(Error::<T>::MarketDoesNotExist)?;
+            ensure!(source.creator == who, Error::<T>::NotMarketCreator);
+            ensure!(source.status == MarketStatus::Resolved, Error::<T>::MarketNotResolved);
+
+            let market_id = Self::next_market_id(&who);
+            let outcome_count = match source.market_type {
+                MarketType::Categorical => Some(source.outcome_count),
+                _ => None,
             };
 
-            // Store market
-            Markets::<T>::insert(market_id, market);
+            Self::do_create_market(
+                &who,
+                market_id,
+                source.market_type,
+                source.mechanism,
+                new_trading_end,
+                source.liquidity_parameter,
+                Zero::zero(),
+                source.scalar_bounds,
+                outcome_count,
+                source.metadata,
+                Some(source.oracle),
+                source.collateral_asset,
+                source.category,
+                source.denom,
+                source.outcome_labels
+            )?;
+            Markets::<T>::mutate(market_id, |market| {
+                if let Some(market) = market {
+                    market.cloned_from = Some(source_id);
+                    market.scale = source.scale;
+                    market.payout_curve = source.payout_curve;
+                    market.creator_fee = source.creator_fee.min(T::MaxCreatorFee::get());
+                    market.auto_activate = source.auto_activate;
+                }
+            });
 
             // Emit event
-            Self::deposit_event(RawEvent::MarketCreated(who, market_id));
+            Self::deposit_event(RawEvent::MarketCloned(who, source_id, market_id));
 
             Ok(())
         }
 
-        // Resolve a prediction market
-        #[weight = 10_000]
-        pub fn resolve_market(
-            origin, 
+        // Create a decision market: two scalar markets on a metric within
+        // `metric_bounds`, one conditional on the policy being enacted and one
+        // on it being rejected. Both open for trading straight away.
+        #[weight = T::WeightInfo::create_decision_market()]
+        #[transactional]
+        pub fn create_decision_market(
+            origin,
+            policy_metadata: Vec<u8>,
+            metric_bounds: (i128, i128),
+            mechanism: MarketMechanism,
+            trading_end: T::BlockNumber,
+            liquidity_parameter: BalanceOf<T>,
+            oracle: Option<T::AccountId>,
+            bound_call: Option<Box<<T as Config>::Call>>
+        ) -> DispatchResult {
+            let who = Self::ensure_creator(origin)?;
+            Self::note_creation(&who)?;
+
+            // Enactment must fit in a block alongside the execution itself
+            if let Some(call) = bound_call.as_ref() {
+                ensure!(
+                    call.get_dispatch_info().weight <= T::MaxExecutionWeight::get(),
+                    Error::<T>::CallTooHeavy
+                );
+            }
+
+            let policy_metadata: BoundedVec<u8, T::MaxMetadataLen> = policy_metadata
+                .try_into()
+                .map_err(|_| Error::<T>::MetadataTooLong)?;
+            // Ids come from the creator's nonce like any other market, so the
+            // same policy can be proposed twice in one block
+            let decision_id = Self::next_market_id(&who);
+
+            // Spawn and open the conditional pair
+            let mut branches = [T::Hash::default(); 2];
+            for branch in branches.iter_mut() {
+                let market_id = Self::next_market_id(&who);
+                Self::do_create_market(
+                    &who,
+                    market_id,
+                    MarketType::Scalar,
+                    mechanism.clone(),
+                    trading_end,
+                    liquidity_parameter,
+                    Zero::zero(),
+                    Some(metric_bounds),
+                    None,
+                    policy_metadata.clone(),
+                    oracle.clone(),
+                    T::NativeAssetId::get(),
+                    MarketCategory::Other,
+                    BoundedVec::default(),
+                    BoundedVec::default()
+                )?;
+                Self::do_activate_market(&who, market_id)?;
+                *branch = market_id;
+            }
+            let [enacted_market, rejected_market] = branches;
+
+            // Store decision
+            DecisionMarkets::<T>::insert(decision_id, DecisionMarket {
+                creator: who.clone(),
+                policy_metadata,
+                enacted_market,
+                rejected_market,
+                recommendation: None,
+                settled_at: None,
+                bound_call,
+            });
+
+            // Emit event
+            Self::deposit_event(RawEvent::DecisionMarketCreated(who, decision_id, enacted_market, rejected_market));
+
+            Ok(())
+        }
+
+        // Recommend the policy branch with the higher expected metric once
+        // trading on both conditional markets has ended, and refund the
+        // branch whose condition will not materialize
+        #[weight = T::WeightInfo::settle_decision()
+            .saturating_add(T::WeightInfo::process_refunds(T::MaxRefundsPerCall::get()))]
+        #[transactional]
+        pub fn settle_decision(
+            origin,
+            decision_id: T::Hash
+        ) -> DispatchResult {
+            ensure_signed(origin)?;
+
+            // Retrieve decision and its markets
+            let mut decision = DecisionMarkets::<T>::get(decision_id)
+                .ok_or(Error::<T>::DecisionDoesNotExist)?;
+            ensure!(decision.recommendation.is_none(), Error::<T>::DecisionAlreadySettled);
+            let if_enacted = Self::decision_branch_value(decision.enacted_market)?;
+            let if_rejected = Self::decision_branch_value(decision.rejected_market)?;
+
+            // A tie keeps the status quo
+            let enact = if_enacted > if_rejected;
+
+            // Unwind the branch that did not occur
+            let unchosen = if enact { decision.rejected_market } else { decision.enacted_market };
+            let mut market = Markets::<T>::get(unchosen).ok_or(Error::<T>::MarketDoesNotExist)?;
+            Self::refund_conditional(unchosen, &mut market)?;
+            Self::put_market(unchosen, market);
+
+            decision.recommendation = Some(enact);
+            decision.settled_at = Some(system::Module::<T>::block_number());
+            DecisionMarkets::<T>::insert(decision_id, decision);
+
+            // Emit event
+            Self::deposit_event(RawEvent::PolicyRecommended(decision_id, enact));
+
+            Ok(())
+        }
+
+        // Dispatch the call bound to an enacted decision once the dispute
+        // window after its settlement has closed
+        #[weight = T::WeightInfo::execute_decision().saturating_add(T::MaxExecutionWeight::get())]
+        pub fn execute_decision(
+            origin,
+            decision_id: T::Hash
+        ) -> DispatchResult {
+            ensure_signed(origin)?;
+
+            // Retrieve decision
+            let mut decision = DecisionMarkets::<T>::get(decision_id)
+                .ok_or(Error::<T>::DecisionDoesNotExist)?;
+            ensure!(decision.recommendation == Some(true), Error::<T>::DecisionNotEnacted);
+            let settled_at = decision.settled_at.ok_or(Error::<T>::DecisionNotEnacted)?;
+            ensure!(
+                system::Module::<T>::block_number() >= settled_at.saturating_add(T::DisputePeriod::get()),
+                Error::<T>::DisputeWindowOpen
+            );
+
+            // Taking the call guarantees it runs at most once
+            let call = decision.bound_call.take().ok_or(Error::<T>::NothingToExecute)?;
+            DecisionMarkets::<T>::insert(decision_id, decision);
+
+            // The outcome of the call is reported, not propagated
+            let result = call.dispatch(T::ExecutionOrigin::get())
+                .map(|_| ())
+                .map_err(|e| e.error);
+
+            // Emit event
+            Self::deposit_event(RawEvent::ProposalExecuted(decision_id, result));
+
+            Ok(())
+        }
+
+        // Open a freshly created market for trading
+        #[weight = T::WeightInfo::activate_market()]
+        pub fn activate_market(
+            origin,
             market_id: T::Hash
         ) -> DispatchResult {
             let who = ensure_signed(origin)?;
 
+            // Give LPs and traders time to prepare before trading opens
+            ensure!(Markets::<T>::contains_key(market_id), Error::<T>::MarketDoesNotExist);
+            ensure!(Self::activation_period_elapsed(market_id), Error::<T>::ActivationTooEarly);
+
+            Self::do_activate_market(&who, market_id)
+        }
+
+        // Push back the end of an active market's trading window
+        #[weight = T::WeightInfo::extend_trading()]
+        pub fn extend_trading(
+            origin,
+            market_id: T::Hash,
+            new_trading_end: T::BlockNumber
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
             // Retrieve market
             let mut market = Markets::<T>::get(market_id)
                 .ok_or(Error::<T>::MarketDoesNotExist)?;
 
-            // Validate market can be resolved
-            ensure!(market.status == MarketStatus::Active, Error::<T>::MarketNotResolvable);
+            // Only the creator may extend, only forwards and only so far
+            ensure!(market.creator == who, Error::<T>::NotMarketCreator);
+            ensure!(market.status == MarketStatus::Active, Error::<T>::MarketNotActive);
+            ensure!(new_trading_end > market.trading_end, Error::<T>::TradingEndNotExtended);
+            let now = system::Module::<T>::block_number();
+            ensure!(
+                new_trading_end.saturating_sub(now) <= T::MaxExtension::get(),
+                Error::<T>::ExtensionTooLong
+            );
+            ensure!(
+                new_trading_end.saturating_sub(market.creation_block) <= T::MaxMarketLifetime::get(),
+                Error::<T>::LifetimeTooLong
+            );
 
-            // Update market status
-            market.status = MarketStatus::Resolved;
-            market.resolution_block = Some(system::Module::<T>::block_number());
+            // Store updated market; its old expiry entry is skipped when reached
+            market.trading_end = new_trading_end;
+            Self::put_market(market_id, market);
+            Self::schedule_expiry(market_id, new_trading_end);
+
+            // Emit event
+            Self::deposit_event(RawEvent::TradingExtended(market_id, new_trading_end));
+
+            Ok(())
+        }
+
+        // Halt trading on an active market ahead of the report, e.g. once the
+        // event has happened but its outcome is not known yet
+        #[weight = T::WeightInfo::close_market()]
+        pub fn close_market(
+            origin,
+            market_id: T::Hash
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            // Retrieve market
+            let mut market = Markets::<T>::get(market_id)
+                .ok_or(Error::<T>::MarketDoesNotExist)?;
+
+            // Either the creator or the oracle may close
+            ensure!(
+                market.creator == who || market.oracle == who,
+                Error::<T>::NotCreatorOrOracle
+            );
+            ensure!(market.status == MarketStatus::Active, Error::<T>::MarketNotActive);
+
+            Self::halt_trading(who, market_id, market);
+
+            Ok(())
+        }
+
+        // Close a market past its trading end and report its outcome in one
+        // call, leaving no block for a last trade to land in between
+        #[weight = T::WeightInfo::close_and_resolve()]
+        #[transactional]
+        pub fn close_and_resolve(
+            origin,
+            market_id: T::Hash,
+            outcome: MarketOutcome
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            // Retrieve market
+            let market = Markets::<T>::get(market_id)
+                .ok_or(Error::<T>::MarketDoesNotExist)?;
+
+            // Only the oracle may report, and only once trading has ended
+            let now = system::Module::<T>::block_number();
+            ensure!(Self::current_oracle(market_id, &market, now) == who, Error::<T>::NotAuthorizedOracle);
+            ensure!(market.status == MarketStatus::Active, Error::<T>::MarketNotActive);
+            ensure!(now >= market.trading_end, Error::<T>::TradingStillOpen);
+
+            Self::halt_trading(who.clone(), market_id, market);
+            Self::do_resolve_market(who, market_id, outcome)
+        }
+
+        // Hand the right to resolve a market to a different account
+        #[weight = T::WeightInfo::set_oracle()]
+        pub fn set_oracle(
+            origin,
+            market_id: T::Hash,
+            oracle: T::AccountId
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            // Retrieve market
+            let mut market = Markets::<T>::get(market_id)
+                .ok_or(Error::<T>::MarketDoesNotExist)?;
+
+            // Only the creator may change the oracle, and only before trading opens
+            ensure!(market.creator == who, Error::<T>::NotMarketCreator);
+            ensure!(market.status == MarketStatus::Created, Error::<T>::MarketNotActivatable);
+            Self::ensure_oracle_allowed(&who, &oracle)?;
 
             // Store updated market
-            Markets::<T>::insert(market_id, market);
+            market.oracle = oracle.clone();
+            Self::put_market(market_id, market);
 
             // Emit event
-            Self::deposit_event(RawEvent::MarketResolved(who, market_id));
+            Self::deposit_event(RawEvent::OracleSet(market_id, oracle));
 
             Ok(())
         }
-    }
-}
 
-// Storage Declarations
-decl_storage! {
-    trait Store for Module<T: Config> as FutarchyMarkets {
-        // Store all markets
-        Markets get(fn markets): map hasher(blake2_128_concat) T::Hash => Option<PredictionMarket<T::AccountId, BalanceOf<T>, T::BlockNumber>>;
-        
-        // Total number of markets
-        MarketCount get(fn market_count): u64;
-    }
-}
+        // Condition a market on how other binary markets resolve: it only pays
+        // out if each of them resolves to the given side. Charged for a full
+        // set since any earlier conditions are released as well
+        #[weight = T::WeightInfo::set_parent_conditions(T::MaxConditions::get())]
+        pub fn set_parent_conditions(
+            origin,
+            market_id: T::Hash,
+            conditions: BoundedVec<(T::Hash, bool), T::MaxConditions>
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
 
-// Event Declarations
-decl_event!(
-    pub enum Event<T> where AccountId = <T as frame_system::Config>::AccountId {
-        // Market created with its unique ID
-        MarketCreated(AccountId, T::Hash),
-        // Market resolved
-        MarketResolved(AccountId, T::Hash),
-    }
-}
+            // Retrieve market
+            let mut market = Markets::<T>::get(market_id)
+                .ok_or(Error::<T>::MarketDoesNotExist)?;
 
-// Error Declarations
-decl_error! {
-    pub enum Error for Module<T: Config> {
-        // Market does not exist
-        MarketDoesNotExist,
-        // Market is not in a state that can be resolved
-        MarketNotResolvable,
-        // Insufficient funds for market creation
-        InsufficientFunds,
-    }
-}
+            // Only the creator may set conditions, and only before trading opens
+            ensure!(market.creator == who, Error::<T>::NotMarketCreator);
+            ensure!(market.status == MarketStatus::Created, Error::<T>::MarketNotActivatable);
 
-// Module Implementation
-impl<T: Config> Module<T> {
-    // Helper function to get total market count
-    pub fn market_count() -> u64 {
-        MarketCount::get()
+            // Every condition names a distinct, existing binary market
+            for (index, (parent_id, _)) in conditions.iter().enumerate() {
+                ensure!(*parent_id != market_id, Error::<T>::InvalidCondition);
+                ensure!(
+                    conditions.iter().skip(index + 1).all(|(other, _)| other != parent_id),
+                    Error::<T>::InvalidCondition
+                );
+                let parent = Markets::<T>::get(parent_id)
+                    .ok_or(Error::<T>::MarketDoesNotExist)?;
+                ensure!(parent.market_type == MarketType::Binary, Error::<T>::InvalidCondition);
+            }
+
+            // Parents stay around for as long as this market depends on them
+            for (parent_id, _) in market.parent_conditions.iter() {
+                DependentMarkets::<T>::remove(parent_id, market_id);
+            }
+            for (parent_id, _) in conditions.iter() {
+                DependentMarkets::<T>::insert(parent_id, market_id, ());
+            }
+
+            // Store updated market
+            market.parent_conditions = conditions;
+            Self::put_market(market_id, market);
+
+            // Emit event
+            Self::deposit_event(RawEvent::ParentConditionsSet(market_id));
+
+            Ok(())
+        }
+
+        // Have a weighted committee of oracles resolve a market instead of
+        // its single oracle, settling once `threshold` of them have submitted
+        #[weight = T::WeightInfo::set_oracle_committee(oracles.len() as u32)]
+        pub fn set_oracle_committee(
+            origin,
+            market_id: T::Hash,
+            oracles: BoundedVec<(T::AccountId, u32), T::MaxOracles>,
+            threshold: u32
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            // Retrieve market
+            let market = Markets::<T>::get(market_id)
+                .ok_or(Error::<T>::MarketDoesNotExist)?;
+
+            // Only the creator may appoint the committee, and only before trading opens
+            ensure!(market.creator == who, Error::<T>::NotMarketCreator);
+            ensure!(market.status == MarketStatus::Created, Error::<T>::MarketNotActivatable);
+
+            // Every member counts, appears once and could resolve on their own
+            ensure!(
+                threshold > 0 && threshold as usize <= oracles.len(),
+                Error::<T>::InvalidCommittee
+            );
+            for (index, (oracle, weight)) in oracles.iter().enumerate() {
+                ensure!(*weight > 0, Error::<T>::InvalidCommittee);
+                ensure!(
+                    oracles.iter().skip(index + 1).all(|(other, _)| other != oracle),
+                    Error::<T>::InvalidCommittee
+                );
+                Self::ensure_oracle_allowed(&who, oracle)?;
+            }
+
+            // Store the committee
+            OracleCommittees::<T>::insert(market_id, (oracles.clone(), threshold));
+
+            // Emit event
+            Self::deposit_event(RawEvent::OracleCommitteeSet(market_id, oracles.into_inner(), threshold));
+
+            Ok(())
+        }
+
+        // Line up backup oracles that take over in order whenever the one
+        // before them lets `OracleSlaWindow` pass after trading ends
+        // without reporting
+        #[weight = T::WeightInfo::set_backup_oracles(oracles.len() as u32)]
+        pub fn set_backup_oracles(
+            origin,
+            market_id: T::Hash,
+            oracles: BoundedVec<T::AccountId, T::MaxOracles>
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            // Retrieve market
+            let market = Markets::<T>::get(market_id)
+                .ok_or(Error::<T>::MarketDoesNotExist)?;
+
+            // Only the creator may line up backups, and only before trading opens
+            ensure!(market.creator == who, Error::<T>::NotMarketCreator);
+            ensure!(market.status == MarketStatus::Created, Error::<T>::MarketNotActivatable);
+            for oracle in oracles.iter() {
+                Self::ensure_oracle_allowed(&who, oracle)?;
+            }
+
+            // Store the backups, an empty list removing them
+            if oracles.is_empty() {
+                BackupOracles::<T>::remove(market_id);
+            } else {
+                BackupOracles::<T>::insert(market_id, oracles.clone());
+            }
+
+            // Emit event
+            Self::deposit_event(RawEvent::BackupOraclesSet(market_id, oracles.into_inner()));
+
+            Ok(())
+        }
+
+        // Set the per-account position cap of a market, `None` falling back
+        // to the runtime-wide cap
+        #[weight = T::WeightInfo::set_position_cap()]
+        pub fn set_position_cap(
+            origin,
+            market_id: T::Hash,
+            cap: Option<BalanceOf<T>>
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            // Retrieve market
+            let mut market = Markets::<T>::get(market_id)
+                .ok_or(Error::<T>::MarketDoesNotExist)?;
+
+            // Only the creator may set the cap, and only before trading opens
+            ensure!(market.creator == who, Error::<T>::NotMarketCreator);
+            ensure!(market.status == MarketStatus::Created, Error::<T>::MarketNotActivatable);
+
+            // Store updated market
+            market.position_cap = cap;
+            Self::put_market(market_id, market);
+
+            // Emit event
+            Self::deposit_event(RawEvent::PositionCapSet(market_id, cap));
+
+            Ok(())
+        }
+
+        // Cap the collateral a market's pot may hold, `None` lifting the cap
+        #[weight = T::WeightInfo::set_collateral_cap()]
+        pub fn set_collateral_cap(
+            origin,
+            market_id: T::Hash,
+            cap: Option<BalanceOf<T>>
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            // Retrieve market
+            let mut market = Markets::<T>::get(market_id)
+                .ok_or(Error::<T>::MarketDoesNotExist)?;
+
+            // Only the creator may set the cap, and only before trading opens
+            ensure!(market.creator == who, Error::<T>::NotMarketCreator);
+            ensure!(market.status == MarketStatus::Created, Error::<T>::MarketNotActivatable);
+
+            // Store updated market
+            market.max_collateral = cap;
+            Self::put_market(market_id, market);
+
+            // Emit event
+            Self::deposit_event(RawEvent::CollateralCapSet(market_id, cap));
+
+            Ok(())
+        }
+
+        // Cap the shares of each outcome a market may have outstanding,
+        // `None` lifting the cap
+        #[weight = T::WeightInfo::set_share_cap()]
+        pub fn set_share_cap(
+            origin,
+            market_id: T::Hash,
+            cap: Option<BalanceOf<T>>
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            // Retrieve market
+            let mut market = Markets::<T>::get(market_id)
+                .ok_or(Error::<T>::MarketDoesNotExist)?;
+
+            // Only the creator may set the cap, and only before trading opens
+            ensure!(market.creator == who, Error::<T>::NotMarketCreator);
+            ensure!(market.status == MarketStatus::Created, Error::<T>::MarketNotActivatable);
+
+            // Store updated market
+            market.max_shares = cap;
+            Self::put_market(market_id, market);
+
+            // Emit event
+            Self::deposit_event(RawEvent::ShareCapSet(market_id, cap));
+
+            Ok(())
+        }
+
+        // Have `add_liquidity` open the market as soon as it can be activated
+        #[weight = T::WeightInfo::set_auto_activate()]
+        pub fn set_auto_activate(
+            origin,
+            market_id: T::Hash,
+            auto_activate: bool
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            // Retrieve market
+            let mut market = Markets::<T>::get(market_id)
+                .ok_or(Error::<T>::MarketDoesNotExist)?;
+
+            // Only the creator may set the flag, and only before trading opens
+            ensure!(market.creator == who, Error::<T>::NotMarketCreator);
+            ensure!(market.status == MarketStatus::Created, Error::<T>::MarketNotActivatable);
+
+            // Store updated market
+            market.auto_activate = auto_activate;
+            Self::put_market(market_id, market);
+
+            // Emit event
+            Self::deposit_event(RawEvent::AutoActivateSet(market_id, auto_activate));
+
+            Ok(())
+        }
+
+        // Replace the question text of a market before trading opens
+        #[weight = T::WeightInfo::update_metadata(T::MaxMetadataLen::get())]
+        pub fn update_metadata(
+            origin,
+            market_id: T::Hash,
+            new_metadata: Vec<u8>
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let metadata: BoundedVec<u8, T::MaxMetadataLen> = new_metadata
+                .try_into()
+                .map_err(|_| Error::<T>::MetadataTooLong)?;
+
+            // Retrieve market
+            let mut market = Markets::<T>::get(market_id)
+                .ok_or(Error::<T>::MarketDoesNotExist)?;
+
+            // Traders must be able to rely on the question they bought into
+            ensure!(market.creator == who, Error::<T>::NotMarketCreator);
+            ensure!(market.status == MarketStatus::Created, Error::<T>::MarketLocked);
+
+            // Store updated market
+            market.metadata = metadata;
+            Self::put_market(market_id, market);
+
+            // Emit event
+            Self::deposit_event(RawEvent::MetadataUpdated(market_id));
+
+            Ok(())
+        }
+
+        // Set the number of implied decimal places of a scalar market's
+        // reported value
+        #[weight = T::WeightInfo::set_scalar_scale()]
+        pub fn set_scalar_scale(
+            origin,
+            market_id: T::Hash,
+            scale: u8
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            // Retrieve market
+            let mut market = Markets::<T>::get(market_id)
+                .ok_or(Error::<T>::MarketDoesNotExist)?;
+
+            // Only the creator may set the scale, and only before trading opens
+            ensure!(market.creator == who, Error::<T>::NotMarketCreator);
+            ensure!(market.status == MarketStatus::Created, Error::<T>::MarketNotActivatable);
+            ensure!(market.market_type == MarketType::Scalar, Error::<T>::OutcomeTypeMismatch);
+            ensure!(scale <= T::MaxScalarScale::get(), Error::<T>::ScaleTooLarge);
+            Self::ensure_scalar_bounds(&market.market_type, market.scalar_bounds, scale)?;
+
+            // Store updated market
+            market.scale = scale;
+            Self::put_market(market_id, market);
+
+            // Emit event
+            Self::deposit_event(RawEvent::ScalarScaleSet(market_id, scale));
+
+            Ok(())
+        }
+
+        // Choose how a scalar market's payout responds to the reported value
+        #[weight = T::WeightInfo::set_payout_curve()]
+        pub fn set_payout_curve(
+            origin,
+            market_id: T::Hash,
+            curve: ScalarCurve
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            // Retrieve market
+            let mut market = Markets::<T>::get(market_id)
+                .ok_or(Error::<T>::MarketDoesNotExist)?;
+
+            // Only the creator may pick the curve, and only before trading opens
+            ensure!(market.creator == who, Error::<T>::NotMarketCreator);
+            ensure!(market.status == MarketStatus::Created, Error::<T>::MarketNotActivatable);
+            ensure!(market.market_type == MarketType::Scalar, Error::<T>::OutcomeTypeMismatch);
+
+            // Store updated market
+            market.payout_curve = curve;
+            Self::put_market(market_id, market);
+
+            // Emit event
+            Self::deposit_event(RawEvent::PayoutCurveSet(market_id, curve));
+
+            Ok(())
+        }
+
+        // Set the cut of every trade's value paid to the creator on top of
+        // the trading fee, withdrawn with `claim_creator_fees`
+        #[weight = T::WeightInfo::set_creator_fee()]
+        pub fn set_creator_fee(
+            origin,
+            market_id: T::Hash,
+            fee: Permill
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            // Retrieve market
+            let mut market = Markets::<T>::get(market_id)
+                .ok_or(Error::<T>::MarketDoesNotExist)?;
+
+            // Only the creator may set the fee, and only before trading opens
+            ensure!(market.creator == who, Error::<T>::NotMarketCreator);
+            ensure!(market.status == MarketStatus::Created, Error::<T>::MarketNotActivatable);
+            ensure!(fee <= T::MaxCreatorFee::get(), Error::<T>::CreatorFeeTooHigh);
+
+            // Store updated market
+            market.creator_fee = fee;
+            Self::put_market(market_id, market);
+
+            // Emit event
+            Self::deposit_event(RawEvent::CreatorFeeSet(market_id, fee));
+
+            Ok(())
+        }
+
+        // Rule out one outcome of a categorical market before the winner is
+        // known. Its shares can no longer be traded, its price reads zero and
+        // the final report must pick one of the remaining outcomes.
+        #[weight = T::WeightInfo::eliminate_outcome()]
+        pub fn eliminate_outcome(
+            origin,
+            market_id: T::Hash,
+            index: u32
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            // Retrieve market
+            let mut market = Markets::<T>::get(market_id)
+                .ok_or(Error::<T>::MarketDoesNotExist)?;
+
+            // Only the oracle may rule outcomes out, up to the report
+            ensure!(market.oracle == who, Error::<T>::NotAuthorizedOracle);
+            ensure!(market.market_type == MarketType::Categorical, Error::<T>::OutcomeTypeMismatch);
+            ensure!(
+                matches!(market.status, MarketStatus::Active | MarketStatus::Closed),
+                Error::<T>::MarketNotActive
+            );
+            ensure!(index < market.outcome_count, Error::<T>::InvalidOutcome);
+            ensure!(!Self::is_eliminated(&market, index), Error::<T>::OutcomeEliminated);
+
+            // At least one outcome has to survive to be reported
+            let eliminated = market.eliminated | (1 << index);
+            ensure!(eliminated.count_ones() < market.outcome_count, Error::<T>::OutcomeEliminated);
+
+            // Store updated market
+            market.eliminated = eliminated;
+            Self::put_market(market_id, market);
+
+            // Emit event
+            Self::deposit_event(RawEvent::OutcomeEliminated(market_id, index));
+
+            Self::debug_assert_solvent(market_id);
+
+            Ok(())
+        }
+
+        // Withdraw the creator fees a market accrued, once its outcome is final
+        #[weight = T::WeightInfo::claim_creator_fees()]
+        #[transactional]
+        pub fn claim_creator_fees(
+            origin,
+            market_id: T::Hash
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            // Retrieve market
+            let mut market = Markets::<T>::get(market_id)
+                .ok_or(Error::<T>::MarketDoesNotExist)?;
+            ensure!(market.creator == who, Error::<T>::NotMarketCreator);
+
+            // Like a winning claim, the first claim after the dispute window
+            // finalizes the outcome
+            if market.status == MarketStatus::Proposed {
+                Self::finalize(market_id, &mut market)?;
+                Self::put_market(market_id, market.clone());
+            }
+            ensure!(market.status == MarketStatus::Resolved, Error::<T>::MarketNotResolved);
+
+            let fees = CreatorFees::<T>::take(market_id);
+            ensure!(!fees.is_zero(), Error::<T>::NothingToClaim);
+            Self::transfer_collateral(
+                market.collateral_asset,
+                &Self::market_account(market_id),
+                &who,
+                fees,
+                ExistenceRequirement::AllowDeath
+            )?;
+
+            // Emit event
+            Self::deposit_event(RawEvent::CreatorFeesClaimed(who, market_id, fees));
+
+            Self::debug_assert_solvent(market_id);
+
+            Ok(())
+        }
+
+        // Deepen an LMSR market with treasury funds: `amount` pays for the
+        // worst-case loss of the raised liquidity parameter, and whatever the
+        // pot can spare of it once settled goes back to the treasury
+        #[weight = T::WeightInfo::subsidize_market()]
+        #[transactional]
+        pub fn subsidize_market(
+            origin,
+            market_id: T::Hash,
+            amount: BalanceOf<T>
+        ) -> DispatchResult {
+            T::SubsidyOrigin::ensure_origin(origin)?;
+            ensure!(!amount.is_zero(), Error::<T>::InvalidAmount);
+
+            // Retrieve market; only LMSR markets still open to trading qualify
+            let mut market = Markets::<T>::get(market_id)
+                .ok_or(Error::<T>::MarketDoesNotExist)?;
+            ensure!(
+                market.mechanism == MarketMechanism::Lmsr
+                    && matches!(market.status, MarketStatus::Created | MarketStatus::Active),
+                Error::<T>::NotSubsidizable
+            );
+
+            // Raise `b` by as much as the subsidy covers
+            let sets = Self::sets_for_collateral(market.scalar_bounds, amount);
+            let raise = lmsr::liquidity_for(sets, market.outcome_count as usize)
+                .ok_or(Error::<T>::PricingFailed)?
+                .saturated_into::<BalanceOf<T>>();
+            ensure!(!raise.is_zero(), Error::<T>::NotSubsidizable);
+            market.liquidity_parameter = market.liquidity_parameter
+                .checked_add(&raise)
+                .ok_or(Error::<T>::ArithmeticOverflow)?;
+            market.total_liquidity = market.total_liquidity
+                .checked_add(&amount)
+                .ok_or(Error::<T>::ArithmeticOverflow)?;
+
+            // Store updated state
+            let asset = market.collateral_asset;
+            Self::put_market(market_id, market);
+            Subsidies::<T>::mutate(market_id, |subsidy| *subsidy = subsidy.saturating_add(amount));
+
+            // Fund the market pot from the treasury
+            Self::transfer_collateral(
+                asset,
+                &T::TreasuryAccount::get(),
+                &Self::market_account(market_id),
+                amount,
+                ExistenceRequirement::KeepAlive
+            )?;
+
+            // Emit event
+            Self::deposit_event(RawEvent::MarketSubsidized(market_id, amount));
+
+            Self::debug_assert_solvent(market_id);
+
+            Ok(())
+        }
+
+        // Cancel a market and return the creation deposit, paying out the
+        // first `MaxRefundsPerCall` liquidity providers
+        #[weight = T::WeightInfo::cancel_market()
+            .saturating_add(T::WeightInfo::process_refunds(T::MaxRefundsPerCall::get()))]
+        #[transactional]
+        pub fn cancel_market(
+            origin,
+            market_id: T::Hash
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            // Retrieve market
+            let mut market = Markets::<T>::get(market_id)
+                .ok_or(Error::<T>::MarketDoesNotExist)?;
+
+            // Only the creator may cancel, and only before resolution
+            ensure!(market.creator == who, Error::<T>::NotMarketCreator);
+            ensure!(
+                market.status == MarketStatus::Created || market.status == MarketStatus::Active,
+                Error::<T>::MarketNotCancellable
+            );
+            ensure!(TotalShares::<T>::get(market_id).is_empty(), Error::<T>::OutstandingShares);
+
+            // With no shares outstanding the pot belongs to the liquidity providers
+            Self::cancel(market_id, &mut market)?;
+            if Self::refunds_pending(market_id) {
+                Self::do_process_refunds(market_id, market.collateral_asset)?;
+            }
+            Self::put_market(market_id, market);
+            Self::debug_assert_solvent(market_id);
+
+            Ok(())
+        }
+
+        // Cancel any unresolved market, refunding traders at cost and sending
+        // the creator's deposit to the treasury
+        #[weight = T::WeightInfo::force_cancel_market()
+            .saturating_add(T::WeightInfo::process_refunds(T::MaxRefundsPerCall::get()))]
+        #[transactional]
+        pub fn force_cancel_market(
+            origin,
+            market_id: T::Hash
+        ) -> DispatchResult {
+            T::ForceOrigin::ensure_origin(origin)?;
+
+            // Retrieve market
+            let mut market = Markets::<T>::get(market_id)
+                .ok_or(Error::<T>::MarketDoesNotExist)?;
+            ensure!(
+                market.status != MarketStatus::Resolved && market.status != MarketStatus::Cancelled,
+                Error::<T>::MarketNotCancellable
+            );
+
+            // Slash the deposit before `cancel` would return it
+            if !market.deposit_refunded {
+                market.deposit_refunded = true;
+                T::Currency::repatriate_reserved(
+                    &market.creator,
+                    &T::TreasuryAccount::get(),
+                    T::MarketCreationDeposit::get(),
+                    BalanceStatus::Free
+                )?;
+            }
+            Self::refund_conditional(market_id, &mut market)?;
+            Self::put_market(market_id, market);
+
+            // Emit event
+            Self::deposit_event(RawEvent::MarketForceCancelled(market_id));
+
+            Self::debug_assert_solvent(market_id);
+
+            Ok(())
+        }
+
+        // Replace a misconfigured, unresolved market with one built from
+        // `to_spec`, carrying over its pot, positions, cost basis, LP shares,
+        // oracles, featured flag and the markets conditioned on it so nobody
+        // has to re-enter. The replacement keeps the source's metadata,
+        // creator and deposit; only the trading end, scalar bounds, oracle
+        // and category may change, and the source is cancelled empty.
+        //
+        // Everything is moved in one go, so the call is weighted by the
+        // `holders`, `providers` and `dependents` the caller says the source
+        // has, and fails if it has more.
+        #[weight = T::WeightInfo::migrate_market(*holders, *providers, *dependents)]
+        #[transactional]
+        pub fn migrate_market(
+            origin,
+            from_id: T::Hash,
+            to_spec: MarketSpecOf<T>,
+            holders: u32,
+            providers: u32,
+            dependents: u32
+        ) -> DispatchResult {
+            T::ForceOrigin::ensure_origin(origin)?;
+            let now = system::Module::<T>::block_number();
+
+            // Retrieve market
+            let mut source = Markets::<T>::get(from_id)
+                .ok_or(Error::<T>::MarketDoesNotExist)?;
+            ensure!(
+                matches!(source.status, MarketStatus::Created | MarketStatus::Active | MarketStatus::Closed),
+                Error::<T>::MarketNotCancellable
+            );
+            Self::ensure_migratable(&source, &to_spec)?;
+
+            // Reopening a closed market takes up another trading slot
+            if source.status == MarketStatus::Closed {
+                ensure!(
+                    StatusCounts::get(MarketStatus::Active) < u64::from(T::MaxActiveMarkets::get()),
+                    Error::<T>::TooManyActiveMarkets
+                );
+            }
+
+            // Read no more of each index than the call was weighted for
+            let positions: Vec<_> = Positions::<T>::iter_prefix(from_id)
+                .take(holders as usize + 1)
+                .collect();
+            let bases: Vec<_> = CostBasis::<T>::iter_prefix(from_id)
+                .take(holders as usize + 1)
+                .collect();
+            let stakes: Vec<_> = LpShares::<T>::iter_prefix(from_id)
+                .take(providers as usize + 1)
+                .collect();
+            let children: Vec<T::Hash> = DependentMarkets::<T>::iter_prefix(from_id)
+                .map(|(child, _)| child)
+                .take(dependents as usize + 1)
+                .collect();
+            ensure!(
+                positions.len() <= holders as usize
+                    && bases.len() <= holders as usize
+                    && stakes.len() <= providers as usize
+                    && children.len() <= dependents as usize,
+                Error::<T>::MigrationTooLarge
+            );
+
+            // Build the replacement, reopening a closed market for trading
+            let market_id = Self::next_market_id(&source.creator);
+            let mut market = source.clone();
+            market.id = market_id;
+            market.status = match source.status {
+                MarketStatus::Created => MarketStatus::Created,
+                _ => MarketStatus::Active,
+            };
+            market.creation_block = now;
+            market.trading_end = to_spec.trading_end;
+            market.scalar_bounds = to_spec.scalar_bounds;
+            market.category = to_spec.category;
+            if let Some(oracle) = to_spec.oracle {
+                Self::ensure_oracle_allowed(&source.creator, &oracle)?;
+                market.oracle = oracle;
+            }
+
+            // Move holders, liquidity providers and the pool over
+            for (holder, position) in positions {
+                HeldMarkets::<T>::remove(&holder, from_id);
+                HeldMarkets::<T>::insert(&holder, market_id, ());
+                Positions::<T>::remove(from_id, &holder);
+                Positions::<T>::insert(market_id, &holder, position);
+            }
+            for (holder, basis) in bases {
+                CostBasis::<T>::remove(from_id, &holder);
+                CostBasis::<T>::insert(market_id, &holder, basis);
+            }
+            for (provider, lp) in stakes {
+                LpShares::<T>::remove(from_id, &provider);
+                LpShares::<T>::insert(market_id, &provider, lp);
+            }
+            LpIssuance::<T>::insert(market_id, LpIssuance::<T>::take(from_id));
+            TotalCostBasis::<T>::insert(market_id, TotalCostBasis::<T>::take(from_id));
+            TotalShares::<T>::insert(market_id, TotalShares::<T>::take(from_id));
+            let reserves = Reserves::<T>::take(from_id);
+            if !reserves.is_empty() {
+                Reserves::<T>::insert(market_id, reserves);
+            }
+            Subsidies::<T>::insert(market_id, Subsidies::<T>::take(from_id));
+            CollectedFees::<T>::insert(market_id, CollectedFees::<T>::take(from_id));
+            CreatorFees::<T>::insert(market_id, CreatorFees::<T>::take(from_id));
+            PriceHistory::<T>::insert(market_id, PriceHistory::<T>::take(from_id));
+            if let Some(committee) = OracleCommittees::<T>::take(from_id) {
+                OracleCommittees::<T>::insert(market_id, committee);
+            }
+            let backups = BackupOracles::<T>::take(from_id);
+            if !backups.is_empty() {
+                BackupOracles::<T>::insert(market_id, backups);
+            }
+            let featured = Featured::<T>::contains_key(from_id);
+            Self::move_pot(source.collateral_asset, from_id, market_id)?;
+
+            // Conditions move with the market, both those it sets on its
+            // parents and those its dependents set on it
+            for (parent_id, _) in source.parent_conditions.iter() {
+                DependentMarkets::<T>::remove(parent_id, from_id);
+                DependentMarkets::<T>::insert(parent_id, market_id, ());
+            }
+            source.parent_conditions = BoundedVec::default();
+            for child_id in children {
+                if let Some(mut child) = Markets::<T>::get(child_id) {
+                    for (parent_id, _) in child.parent_conditions.iter_mut() {
+                        if *parent_id == from_id {
+                            *parent_id = market_id;
+                        }
+                    }
+                    Self::put_market(child_id, child);
+                }
+                DependentMarkets::<T>::remove(from_id, child_id);
+                DependentMarkets::<T>::insert(market_id, child_id, ());
+            }
+
+            // Cancel the emptied source; the deposit stays reserved for the
+            // replacement
+            source.total_liquidity = Zero::zero();
+            source.deposit_refunded = true;
+            Self::cancel(from_id, &mut source)?;
+            Self::put_market(from_id, source);
+
+            // Index and store the replacement
+            let count = MarketCount::get()
+                .checked_add(1)
+                .ok_or(Error::<T>::MarketCountOverflow)?;
+            MarketCount::put(count);
+            MarketsByCreator::<T>::try_mutate(&market.creator, |ids| ids.try_push(market_id))
+                .map_err(|_| Error::<T>::TooManyMarkets)?;
+            MarketsByCategory::<T>::try_mutate(market.category, |ids| ids.try_push(market_id))
+                .map_err(|_| Error::<T>::TooManyInCategory)?;
+            if market.status == MarketStatus::Active {
+                ActiveMarkets::<T>::insert(market_id, ());
+            }
+            Self::schedule_expiry(market_id, market.trading_end);
+            Self::put_market(market_id, market);
+            if featured {
+                Featured::<T>::insert(market_id, ());
+            }
+
+            // Emit event
+            Self::deposit_event(RawEvent::MarketMigrated(from_id, market_id));
+
+            Self::debug_assert_solvent(from_id);
+            Self::debug_assert_solvent(market_id);
+
+            Ok(())
+        }
+
+        // Freeze or unfreeze trading and liquidity across every market;
+        // resolution and claims carry on regardless
+        #[weight = T::WeightInfo::set_paused()]
+        pub fn set_paused(origin, paused: bool) -> DispatchResult {
+            T::PauseOrigin::ensure_origin(origin)?;
+
+            Paused::put(paused);
+
+            // Emit event
+            Self::deposit_event(RawEvent::PausedSet(paused));
+
+            Ok(())
+        }
+
+        // Promote a live market to front-ends, or stop promoting it
+        #[weight = T::WeightInfo::set_featured()]
+        pub fn set_featured(origin, market_id: T::Hash, featured: bool) -> DispatchResult {
+            T::CurationOrigin::ensure_origin(origin)?;
+
+            // Only markets still to be settled may be featured
+            let market = Markets::<T>::get(market_id)
+                .ok_or(Error::<T>::MarketDoesNotExist)?;
+            if featured {
+                ensure!(
+                    market.status != MarketStatus::Resolved && market.status != MarketStatus::Cancelled,
+                    Error::<T>::MarketSettled
+                );
+                Featured::<T>::insert(market_id, ());
+            } else {
+                Featured::<T>::remove(market_id);
+            }
+
+            // Emit event
+            Self::deposit_event(RawEvent::FeaturedSet(market_id, featured));
+
+            Ok(())
+        }
+
+        // Allow an account to create markets under `CreationPolicy::WhitelistOnly`
+        #[weight = T::WeightInfo::add_approved_creator()]
+        pub fn add_approved_creator(origin, who: T::AccountId) -> DispatchResult {
+            T::CreatorApprovalOrigin::ensure_origin(origin)?;
+
+            ApprovedCreators::<T>::insert(&who, ());
+
+            // Emit event
+            Self::deposit_event(RawEvent::CreatorApproved(who));
+
+            Ok(())
+        }
+
+        // Withdraw an account's approval to create markets; its existing
+        // markets are unaffected
+        #[weight = T::WeightInfo::remove_approved_creator()]
+        pub fn remove_approved_creator(origin, who: T::AccountId) -> DispatchResult {
+            T::CreatorApprovalOrigin::ensure_origin(origin)?;
+
+            ApprovedCreators::<T>::remove(&who);
+
+            // Emit event
+            Self::deposit_event(RawEvent::CreatorRemoved(who));
+
+            Ok(())
+        }
+
+        // Trading extrinsics run checks-effects-interactions: every read and
+        // validation comes first, storage is written next, and collateral
+        // moves last. They are also transactional, so a transfer failing at
+        // the end rolls the writes back instead of leaving shares unpaid for.
+
+        // Buy `amount` shares of one outcome of an active market, paying at
+        // most `max_cost` including fees
+        #[weight = T::WeightInfo::buy_shares(T::MaxOutcomes::get())]
+        #[transactional]
+        pub fn buy_shares(
+            origin,
+            market_id: T::Hash,
+            outcome: u32,
+            amount: BalanceOf<T>,
+            max_cost: Option<BalanceOf<T>>
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            Self::do_buy_shares(who, market_id, outcome, amount, max_cost)
+        }
+
+        // Sell `amount` shares of one outcome back to an active market,
+        // receiving at least `min_proceeds` after fees
+        #[weight = T::WeightInfo::sell_shares(T::MaxOutcomes::get())]
+        #[transactional]
+        pub fn sell_shares(
+            origin,
+            market_id: T::Hash,
+            outcome: u32,
+            amount: BalanceOf<T>,
+            min_proceeds: Option<BalanceOf<T>>
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            Self::do_sell_shares(who, market_id, outcome, amount, min_proceeds)
+        }
+
+        // Let `delegate` buy and sell shares on the caller's behalf
+        #[weight = T::WeightInfo::add_trading_delegate()]
+        pub fn add_trading_delegate(origin, delegate: T::AccountId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            ensure!(delegate != who, Error::<T>::InvalidDelegate);
+            ensure!(
+                !TradingDelegates::<T>::contains_key(&who, &delegate),
+                Error::<T>::InvalidDelegate
+            );
+
+            TradingDelegates::<T>::insert(&who, &delegate, ());
+
+            // Emit event
+            Self::deposit_event(RawEvent::TradingDelegateAdded(who, delegate));
+
+            Ok(())
+        }
+
+        // Revoke a delegate's right to trade for the caller
+        #[weight = T::WeightInfo::remove_trading_delegate()]
+        pub fn remove_trading_delegate(origin, delegate: T::AccountId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            ensure!(
+                TradingDelegates::<T>::contains_key(&who, &delegate),
+                Error::<T>::NotTradingDelegate
+            );
+
+            TradingDelegates::<T>::remove(&who, &delegate);
+
+            // Emit event
+            Self::deposit_event(RawEvent::TradingDelegateRemoved(who, delegate));
+
+            Ok(())
+        }
+
+        // Buy shares into `owner`'s position as one of their delegates. The
+        // owner pays, and the shares are theirs.
+        #[weight = T::WeightInfo::buy_shares_for(T::MaxOutcomes::get())]
+        #[transactional]
+        pub fn buy_shares_for(
+            origin,
+            owner: T::AccountId,
+            market_id: T::Hash,
+            outcome: u32,
+            amount: BalanceOf<T>,
+            max_cost: Option<BalanceOf<T>>
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            ensure!(TradingDelegates::<T>::contains_key(&owner, &who), Error::<T>::NotTradingDelegate);
+            Self::do_buy_shares(owner, market_id, outcome, amount, max_cost)
+        }
+
+        // Sell shares out of `owner`'s position as one of their delegates,
+        // paying the proceeds to the owner
+        #[weight = T::WeightInfo::sell_shares_for(T::MaxOutcomes::get())]
+        #[transactional]
+        pub fn sell_shares_for(
+            origin,
+            owner: T::AccountId,
+            market_id: T::Hash,
+            outcome: u32,
+            amount: BalanceOf<T>,
+            min_proceeds: Option<BalanceOf<T>>
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            ensure!(TradingDelegates::<T>::contains_key(&owner, &who), Error::<T>::NotTradingDelegate);
+            Self::do_sell_shares(owner, market_id, outcome, amount, min_proceeds)
+        }
+
+        // Move outcome shares to another account without trading them
+        #[weight = T::WeightInfo::transfer_shares()]
+        pub fn transfer_shares(
+            origin,
+            market_id: T::Hash,
+            to: T::AccountId,
+            outcome: u32,
+            amount: BalanceOf<T>
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            Self::ensure_not_paused()?;
+            ensure!(!amount.is_zero(), Error::<T>::InvalidAmount);
+
+            // Shares stop moving once the market is settled
+            let market = Markets::<T>::get(market_id)
+                .ok_or(Error::<T>::MarketDoesNotExist)?;
+            ensure!(
+                market.status != MarketStatus::Resolved && market.status != MarketStatus::Cancelled,
+                Error::<T>::MarketSettled
+            );
+            ensure!(outcome < market.outcome_count, Error::<T>::InvalidOutcome);
+
+            // The recipient is held to the same position cap as a buyer
+            let index = outcome as usize;
+            if to != who {
+                let received = Positions::<T>::get(market_id, &to).balance_of(index);
+                Self::ensure_within_position_cap(&market, received, amount)?;
+            }
+
+            // The cost basis follows the shares pro rata, so refunds of a
+            // called-off market go to whoever holds them
+            let held = Positions::<T>::get(market_id, &who).total();
+            Positions::<T>::try_mutate(market_id, &who, |position| {
+                position.burn(index, amount).ok_or(Error::<T>::InsufficientShares)
+            })?;
+            if Positions::<T>::get(market_id, &who).is_empty() {
+                HeldMarkets::<T>::remove(&who, market_id);
+            }
+            Positions::<T>::mutate(market_id, &to, |position| position.mint(index, amount));
+            HeldMarkets::<T>::insert(&to, market_id, ());
+            let basis = amm::mul_div(
+                CostBasis::<T>::get(market_id, &who).saturated_into::<u128>(),
+                amount.saturated_into::<u128>(),
+                held.saturated_into::<u128>()
+            )
+            .unwrap_or_default()
+            .saturated_into::<BalanceOf<T>>();
+            CostBasis::<T>::mutate(market_id, &who, |b| *b = b.saturating_sub(basis));
+            CostBasis::<T>::mutate(market_id, &to, |b| *b = b.saturating_add(basis));
+
+            // Emit event
+            Self::deposit_event(RawEvent::SharesTransferred(who, to, market_id, outcome, amount));
+
+            Self::debug_assert_solvent(market_id);
+
+            Ok(())
+        }
+
+        // Mint `amount` shares of every outcome, paying one unit of collateral
+        // per set, or the width of the range on scalar markets. A complete set
+        // always settles at what it cost, so prices do not move.
+        #[weight = T::WeightInfo::split_collateral(T::MaxOutcomes::get())]
+        #[transactional]
+        pub fn split_collateral(
+            origin,
+            market_id: T::Hash,
+            amount: BalanceOf<T>
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            Self::ensure_not_paused()?;
+            ensure!(!amount.is_zero(), Error::<T>::InvalidAmount);
+
+            // Retrieve market
+            let mut market = Markets::<T>::get(market_id)
+                .ok_or(Error::<T>::MarketDoesNotExist)?;
+            Self::ensure_tradable(&market)?;
+            let value = Self::collateral_for_sets(market.scalar_bounds, amount.saturated_into::<u128>())?;
+            market.total_liquidity = market.total_liquidity
+                .checked_add(&value)
+                .ok_or(Error::<T>::ArithmeticOverflow)?;
+            Self::ensure_within_collateral_cap(&market, market.total_liquidity)?;
+            let largest = Self::outstanding_shares(market_id, &market)
+                .into_iter()
+                .max()
+                .unwrap_or_default()
+                .saturated_into::<BalanceOf<T>>();
+            Self::ensure_within_share_cap(&market, largest.saturating_add(amount))?;
+            let position = Positions::<T>::get(market_id, &who);
+            for index in 0..market.outcome_count as usize {
+                Self::ensure_within_position_cap(&market, position.balance_of(index), amount)?;
+            }
+            let inflow = Self::checked_inflow(value)?;
+
+            // Move the collateral backing the sets into the market pot
+            Self::transfer_collateral(
+                market.collateral_asset,
+                &who,
+                &Self::market_account(market_id),
+                value,
+                ExistenceRequirement::KeepAlive
+            )?;
+
+            // Mint one share of every outcome per set
+            for index in 0..market.outcome_count as usize {
+                Positions::<T>::mutate(market_id, &who, |position| position.mint(index, amount));
+                TotalShares::<T>::mutate(market_id, |totals| totals.mint(index, amount));
+            }
+            HeldMarkets::<T>::insert(&who, market_id, ());
+            CostBasis::<T>::mutate(market_id, &who, |basis| *basis = basis.saturating_add(value));
+            TotalCostBasis::<T>::mutate(market_id, |total| *total = total.saturating_add(value));
+            Self::put_market(market_id, market);
+            BlockInflow::<T>::put(inflow);
+
+            // Emit event
+            Self::deposit_event(RawEvent::CollateralSplit(who, market_id, amount));
+
+            Self::debug_assert_solvent(market_id);
+
+            Ok(())
+        }
+
+        // Burn `amount` shares of every outcome for the collateral the sets
+        // were minted for, less the trading fees. Complete sets may be
+        // redeemed until the market settles.
+        #[weight = T::WeightInfo::merge_shares(T::MaxOutcomes::get())]
+        #[transactional]
+        pub fn merge_shares(
+            origin,
+            market_id: T::Hash,
+            amount: BalanceOf<T>
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            Self::ensure_not_paused()?;
+            ensure!(!amount.is_zero(), Error::<T>::InvalidAmount);
+
+            // Retrieve market
+            let mut market = Markets::<T>::get(market_id)
+                .ok_or(Error::<T>::MarketDoesNotExist)?;
+            ensure!(
+                !matches!(
+                    market.status,
+                    MarketStatus::Created | MarketStatus::Resolved | MarketStatus::Cancelled
+                ),
+                Error::<T>::MarketNotActive
+            );
+
+            // Burn one share of every outcome per unit
+            let mut position = Positions::<T>::get(market_id, &who);
+            let mut totals = TotalShares::<T>::get(market_id);
+            for index in 0..market.outcome_count as usize {
+                position.burn(index, amount).ok_or(Error::<T>::InsufficientShares)?;
+                totals.burn(index, amount).ok_or(Error::<T>::InsufficientShares)?;
+            }
+            let value = Self::collateral_for_sets(market.scalar_bounds, amount.saturated_into::<u128>())?;
+            market.total_liquidity = market.total_liquidity
+                .checked_sub(&value)
+                .ok_or(Error::<T>::ArithmeticOverflow)?;
+
+            // Pay out the sets' value, keeping the fees behind
+            let fee = T::TradingFee::get().mul_floor(value);
+            let protocol_fee = T::ProtocolFee::get().mul_floor(value);
+            let net = value
+                .checked_sub(&fee)
+                .and_then(|net| net.checked_sub(&protocol_fee))
+                .ok_or(Error::<T>::ArithmeticOverflow)?;
+            let fees = CollectedFees::<T>::get(market_id)
+                .checked_add(&fee)
+                .ok_or(Error::<T>::ArithmeticOverflow)?;
+            let pot = Self::market_account(market_id);
+            Self::transfer_collateral(
+                market.collateral_asset,
+                &pot,
+                &who,
+                net,
+                ExistenceRequirement::AllowDeath
+            )?;
+            CollectedFees::<T>::insert(market_id, fees);
+            Self::pay_protocol_fee(market_id, market.collateral_asset, &pot, protocol_fee)?;
+
+            // Store updated state
+            if position.is_empty() {
+                HeldMarkets::<T>::remove(&who, market_id);
+            }
+            Positions::<T>::insert(market_id, &who, position);
+            TotalShares::<T>::insert(market_id, totals);
+            let basis = CostBasis::<T>::get(market_id, &who);
+            let remaining = basis.saturating_sub(value);
+            CostBasis::<T>::insert(market_id, &who, remaining);
+            TotalCostBasis::<T>::mutate(market_id, |total| *total = total.saturating_sub(basis - remaining));
+            Self::put_market(market_id, market);
+
+            // Emit event
+            Self::deposit_event(RawEvent::SharesMerged(who, market_id, amount, fee));
+
+            Self::debug_assert_solvent(market_id);
+
+            Ok(())
+        }
+
+        // Fund the market maker of a market in exchange for LP shares
+        #[weight = T::WeightInfo::add_liquidity(T::MaxOutcomes::get())]
+        #[transactional]
+        pub fn add_liquidity(
+            origin,
+            market_id: T::Hash,
+            amount: BalanceOf<T>
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            Self::ensure_not_paused()?;
+            ensure!(!amount.is_zero(), Error::<T>::InvalidAmount);
+
+            // Retrieve market
+            let mut market = Markets::<T>::get(market_id)
+                .ok_or(Error::<T>::MarketDoesNotExist)?;
+            ensure!(
+                market.status == MarketStatus::Created || market.status == MarketStatus::Active,
+                Error::<T>::MarketNotActive
+            );
+            Self::ensure_within_collateral_cap(&market, market.total_liquidity.saturating_add(amount))?;
+            let inflow = Self::checked_inflow(amount)?;
+
+            Self::do_add_liquidity(&who, market_id, &mut market, amount)?;
+            let creator = market.creator.clone();
+            let auto_activate = market.auto_activate
+                && market.status == MarketStatus::Created
+                && market.total_liquidity >= T::MinActivationLiquidity::get();
+            Self::put_market(market_id, market);
+            BlockInflow::<T>::put(inflow);
+
+            // Open the market if this deposit made it deep enough; one still
+            // short of `MinActivationLiquidity` keeps waiting for more
+            if auto_activate && Self::activation_period_elapsed(market_id) {
+                Self::do_activate_market(&creator, market_id)?;
+            }
+
+            Self::debug_assert_solvent(market_id);
+
+            Ok(())
+        }
+
+        // Burn LP shares for a proportional slice of the market maker's funds
+        #[weight = T::WeightInfo::remove_liquidity(T::MaxOutcomes::get())]
+        pub fn remove_liquidity(
+            origin,
+            market_id: T::Hash,
+            lp_amount: BalanceOf<T>
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            Self::ensure_not_paused()?;
+            ensure!(!lp_amount.is_zero(), Error::<T>::InvalidAmount);
+
+            // Retrieve market
+            let mut market = Markets::<T>::get(market_id)
+                .ok_or(Error::<T>::MarketDoesNotExist)?;
+            ensure!(market.status != MarketStatus::Cancelled, Error::<T>::MarketNotActive);
+
+            // Closed markets keep their pot until resolution, and once resolved
+            // it stays locked for `LpWithdrawalDelay` so winners can claim first
+            ensure!(
+                !matches!(
+                    market.status,
+                    MarketStatus::Closed | MarketStatus::Proposed | MarketStatus::Disputed
+                ),
+                Error::<T>::LiquidityLocked
+            );
+            let resolved = market.status == MarketStatus::Resolved;
+            if resolved {
+                let now = system::Module::<T>::block_number();
+                let unlocked = SettledAt::<T>::get(market_id)
+                    .map_or(true, |settled| now >= settled.saturating_add(T::LpWithdrawalDelay::get()));
+                ensure!(unlocked, Error::<T>::LiquidityLocked);
+            }
+
+            let held = LpShares::<T>::get(market_id, &who);
+            ensure!(held >= lp_amount, Error::<T>::InsufficientLpShares);
+            let issuance = LpIssuance::<T>::get(market_id);
+
+            // Work out the collateral owed, and for constant-product pools the
+            // shares that could not be merged back into collateral
+            let (payout, withdrawal) = match market.mechanism {
+                // After settlement whatever the pot holds beyond the winnings
+                // still unclaimed belongs to the LPs
+                _ if resolved => {
+                    let residual = market.total_liquidity
+                        .saturating_sub(Self::unclaimed_winnings(market_id, &market));
+                    let payout = amm::mul_div(
+                        residual.saturated_into::<u128>(),
+                        lp_amount.saturated_into::<u128>(),
+                        issuance.saturated_into::<u128>()
+                    )
+                    .ok_or(Error::<T>::PricingFailed)?;
+                    (payout, None)
+                }
+                MarketMechanism::Lmsr => {
+                    let payout = amm::mul_div(
+                        Self::pool_value(market_id, &market).saturated_into::<u128>(),
+                        lp_amount.saturated_into::<u128>(),
+                        issuance.saturated_into::<u128>()
+                    )
+                    .ok_or(Error::<T>::PricingFailed)?;
+                    (payout, None)
+                }
+                MarketMechanism::ConstantProduct => {
+                    let (reserves, removed) = amm::remove_funding(
+                        &Self::pool_reserves(market_id),
+                        lp_amount.saturated_into::<u128>(),
+                        issuance.saturated_into::<u128>()
+                    )
+                    .ok_or(Error::<T>::PricingFailed)?;
+                    let sets = removed.iter().copied().min().unwrap_or_default();
+                    let leftover: Vec<u128> = removed.iter().map(|x| x - sets).collect();
+                    (Self::collateral_for_sets(market.scalar_bounds, sets)?.saturated_into::<u128>(), Some((reserves, leftover)))
+                }
+            };
+            let payout = payout.saturated_into::<BalanceOf<T>>();
+
+            // Providers also take their pro-rata share of trading fees
+            let fees = CollectedFees::<T>::get(market_id);
+            let fee_share = amm::mul_div(
+                fees.saturated_into::<u128>(),
+                lp_amount.saturated_into::<u128>(),
+                issuance.saturated_into::<u128>()
+            )
+            .ok_or(Error::<T>::PricingFailed)?
+            .saturated_into::<BalanceOf<T>>();
+
+            // Pay the provider out of the market pot
+            Self::transfer_collateral(
+                market.collateral_asset,
+                &Self::market_account(market_id),
+                &who,
+                payout.saturating_add(fee_share),
+                ExistenceRequirement::AllowDeath
+            )?;
+            CollectedFees::<T>::insert(market_id, fees.saturating_sub(fee_share));
+
+            // Store updated state
+            if let Some((reserves, leftover)) = withdrawal {
+                Self::credit_shares(market_id, &who, &leftover);
+                Reserves::<T>::insert(
+                    market_id,
+                    reserves.into_iter().map(|r| r.saturated_into()).collect::<Vec<BalanceOf<T>>>()
+                );
+            }
+            LpShares::<T>::insert(market_id, &who, held - lp_amount);
+            LpIssuance::<T>::insert(market_id, issuance.saturating_sub(lp_amount));
+            market.total_liquidity = market.total_liquidity.saturating_sub(payout);
+            Self::put_market(market_id, market);
+
+            // Emit event
+            Self::deposit_event(RawEvent::LiquidityRemoved(who, market_id, lp_amount, payout.saturating_add(fee_share)));
+
+            Self::debug_assert_solvent(market_id);
+
+            Ok(())
+        }
+
+        // Report the outcome of a prediction market, opening the dispute window
+        #[weight = T::WeightInfo::resolve_market()]
+        pub fn resolve_market(
+            origin, 
+            market_id: T::Hash,
+            outcome: MarketOutcome
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            Self::do_resolve_market(who, market_id, outcome)
+        }
+
+        // Submit one committee member's view of a market's outcome. Once the
+        // threshold is met the weighted votes are aggregated, the median for
+        // scalar markets and the plurality otherwise, and the market resolves
+        // straight away.
+        #[weight = T::WeightInfo::submit_resolution(T::MaxOracles::get())]
+        #[transactional]
+        pub fn submit_resolution(
+            origin,
+            market_id: T::Hash,
+            outcome: MarketOutcome
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            // Retrieve market
+            let mut market = Markets::<T>::get(market_id)
+                .ok_or(Error::<T>::MarketDoesNotExist)?;
+
+            // Only committee members may vote, once each
+            let (oracles, threshold) = OracleCommittees::<T>::get(market_id)
+                .ok_or(Error::<T>::NotAuthorizedOracle)?;
+            ensure!(oracles.iter().any(|(oracle, _)| *oracle == who), Error::<T>::NotAuthorizedOracle);
+            ensure!(!ResolutionVotes::<T>::contains_key(market_id, &who), Error::<T>::DuplicateSubmission);
+
+            // Same window and outcome checks as a single oracle's report
+            let now = system::Module::<T>::block_number();
+            match market.status {
+                MarketStatus::Active => ensure!(now >= market.trading_end, Error::<T>::TradingStillOpen),
+                MarketStatus::Closed => {}
+                _ => return Err(Error::<T>::MarketNotResolvable.into()),
+            }
+            Self::ensure_outcome_matches(&market, &outcome)?;
+            let outcome = Self::clamp_outcome(&market, outcome);
+
+            // Record the vote
+            ResolutionVotes::<T>::insert(market_id, &who, outcome.clone());
+            Self::deposit_event(RawEvent::ResolutionSubmitted(who.clone(), market_id, outcome));
+
+            // Wait for the quorum
+            let votes: Vec<(MarketOutcome, u32)> = oracles
+                .iter()
+                .filter_map(|(oracle, weight)| ResolutionVotes::<T>::get(market_id, oracle).map(|vote| (vote, *weight)))
+                .collect();
+            if (votes.len() as u32) < threshold {
+                return Ok(());
+            }
+            let outcome = Self::aggregate_votes(votes).ok_or(Error::<T>::InvalidOutcome)?;
+
+            // Update market status; the committee's verdict is final
+            market.status = MarketStatus::Resolved;
+            market.resolution_block = Some(now);
+            market.resolved_outcome = Some(outcome.clone());
+            Self::settle_creation_deposit(&mut market, true);
+            let owed = Self::unclaimed_winnings(market_id, &market);
+            Self::return_subsidy(market_id, &mut market, owed)?;
+            let liquidity = market.total_liquidity;
+
+            // Store updated market; it is no longer open for trading
+            Self::put_market(market_id, market);
+            ActiveMarkets::<T>::remove(market_id);
+            let _ = ResolutionVotes::<T>::remove_prefix(market_id, None);
+            T::OnMarketResolved::on_market_resolved(market_id, &outcome);
+
+            // Emit events
+            Self::deposit_market_event(market_id, RawEvent::MarketResolved(who, market_id, outcome, liquidity));
+            Self::deposit_market_event(market_id, RawEvent::MarketFinalized(market_id));
+
+            Self::debug_assert_solvent(market_id);
+
+            Ok(())
+        }
+
+        // Challenge a reported outcome while the dispute window is open
+        #[weight = T::WeightInfo::dispute_market()]
+        pub fn dispute_market(
+            origin,
+            market_id: T::Hash
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            // Retrieve market
+            let mut market = Markets::<T>::get(market_id)
+                .ok_or(Error::<T>::MarketDoesNotExist)?;
+            ensure!(market.status == MarketStatus::Proposed, Error::<T>::MarketNotDisputable);
+            ensure!(!Self::dispute_window_passed(&market), Error::<T>::DisputeWindowClosed);
+
+            // Bond the disputer in proportion to what the market holds
+            let bond = Self::dispute_bond(&market);
+            T::Currency::reserve(&who, bond)?;
+
+            // Update market status
+            market.status = MarketStatus::Disputed;
+            market.dispute_bond = bond;
+            Self::put_market(market_id, market);
+            Disputes::<T>::insert(market_id, (who.clone(), bond));
+
+            // Emit event
+            Self::deposit_event(RawEvent::MarketDisputed(who, market_id));
+
+            Self::debug_assert_solvent(market_id);
+
+            Ok(())
+        }
+
+        // Rule on a disputed market, settling the disputer's bond
+        #[weight = T::WeightInfo::resolve_dispute()]
+        #[transactional]
+        pub fn resolve_dispute(
+            origin,
+            market_id: T::Hash,
+            final_outcome: MarketOutcome
+        ) -> DispatchResult {
+            T::DisputeOrigin::ensure_origin(origin)?;
+
+            // Retrieve market
+            let mut market = Markets::<T>::get(market_id)
+                .ok_or(Error::<T>::MarketDoesNotExist)?;
+            ensure!(market.status == MarketStatus::Disputed, Error::<T>::MarketNotDisputed);
+            Self::ensure_outcome_matches(&market, &final_outcome)?;
+            let final_outcome = Self::clamp_outcome(&market, final_outcome);
+
+            // A disputer proven wrong loses their bond, otherwise it is returned
+            let original_stood = market.resolved_outcome.as_ref() == Some(&final_outcome);
+            Self::settle_dispute_bond(market_id, &mut market, !original_stood);
+
+            // Update market status; the creator and the oracle only keep
+            // their deposit and bond if the reported outcome stood
+            market.status = MarketStatus::Resolved;
+            market.resolved_outcome = Some(final_outcome.clone());
+            Self::settle_creation_deposit(&mut market, original_stood);
+            Self::settle_resolution_bond(&mut market, original_stood);
+            let owed = Self::unclaimed_winnings(market_id, &market);
+            Self::return_subsidy(market_id, &mut market, owed)?;
+            Self::put_market(market_id, market);
+            T::OnMarketResolved::on_market_resolved(market_id, &final_outcome);
+
+            // Emit event
+            Self::deposit_market_event(market_id, RawEvent::DisputeResolved(market_id, final_outcome, original_stood));
+
+            Self::debug_assert_solvent(market_id);
+
+            Ok(())
+        }
+
+        // Finalize an undisputed outcome once the dispute window has passed
+        #[weight = T::WeightInfo::finalize_market()]
+        #[transactional]
+        pub fn finalize_market(
+            origin,
+            market_id: T::Hash
+        ) -> DispatchResult {
+            ensure_signed(origin)?;
+
+            // Retrieve market
+            let mut market = Markets::<T>::get(market_id)
+                .ok_or(Error::<T>::MarketDoesNotExist)?;
+            Self::finalize(market_id, &mut market)?;
+            Self::put_market(market_id, market);
+            Self::debug_assert_solvent(market_id);
+
+            Ok(())
+        }
+
+        // Reclaim the collateral paid for shares of a market that was
+        // cancelled before resolving, such as one that expired without a report
+        #[weight = T::WeightInfo::claim_refund()]
+        #[transactional]
+        pub fn claim_refund(
+            origin,
+            market_id: T::Hash
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let market = Markets::<T>::get(market_id)
+                .ok_or(Error::<T>::MarketDoesNotExist)?;
+            ensure!(PendingRefunds::<T>::contains_key(market_id), Error::<T>::NothingToClaim);
+
+            let basis = CostBasis::<T>::get(market_id, &who);
+            let refund = Self::pay_refund(market_id, market.collateral_asset, &who, basis)?;
+            ensure!(!refund.is_zero(), Error::<T>::NothingToClaim);
+
+            Self::debug_assert_solvent(market_id);
+
+            Ok(())
+        }
+
+        // Refund the next `MaxRefundsPerCall` holders or liquidity providers
+        // of a cancelled market, or clear its leftover positions; anyone may
+        // push the refunds along until everybody is paid
+        #[weight = T::WeightInfo::process_refunds(T::MaxRefundsPerCall::get())]
+        #[transactional]
+        pub fn process_refunds(
+            origin,
+            market_id: T::Hash
+        ) -> DispatchResult {
+            ensure_signed(origin)?;
+
+            let market = Markets::<T>::get(market_id)
+                .ok_or(Error::<T>::MarketDoesNotExist)?;
+            ensure!(
+                market.status == MarketStatus::Cancelled && Self::refunds_pending(market_id),
+                Error::<T>::NothingToClaim
+            );
+
+            Self::do_process_refunds(market_id, market.collateral_asset)?;
+            Self::debug_assert_solvent(market_id);
+
+            Ok(())
+        }
+
+        // Redeem the shares of a resolved market against the pot
+        #[weight = T::WeightInfo::claim_winnings(T::MaxOutcomes::get())]
+        #[transactional]
+        pub fn claim_winnings(
+            origin,
+            market_id: T::Hash
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            Self::do_claim_winnings(who, market_id)?;
+            Self::debug_assert_solvent(market_id);
+
+            Ok(())
+        }
+
+        // Write off a position that resolved worthless, realizing its loss.
+        // Positions that still pay out go through `claim_winnings` instead.
+        #[weight = T::WeightInfo::settle_position(T::MaxOutcomes::get())]
+        #[transactional]
+        pub fn settle_position(
+            origin,
+            market_id: T::Hash
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            Self::do_settle_position(who, market_id)?;
+            Self::debug_assert_solvent(market_id);
+
+            Ok(())
+        }
+
+        // Claim from every listed market the caller has something to redeem
+        // in. Markets that cannot pay the caller are skipped.
+        #[weight = T::WeightInfo::redeem_all(market_ids.len() as u32)]
+        #[transactional]
+        pub fn redeem_all(
+            origin,
+            market_ids: BoundedVec<T::Hash, T::MaxRedeemBatch>
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            // Each claim commits or rolls back on its own, and a sweep that
+            // pays nothing rolls back as a whole
+            let mut total: BalanceOf<T> = Zero::zero();
+            for market_id in market_ids {
+                let paid = with_transaction(|| match Self::do_claim_winnings(who.clone(), market_id) {
+                    Ok(paid) => TransactionOutcome::Commit(paid),
+                    Err(_) => TransactionOutcome::Rollback(Zero::zero()),
+                });
+                Self::debug_assert_solvent(market_id);
+                total = total.saturating_add(paid);
+            }
+            ensure!(!total.is_zero(), Error::<T>::NothingToClaim);
+
+            // Emit event
+            Self::deposit_event(RawEvent::BatchRedeemed(who, total));
+
+            Ok(())
+        }
+
+        // Delete a settled market nobody has anything left to claim from once
+        // its lifetime has passed, collecting the reap incentive
+        #[weight = T::WeightInfo::reap_market()]
+        #[transactional]
+        pub fn reap_market(
+            origin,
+            market_id: T::Hash
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            // Retrieve market
+            let market = Markets::<T>::get(market_id)
+                .ok_or(Error::<T>::MarketDoesNotExist)?;
+            let now = system::Module::<T>::block_number();
+            ensure!(
+                now >= market.creation_block.saturating_add(T::MaxMarketLifetime::get()),
+                Error::<T>::MarketNotReapable
+            );
+
+            // Winnings, LP stakes and refunds must all have been collected
+            let settled = match market.status {
+                MarketStatus::Resolved => {
+                    Self::unclaimed_winnings(market_id, &market).is_zero()
+                        && LpIssuance::<T>::get(market_id).is_zero()
+                        && CreatorFees::<T>::get(market_id).is_zero()
+                }
+                MarketStatus::Cancelled => !Self::refunds_pending(market_id),
+                _ => return Err(Error::<T>::MarketNotReapable.into()),
+            };
+            ensure!(settled, Error::<T>::OutstandingClaims);
+
+            // Markets conditioned on this one still read its outcome
+            ensure!(
+                DependentMarkets::<T>::iter_prefix(market_id).next().is_none(),
+                Error::<T>::ConditionDependents
+            );
+
+            // The reaper sweeps the pot: the incentive plus any rounding dust
+            let pot = Self::market_account(market_id);
+            if market.collateral_asset != T::NativeAssetId::get() {
+                let dust = <T::Assets as fungibles::Inspect<_>>::balance(market.collateral_asset, &pot);
+                if !dust.is_zero() {
+                    Self::transfer_collateral(
+                        market.collateral_asset,
+                        &pot,
+                        &who,
+                        dust,
+                        ExistenceRequirement::AllowDeath
+                    )?;
+                }
+            }
+            let incentive = T::Currency::free_balance(&pot);
+            T::Currency::transfer(&pot, &who, incentive, ExistenceRequirement::AllowDeath)?;
+
+            // Resolved markets are still indexed under their creator and category
+            if market.status == MarketStatus::Resolved {
+                MarketCount::mutate(|count| *count = count.saturating_sub(1));
+                MarketsByCreator::<T>::mutate(&market.creator, |ids| ids.retain(|id| *id != market_id));
+                MarketsByCategory::<T>::mutate(market.category, |ids| ids.retain(|id| *id != market_id));
+            }
+
+            // Drop everything stored for the market
+            Self::remove_market(market_id);
+            for (holder, _) in Positions::<T>::drain_prefix(market_id) {
+                HeldMarkets::<T>::remove(&holder, market_id);
+            }
+            let _ = CostBasis::<T>::remove_prefix(market_id, None);
+            let _ = LpShares::<T>::remove_prefix(market_id, None);
+            TotalShares::<T>::remove(market_id);
+            TotalCostBasis::<T>::remove(market_id);
+            PendingRefunds::<T>::remove(market_id);
+            LpRefunds::<T>::remove(market_id);
+            CollectedFees::<T>::remove(market_id);
+            PayoutDust::<T>::remove(market_id);
+            CreatorFees::<T>::remove(market_id);
+            StaleMarkets::<T>::remove(market_id);
+            LpIssuance::<T>::remove(market_id);
+            Reserves::<T>::remove(market_id);
+            OracleCommittees::<T>::remove(market_id);
+            BackupOracles::<T>::remove(market_id);
+            PriceHistory::<T>::remove(market_id);
+
+            // Emit event
+            Self::deposit_event(RawEvent::MarketReaped(who, market_id, incentive));
+
+            Ok(())
+        }
+    }
+}
+
+// Storage Declarations
+decl_storage! {
+    trait Store for Module<T: Config> as FutarchyMarkets {
+        // Layout version of the pallet's storage; new chains start current
+        StorageVersion get(fn storage_version) build(|_: &GenesisConfig<T>| Releases::V1): Releases;
+
+        // Store all markets
+        Markets get(fn markets): map hasher(blake2_128_concat) T::Hash => Option<MarketOf<T>>;
+        
+        // Total number of markets
+        MarketCount get(fn market_count): u64;
+
+        // Number of stored markets per status, kept in step by `put_market`
+        StatusCounts get(fn status_count): map hasher(blake2_128_concat) MarketStatus => u64;
+
+        // Sum of `total_liquidity` over every stored market
+        TotalValueLocked get(fn total_value_locked): BalanceOf<T>;
+
+        // Whether trading and liquidity changes are frozen
+        Paused get(fn paused): bool;
+
+        // Accounts allowed to create markets under `CreationPolicy::WhitelistOnly`
+        ApprovedCreators get(fn approved_creators): map hasher(blake2_128_concat) T::AccountId => ();
+
+        // Markets created per account so far, folded into market ids
+        CreationNonce get(fn creation_nonce): map hasher(blake2_128_concat) T::AccountId => u64;
+
+        // Markets created per account, excluding cancelled ones
+        MarketsByCreator get(fn markets_by_creator): map hasher(blake2_128_concat) T::AccountId => BoundedVec<T::Hash, T::MaxMarketsPerCreator>;
+
+        // Markets listed per category, excluding cancelled ones
+        MarketsByCategory get(fn markets_by_category): map hasher(blake2_128_concat) MarketCategory => BoundedVec<T::Hash, T::MaxPerCategory>;
+
+        // Markets currently open for trading
+        ActiveMarkets get(fn active_markets): map hasher(blake2_128_concat) T::Hash => ();
+
+        // Outcome shares held per market and account
+        Positions get(fn positions): double_map hasher(blake2_128_concat) T::Hash, hasher(blake2_128_concat) T::AccountId => OutcomeShares<BalanceOf<T>>;
+
+        // Markets each account holds shares in, mirroring `Positions`
+        HeldMarkets get(fn held_markets): double_map hasher(blake2_128_concat) T::AccountId, hasher(blake2_128_concat) T::Hash => ();
+
+        // Total shares issued per outcome of each market
+        TotalShares get(fn total_shares): map hasher(blake2_128_concat) T::Hash => OutcomeShares<BalanceOf<T>>;
+
+        // Per-outcome share reserves of constant-product pools
+        Reserves get(fn reserves): map hasher(blake2_128_concat) T::Hash => Vec<BalanceOf<T>>;
+
+        // Net collateral each account paid for its shares of a market, used to
+        // refund conditional markets that are called off
+        CostBasis get(fn cost_basis): double_map hasher(blake2_128_concat) T::Hash, hasher(blake2_128_concat) T::AccountId => BalanceOf<T>;
+
+        // Sum of `CostBasis` over every account of a market
+        TotalCostBasis get(fn total_cost_basis): map hasher(blake2_128_concat) T::Hash => BalanceOf<T>;
+
+        // Refunds left to pay out of cancelled markets (collateral set aside,
+        // cost basis it covers); both shrink as holders are paid
+        PendingRefunds get(fn pending_refunds): map hasher(blake2_128_concat) T::Hash => Option<(BalanceOf<T>, BalanceOf<T>)>;
+
+        // What cancelled markets left their liquidity providers (collateral
+        // set aside, LP shares it covers); both shrink as providers are paid
+        LpRefunds get(fn lp_refunds): map hasher(blake2_128_concat) T::Hash => Option<(BalanceOf<T>, BalanceOf<T>)>;
+
+        // Markets to expire at a block if still unreported by then
+        ExpiringMarkets get(fn expiring_markets): map hasher(twox_64_concat) T::BlockNumber => BoundedVec<T::Hash, T::MaxAutoResolvePerBlock>;
+
+        // Recent prices of outcome 1 (YES, LONG) after trades, oldest first
+        PriceHistory get(fn price_history): map hasher(blake2_128_concat) T::Hash => BoundedVec<(T::BlockNumber, FixedU128), T::MaxHistoryPoints>;
+
+        // Rounding dust accumulated by the winning claims of each market
+        PayoutDust get(fn payout_dust): map hasher(blake2_128_concat) T::Hash => FixedU128;
+
+        // Treasury subsidy held in each market's pot, returned once settled
+        Subsidies get(fn subsidies): map hasher(blake2_128_concat) T::Hash => BalanceOf<T>;
+
+        // Trading fees held in each market's pot for its liquidity providers
+        CollectedFees get(fn collected_fees): map hasher(blake2_128_concat) T::Hash => BalanceOf<T>;
+
+        // Decision markets and their conditional pair
+        DecisionMarkets get(fn decision_markets): map hasher(blake2_128_concat) T::Hash => Option<DecisionMarketOf<T>>;
+
+        // Oracles resolving a market by vote, with their weights, and the
+        // number of submissions needed
+        OracleCommittees get(fn oracle_committee): map hasher(blake2_128_concat) T::Hash => Option<(BoundedVec<(T::AccountId, u32), T::MaxOracles>, u32)>;
+
+        // Oracles taking over in order from a market's oracle when it misses
+        // its reporting window
+        BackupOracles get(fn backup_oracles): map hasher(blake2_128_concat) T::Hash => BoundedVec<T::AccountId, T::MaxOracles>;
+
+        // Outcome submitted per market and committee member
+        ResolutionVotes get(fn resolution_votes): double_map hasher(blake2_128_concat) T::Hash, hasher(blake2_128_concat) T::AccountId => Option<MarketOutcome>;
+
+        // Open dispute per market (disputer, bond)
+        Disputes get(fn disputes): map hasher(blake2_128_concat) T::Hash => Option<(T::AccountId, BalanceOf<T>)>;
+
+        // LP shares held per market and account
+        LpShares get(fn lp_shares): double_map hasher(blake2_128_concat) T::Hash, hasher(blake2_128_concat) T::AccountId => BalanceOf<T>;
+
+        // Total LP shares issued per market
+        LpIssuance get(fn lp_issuance): map hasher(blake2_128_concat) T::Hash => BalanceOf<T>;
+
+        // Markets flagged by the off-chain worker as past their grace period
+        StaleMarkets get(fn stale_markets): map hasher(blake2_128_concat) T::Hash => ();
+
+        // Creator fees accrued per market and not yet claimed
+        CreatorFees get(fn creator_fees): map hasher(blake2_128_concat) T::Hash => BalanceOf<T>;
+
+        // Accounts allowed to trade for an owner, keyed by (owner, delegate)
+        TradingDelegates get(fn trading_delegates): double_map hasher(blake2_128_concat) T::AccountId, hasher(blake2_128_concat) T::AccountId => ();
+
+        // Collateral taken in by all markets so far this block
+        BlockInflow get(fn block_inflow): BalanceOf<T>;
+
+        // Block each account last created markets in
+        LastCreation get(fn last_creation): map hasher(blake2_128_concat) T::AccountId => Option<T::BlockNumber>;
+
+        // Markets promoted to front-ends by the curation origin, dropped
+        // once they resolve or are cancelled
+        Featured get(fn featured): map hasher(blake2_128_concat) T::Hash => ();
+
+        // Block each market was finally resolved in, starting its
+        // `LpWithdrawalDelay`
+        SettledAt get(fn settled_at): map hasher(blake2_128_concat) T::Hash => Option<T::BlockNumber>;
+
+        // Profit each account realized from sales, claims and written-off
+        // positions, net of the cost basis they retired
+        RealizedPnl get(fn realized_pnl): map hasher(blake2_128_concat) T::AccountId => i128;
+
+        // Accounts with the highest `RealizedPnl`, best first
+        TopTraders get(fn leaderboard): BoundedVec<(T::AccountId, i128), T::MaxLeaderboard>;
+
+        // Markets conditioned on each market's outcome, which keep it from
+        // being reaped
+        DependentMarkets get(fn dependent_markets): double_map hasher(blake2_128_concat) T::Hash, hasher(blake2_128_concat) T::Hash => ();
+    }
+    add_extra_genesis {
+        // Markets bootstrapped at genesis as (creator, type, mechanism, trading
+        // end, liquidity parameter, scalar bounds, outcome count)
+        config(initial_markets): Vec<(
+            T::AccountId,
+            MarketType,
+            MarketMechanism,
+            T::BlockNumber,
+            BalanceOf<T>,
+            Option<(i128, i128)>,
+            Option<u32>
+        )>;
+        build(|config: &GenesisConfig<T>| {
+            // Creators pay deposits and subsidies exactly as if they had called
+            // `create_market`, so their balances must be funded first
+            for (index, spec) in config.initial_markets.iter().cloned().enumerate() {
+                let (creator, market_type, mechanism, trading_end, liquidity_parameter, scalar_bounds, outcome_count) = spec;
+                let market_id = Module::<T>::genesis_market_id(index as u32, &creator);
+                Module::<T>::do_create_market(
+                    &creator,
+                    market_id,
+                    market_type,
+                    mechanism,
+                    trading_end,
+                    liquidity_parameter,
+                    Zero::zero(),
+                    scalar_bounds,
+                    outcome_count,
+                    Default::default(),
+                    None,
+                    T::NativeAssetId::get(),
+                    MarketCategory::Other,
+                    BoundedVec::default(),
+                    BoundedVec::default()
+                )
+                .expect("genesis markets must be valid");
+            }
+        });
+    }
+}
+
+// Event Declarations
+decl_event!(
+    pub enum Event<T> where
+        AccountId = <T as frame_system::Config>::AccountId,
+        Hash = <T as frame_system::Config>::Hash,
+        BlockNumber = <T as frame_system::Config>::BlockNumber,
+        Balance = BalanceOf<T>
+    {
+        // Market created (creator, market, type, trading end, collateral label)
+        MarketCreated(AccountId, Hash, MarketType, BlockNumber, Vec<u8>),
+        // Markets created together by one batch (creator, markets)
+        MarketsBatchCreated(AccountId, Vec<Hash>),
+        // Resolved market re-run (creator, source market, new market)
+        MarketCloned(AccountId, Hash, Hash),
+        // Market opened for trading
+        MarketActivated(AccountId, Hash),
+        // Decision market created (creator, decision, market if enacted, market if rejected)
+        DecisionMarketCreated(AccountId, Hash, Hash, Hash),
+        // Decision settled (decision, whether enacting the policy is recommended)
+        PolicyRecommended(Hash, bool),
+        // Call bound to an enacted decision dispatched (decision, result)
+        ProposalExecuted(Hash, DispatchResult),
+        // Oracle of a market changed (market, new oracle)
+        OracleSet(Hash, AccountId),
+        // Resolution committee of a market appointed (market, oracles and weights, threshold)
+        OracleCommitteeSet(Hash, Vec<(AccountId, u32)>, u32),
+        // Backup oracles of a market lined up in order
+        BackupOraclesSet(Hash, Vec<AccountId>),
+        // Reporting passed to the next oracle in line after the one before it
+        // missed its window (market, new oracle)
+        OracleRotated(Hash, AccountId),
+        // Winnings of several markets claimed in one call (holder, total paid)
+        BatchRedeemed(AccountId, Balance),
+        // Market moved between statuses (market, old, new), alongside the
+        // event specific to the transition
+        MarketStatusChanged(Hash, MarketStatus, MarketStatus),
+        // Committee member voted on an outcome (oracle, market, outcome)
+        ResolutionSubmitted(AccountId, Hash, MarketOutcome),
+        // Trading window of a market extended (market, new trading end)
+        TradingExtended(Hash, BlockNumber),
+        // Market outcome reported (oracle, market, outcome, liquidity in the pot)
+        MarketResolved(AccountId, Hash, MarketOutcome, Balance),
+        // Reported outcome disputed (disputer, market)
+        MarketDisputed(AccountId, Hash),
+        // Reported outcome became final
+        MarketFinalized(Hash),
+        // Market still open past its trading end and grace period
+        MarketStale(Hash),
+        // Dispute ruled on (market, final outcome, whether the report stood)
+        DisputeResolved(Hash, MarketOutcome, bool),
+        // Creation deposit returned to the creator
+        DepositRefunded(AccountId, Balance),
+        // Market cancelled and deposit returned
+        MarketCancelled(AccountId, Hash),
+        // Trader refunded at cost by a called-off market (trader, market, amount)
+        PositionRefunded(AccountId, Hash, Balance),
+        // Batch of refunds paid (market, holders refunded, cost basis still owed)
+        RefundsProgress(Hash, u32, Balance),
+        // Per-account position cap of a market changed
+        PositionCapSet(Hash, Option<Balance>),
+        // Collateral cap of a market changed
+        CollateralCapSet(Hash, Option<Balance>),
+        // Share cap of a market changed
+        ShareCapSet(Hash, Option<Balance>),
+        // Auto-activation flag of a market changed
+        AutoActivateSet(Hash, bool),
+        // Implied decimal places of a scalar market changed
+        ScalarScaleSet(Hash, u8),
+        // Payout curve of a scalar market changed
+        PayoutCurveSet(Hash, ScalarCurve),
+        // Creator's cut of a market's trades changed
+        CreatorFeeSet(Hash, Permill),
+        // Oracle ruled out an outcome of a categorical market (market, outcome)
+        OutcomeEliminated(Hash, u32),
+        // Creator withdrew the fees accrued by a market (creator, market, amount)
+        CreatorFeesClaimed(AccountId, Hash, Balance),
+        // Treasury funds added to a market's liquidity (market, amount)
+        MarketSubsidized(Hash, Balance),
+        // Leftover subsidy handed back to the treasury (market, amount)
+        SubsidyReturned(Hash, Balance),
+        // Market cancelled by the force origin
+        MarketForceCancelled(Hash),
+        // Market replaced by the force origin (source, replacement)
+        MarketMigrated(Hash, Hash),
+        // Trading paused or resumed
+        PausedSet(bool),
+        // Market promoted to front-ends or no longer promoted
+        FeaturedSet(Hash, bool),
+        // Account allowed to create markets
+        CreatorApproved(AccountId),
+        // Account no longer allowed to create markets
+        CreatorRemoved(AccountId),
+        // Settled market deleted from storage (reaper, market, incentive paid)
+        MarketReaped(AccountId, Hash, Balance),
+        // Trading halted ahead of resolution (closer, market)
+        MarketClosed(AccountId, Hash),
+        // Market cancelled after its oracle failed to report in time
+        MarketExpired(Hash),
+        // Shares bought (buyer, market, outcome, shares, cost, fee)
+        SharesBought(AccountId, Hash, u32, Balance, Balance, Balance),
+        // Shares sold (seller, market, outcome, shares, proceeds, fee)
+        SharesSold(AccountId, Hash, u32, Balance, Balance, Balance),
+        // Shares moved between accounts (from, to, market, outcome, shares)
+        SharesTransferred(AccountId, AccountId, Hash, u32, Balance),
+        // Collateral split into complete sets (account, market, sets)
+        CollateralSplit(AccountId, Hash, Balance),
+        // Complete sets merged back into collateral (account, market, sets, fee)
+        SharesMerged(AccountId, Hash, Balance, Balance),
+        // Protocol fee sent to the treasury (market, amount)
+        ProtocolFeeCollected(Hash, Balance),
+        // Liquidity added (provider, market, amount, LP shares minted)
+        LiquidityAdded(AccountId, Hash, Balance, Balance),
+        // Liquidity removed (provider, market, LP shares burned, payout incl. fees)
+        LiquidityRemoved(AccountId, Hash, Balance, Balance),
+        // Winnings claimed (holder, market, payout)
+        WinningsClaimed(AccountId, Hash, Balance),
+        // Worthless position written off (holder, market, cost basis lost)
+        PositionSettled(AccountId, Hash, Balance),
+        // Rounding dust of a market's payouts paid out (market, recipient, amount)
+        PayoutDustReleased(Hash, AccountId, Balance),
+        // Trading delegate approved (owner, delegate)
+        TradingDelegateAdded(AccountId, AccountId),
+        // Trading delegate revoked (owner, delegate)
+        TradingDelegateRemoved(AccountId, AccountId),
+        // Question text of a market replaced before activation
+        MetadataUpdated(Hash),
+        // Markets a combinatorial market is conditioned on changed
+        ParentConditionsSet(Hash),
+    }
+);
+
+// Error Declarations
+decl_error! {
+    pub enum Error for Module<T: Config> {
+        // Market does not exist
+        MarketDoesNotExist,
+        // Market is not in a state that can be resolved
+        MarketNotResolvable,
+        // Insufficient funds for market creation
+        InsufficientFunds,
+        // Market counter would overflow
+        MarketCountOverflow,
+        // Caller is not the creator of the market
+        NotMarketCreator,
+        // Market is not in a state that can be activated
+        MarketNotActivatable,
+        // Market is not in a state that can be cancelled
+        MarketNotCancellable,
+        // Trading end must be after the creation block
+        InvalidTradingEnd,
+        // Market cannot be resolved before its trading window ends
+        TradingStillOpen,
+        // Market still has shares outstanding
+        OutstandingShares,
+        // Market is not open for trading
+        MarketNotActive,
+        // Trading window of the market has ended
+        TradingClosed,
+        // Amount must be greater than zero
+        InvalidAmount,
+        // Account holds fewer shares than requested
+        InsufficientShares,
+        // Liquidity parameter must be greater than zero
+        InvalidLiquidityParameter,
+        // Market maker pricing overflowed or was undefined
+        PricingFailed,
+        // Constant-product markets need a non-zero seed
+        InsufficientSeedLiquidity,
+        // Liquidity cannot be withdrawn from a resolved market yet
+        LiquidityLocked,
+        // Account holds fewer LP shares than requested
+        InsufficientLpShares,
+        // Outcome index is out of range for the market
+        InvalidOutcome,
+        // Market has not been resolved
+        MarketNotResolved,
+        // Account has no winnings to claim
+        NothingToClaim,
+        // Outcome does not match the market type
+        OutcomeTypeMismatch,
+        // Scalar bounds are missing, inverted, or given for a non-scalar market
+        InvalidScalarBounds,
+        // Categorical markets need at least two outcomes
+        TooFewOutcomes,
+        // Categorical market exceeds the configured outcome limit
+        TooManyOutcomes,
+        // Metadata exceeds the configured maximum length
+        MetadataTooLong,
+        // Creator already has the maximum number of markets
+        TooManyMarkets,
+        // Caller is not the oracle of the market
+        NotAuthorizedOracle,
+        // Market has no reported outcome that can be disputed
+        MarketNotDisputable,
+        // Dispute window of the market has passed
+        DisputeWindowClosed,
+        // Dispute window of the market is still open
+        DisputeWindowOpen,
+        // Market is not under dispute
+        MarketNotDisputed,
+        // A market with the generated id already exists
+        MarketIdCollision,
+        // Decision market does not exist
+        DecisionDoesNotExist,
+        // Decision market already has a recommendation
+        DecisionAlreadySettled,
+        // Decision has not been settled in favour of enacting
+        DecisionNotEnacted,
+        // Decision has no call left to execute
+        NothingToExecute,
+        // Bound call exceeds the configured maximum weight
+        CallTooHeavy,
+        // Creators may not act as the oracle of their own market
+        CreatorCannotResolve,
+        // Market holds less liquidity than required to open for trading
+        InsufficientLiquidity,
+        // New trading end does not lie after the current one
+        TradingEndNotExtended,
+        // New trading end lies further ahead than the configured maximum
+        ExtensionTooLong,
+        // Market is resolved or cancelled, so its shares can no longer move
+        MarketSettled,
+        // Trade would cost more, or pay out less, than the caller's limit
+        SlippageExceeded,
+        // Caller is neither the creator nor the oracle of the market
+        NotCreatorOrOracle,
+        // Trading would last longer than the maximum market lifetime
+        LifetimeTooLong,
+        // Market is not settled, or its lifetime has not passed yet
+        MarketNotReapable,
+        // Holders or liquidity providers still have funds to claim
+        OutstandingClaims,
+        // Market is not past its grace period unresolved, or already flagged
+        MarketNotStale,
+        // Balance or liquidity update overflowed its type
+        ArithmeticOverflow,
+        // Purchase would take the buyer's position past the market's cap
+        PositionLimitExceeded,
+        // Category already lists the maximum number of markets
+        TooManyInCategory,
+        // Committee threshold, weights or members are invalid
+        InvalidCommittee,
+        // Oracle already submitted an outcome for the market
+        DuplicateSubmission,
+        // Trading is paused
+        Paused,
+        // Trade is below the minimum number of shares
+        TradeTooSmall,
+        // Scalar market uses more decimal places than allowed, or than its
+        // bounds can be given at
+        ScaleTooLarge,
+        // Creator fee is above `MaxCreatorFee`
+        CreatorFeeTooHigh,
+        // Outcome was eliminated, or eliminating it would leave none
+        OutcomeEliminated,
+        // Deposit would take the market pot above its collateral cap
+        CollateralCapReached,
+        // Market cannot take a subsidy, or the subsidy is too small to matter
+        NotSubsidizable,
+        // Caller may not create markets under the creation policy
+        NotAuthorizedCreator,
+        // Caller is not an approved trading delegate of the owner
+        NotTradingDelegate,
+        // Delegate is the owner themselves, or already approved
+        InvalidDelegate,
+        // As many markets as `MaxActiveMarkets` are already open for trading
+        TooManyActiveMarkets,
+        // Market has opened for trading, so its question can no longer change
+        MarketLocked,
+        // Condition names the market itself, a repeat, or a non-binary market
+        InvalidCondition,
+        // A market this one is conditioned on has not resolved yet
+        ConditionsPending,
+        // Market has not been in `Created` for `MinCreatedPeriod` yet
+        ActivationTooEarly,
+        // Markets already took in `MaxCollateralInflowPerBlock` this block
+        InflowLimitReached,
+        // Account created a market less than `CreatorCooldown` blocks ago
+        CreationCooldown,
+        // Migration spec changes the type, mechanism, liquidity parameter,
+        // outcomes or collateral of the market, or is otherwise invalid
+        MigrationMismatch,
+        // Collateral label is longer than `MaxDenomLen`
+        DenomTooLong,
+        // Scalar range is wider than `MaxScalarRange`
+        ScalarRangeTooLarge,
+        // Other markets are conditioned on this market's outcome
+        ConditionDependents,
+        // Outcome labels given for a market do not match its outcome count
+        LabelCountMismatch,
+        // Outcome label is longer than `MaxLabelLen`
+        LabelTooLong,
+        // Minting would take an outcome past the market's share cap
+        ShareCapReached,
+        // Account holds no shares of the market
+        NoPosition,
+        // Position is still worth something and must be claimed instead
+        PositionPaysOut,
+        // Market has more holders, providers or dependent markets than the
+        // migration was weighted for
+        MigrationTooLarge,
+    }
+}
+
+// Module Implementation
+impl<T: Config> frame_support::unsigned::ValidateUnsigned for Module<T> {
+    type Call = Call<T>;
+
+    // Only accept flags that would succeed, once per market
+    fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+        let market_id = match call {
+            Call::flag_stale_market(market_id) => market_id,
+            _ => return InvalidTransaction::Call.into(),
+        };
+        let now = system::Module::<T>::block_number();
+        let stale = Markets::<T>::get(market_id).map_or(false, |market| Self::is_stale(&market, now));
+        if !stale || StaleMarkets::<T>::contains_key(market_id) {
+            return InvalidTransaction::Stale.into();
+        }
+        ValidTransaction::with_tag_prefix("FutarchyStaleMarket")
+            .priority(T::UnsignedPriority::get())
+            .and_provides(market_id)
+            .longevity(5)
+            .propagate(true)
+            .build()
+    }
+}
+
+impl<T: Config> Module<T> {
+    // Redeem `who`'s shares of a resolved market, returning what they were paid
+    fn do_claim_winnings(who: T::AccountId, market_id: T::Hash) -> Result<BalanceOf<T>, DispatchError> {
+        // Retrieve market
+        let mut market = Markets::<T>::get(market_id)
+            .ok_or(Error::<T>::MarketDoesNotExist)?;
+
+        // The first claim after the dispute window finalizes the outcome
+        if market.status == MarketStatus::Proposed {
+            Self::finalize(market_id, &mut market)?;
+        }
+        ensure!(market.status == MarketStatus::Resolved, Error::<T>::MarketNotResolved);
+
+        // Invalid markets, and combinatorial markets whose conditions did
+        // not hold, hand traders back what they paid for their shares
+        let conditions_met = Self::parent_conditions_met(&market)?;
+        if market.resolved_outcome == Some(MarketOutcome::Invalid) || !conditions_met {
+            let refund = Self::invalid_refund(market_id, &market, &who);
+            ensure!(!refund.is_zero(), Error::<T>::NothingToClaim);
+            Self::transfer_collateral(
+                market.collateral_asset,
+                &Self::market_account(market_id),
+                &who,
+                refund,
+                ExistenceRequirement::AllowDeath
+            )?;
+            market.total_liquidity = market.total_liquidity.saturating_sub(refund);
+
+            let position = Positions::<T>::take(market_id, &who);
+            TotalShares::<T>::mutate(market_id, |totals| {
+                for index in 0..market.outcome_count as usize {
+                    let _ = totals.burn(index, position.balance_of(index));
+                }
+            });
+            HeldMarkets::<T>::remove(&who, market_id);
+            let basis = CostBasis::<T>::take(market_id, &who);
+            TotalCostBasis::<T>::mutate(market_id, |total| *total = total.saturating_sub(basis));
+            Self::record_pnl(&who, refund, basis);
+            Self::put_market(market_id, market);
+
+            Self::deposit_event(RawEvent::PositionRefunded(who, market_id, refund));
+            return Ok(refund);
+        }
+        let payouts = Self::outcome_payouts(&market).ok_or(Error::<T>::MarketNotResolved)?;
+
+        // Every share pays out its outcome's settlement value
+        let position = Positions::<T>::get(market_id, &who);
+        let payout = Self::position_value(&position, &payouts);
+        ensure!(!payout.is_zero(), Error::<T>::NothingToClaim);
+
+        let mut totals = TotalShares::<T>::get(market_id);
+        for (index, _) in payouts.iter().enumerate() {
+            totals.burn(index, position.balance_of(index)).ok_or(Error::<T>::InsufficientShares)?;
+        }
+
+        // Payouts round down; the last winning claim releases the dust
+        let (released, carried) = Self::release_dust(market_id, &position, &totals, &payouts);
+        let rounding = T::PayoutRounding::get();
+        let payout = match rounding {
+            PayoutRounding::DustToLastClaimant => payout.saturating_add(released),
+            PayoutRounding::DustToTreasury => payout,
+        };
+        market.total_liquidity = market.total_liquidity.saturating_sub(payout);
+
+        // Pay the winner out of the market pot
+        let pot = Self::market_account(market_id);
+        Self::transfer_collateral(
+            market.collateral_asset,
+            &pot,
+            &who,
+            payout,
+            ExistenceRequirement::AllowDeath
+        )?;
+        if rounding == PayoutRounding::DustToTreasury && !released.is_zero() {
+            Self::transfer_collateral(
+                market.collateral_asset,
+                &pot,
+                &T::TreasuryAccount::get(),
+                released,
+                ExistenceRequirement::AllowDeath
+            )?;
+            market.total_liquidity = market.total_liquidity.saturating_sub(released);
+        }
+        if !released.is_zero() {
+            let recipient = match rounding {
+                PayoutRounding::DustToLastClaimant => who.clone(),
+                PayoutRounding::DustToTreasury => T::TreasuryAccount::get(),
+            };
+            Self::deposit_event(RawEvent::PayoutDustReleased(market_id, recipient, released));
+        }
+        if carried.is_zero() {
+            PayoutDust::<T>::remove(market_id);
+        } else {
+            PayoutDust::<T>::insert(market_id, carried);
+        }
+
+        // The whole position is settled, losing shares included
+        Positions::<T>::remove(market_id, &who);
+        HeldMarkets::<T>::remove(&who, market_id);
+        let basis = CostBasis::<T>::take(market_id, &who);
+        TotalCostBasis::<T>::mutate(market_id, |total| *total = total.saturating_sub(basis));
+        Self::record_pnl(&who, payout, basis);
+        TotalShares::<T>::insert(market_id, totals);
+        Self::put_market(market_id, market);
+
+        // Emit event
+        Self::deposit_event(RawEvent::WinningsClaimed(who, market_id, payout));
+
+        Ok(payout)
+    }
+
+    // Write off the worthless position of `who` in a resolved market,
+    // booking its whole cost basis as a realized loss
+    fn do_settle_position(who: T::AccountId, market_id: T::Hash) -> DispatchResult {
+        // Retrieve market
+        let mut market = Markets::<T>::get(market_id)
+            .ok_or(Error::<T>::MarketDoesNotExist)?;
+        if market.status == MarketStatus::Proposed {
+            Self::finalize(market_id, &mut market)?;
+        }
+        ensure!(market.status == MarketStatus::Resolved, Error::<T>::MarketNotResolved);
+
+        let position = Positions::<T>::get(market_id, &who);
+        ensure!(!position.is_empty(), Error::<T>::NoPosition);
+
+        // Anything a claim would still pay out has to be claimed instead
+        let refunded = market.resolved_outcome == Some(MarketOutcome::Invalid)
+            || !Self::parent_conditions_met(&market)?;
+        if refunded {
+            ensure!(Self::invalid_refund(market_id, &market, &who).is_zero(), Error::<T>::PositionPaysOut);
+        } else {
+            let payouts = Self::outcome_payouts(&market).ok_or(Error::<T>::MarketNotResolved)?;
+            ensure!(Self::position_value(&position, &payouts).is_zero(), Error::<T>::PositionPaysOut);
+            // Fractions of a unit the shares were worth go to the dust
+            let remainder = Self::rounding_remainder(&position, &payouts);
+            if !remainder.is_zero() {
+                PayoutDust::<T>::mutate(market_id, |dust| *dust = dust.saturating_add(remainder));
+            }
+        }
+
+        // Retire the shares and the basis they were bought with
+        TotalShares::<T>::mutate(market_id, |totals| {
+            for index in 0..market.outcome_count as usize {
+                let _ = totals.burn(index, position.balance_of(index));
+            }
+        });
+        Positions::<T>::remove(market_id, &who);
+        HeldMarkets::<T>::remove(&who, market_id);
+        let basis = CostBasis::<T>::take(market_id, &who);
+        TotalCostBasis::<T>::mutate(market_id, |total| *total = total.saturating_sub(basis));
+        Self::record_pnl(&who, Zero::zero(), basis);
+        Self::put_market(market_id, market);
+
+        // Emit event
+        Self::deposit_event(RawEvent::PositionSettled(who, market_id, basis));
+
+        Ok(())
+    }
+
+    // Book `received` against the `basis` it retires in the realized profit
+    // of `who`, and move them on the leaderboard. Only accounts whose profit
+    // changes are re-ranked, so an account pushed off the board stays off
+    // until its next claim, sale or settlement.
+    fn record_pnl(who: &T::AccountId, received: BalanceOf<T>, basis: BalanceOf<T>) {
+        let signed = |amount: BalanceOf<T>| i128::try_from(amount.saturated_into::<u128>()).unwrap_or(i128::MAX);
+        let pnl = RealizedPnl::<T>::mutate(who, |pnl| {
+            *pnl = pnl.saturating_add(signed(received).saturating_sub(signed(basis)));
+            *pnl
+        });
+
+        let mut top = TopTraders::<T>::get().into_inner();
+        top.retain(|(account, _)| account != who);
+        let rank = top.iter().position(|(_, entry)| *entry < pnl).unwrap_or(top.len());
+        top.insert(rank, (who.clone(), pnl));
+        top.truncate(T::MaxLeaderboard::get() as usize);
+        TopTraders::<T>::put(BoundedVec::try_from(top).unwrap_or_default());
+    }
+
+    // Buy shares for `who`, who pays for them out of their own account
+    fn do_buy_shares(
+        who: T::AccountId,
+        market_id: T::Hash,
+        outcome: u32,
+        amount: BalanceOf<T>,
+        max_cost: Option<BalanceOf<T>>
+    ) -> DispatchResult {
+        Self::ensure_not_paused()?;
+        ensure!(!amount.is_zero(), Error::<T>::InvalidAmount);
+        ensure!(amount >= T::MinTradeAmount::get(), Error::<T>::TradeTooSmall);
+
+        // Retrieve market
+        let mut market = Markets::<T>::get(market_id)
+            .ok_or(Error::<T>::MarketDoesNotExist)?;
+        Self::ensure_tradable(&market)?;
+        ensure!(outcome < market.outcome_count, Error::<T>::InvalidOutcome);
+        ensure!(!Self::is_eliminated(&market, outcome), Error::<T>::OutcomeEliminated);
+
+        // No account may hold more of one outcome than the cap
+        let index = outcome as usize;
+        let held = Positions::<T>::get(market_id, &who).balance_of(index);
+        Self::ensure_within_position_cap(&market, held, amount)?;
+
+        // Price the trade against the market maker
+        let (cost, reserves) = Self::quote_buy(market_id, &market, index, amount)?;
+        let fee = T::TradingFee::get().mul_floor(cost);
+        let protocol_fee = T::ProtocolFee::get().mul_floor(cost);
+        let creator_fee = market.creator_fee.mul_floor(cost);
+        let into_pot = cost
+            .checked_add(&fee)
+            .and_then(|total| total.checked_add(&creator_fee))
+            .ok_or(Error::<T>::ArithmeticOverflow)?;
+        if let Some(max_cost) = max_cost {
+            ensure!(
+                into_pot.checked_add(&protocol_fee).ok_or(Error::<T>::ArithmeticOverflow)? <= max_cost,
+                Error::<T>::SlippageExceeded
+            );
+        }
+        let total_liquidity = market.total_liquidity
+            .checked_add(&cost)
+            .ok_or(Error::<T>::ArithmeticOverflow)?;
+        Self::ensure_within_collateral_cap(&market, total_liquidity)?;
+        Self::ensure_within_share_cap(
+            &market,
+            TotalShares::<T>::get(market_id).balance_of(index).saturating_add(amount)
+        )?;
+        let inflow = Self::checked_inflow(into_pot)?;
+        let fees = CollectedFees::<T>::get(market_id)
+            .checked_add(&fee)
+            .ok_or(Error::<T>::ArithmeticOverflow)?;
+
+        // Mint the purchased shares
+        CollectedFees::<T>::insert(market_id, fees);
+        BlockInflow::<T>::put(inflow);
+        Self::accrue_creator_fee(market_id, creator_fee);
+        Positions::<T>::mutate(market_id, &who, |position| position.mint(index, amount));
+        HeldMarkets::<T>::insert(&who, market_id, ());
+        TotalShares::<T>::mutate(market_id, |totals| totals.mint(index, amount));
+        CostBasis::<T>::mutate(market_id, &who, |basis| *basis = basis.saturating_add(cost));
+        TotalCostBasis::<T>::mutate(market_id, |total| *total = total.saturating_add(cost));
+        market.total_liquidity = total_liquidity;
+
+        // Store updated market
+        let asset = market.collateral_asset;
+        if let Some(reserves) = reserves {
+            Reserves::<T>::insert(market_id, reserves);
+        }
+        Self::put_market(market_id, market);
+        Self::record_price(market_id);
+
+        // Move collateral and the trading fee into the market pot
+        Self::transfer_collateral(
+            asset,
+            &who,
+            &Self::market_account(market_id),
+            into_pot,
+            ExistenceRequirement::KeepAlive
+        )?;
+        Self::pay_protocol_fee(market_id, asset, &who, protocol_fee)?;
+
+        // Emit event
+        Self::deposit_market_event(market_id, RawEvent::SharesBought(who, market_id, outcome, amount, cost, fee));
+
+        Self::debug_assert_solvent(market_id);
+
+        Ok(())
+    }
+
+    // Sell shares held by `who`, paying the proceeds back to them
+    fn do_sell_shares(
+        who: T::AccountId,
+        market_id: T::Hash,
+        outcome: u32,
+        amount: BalanceOf<T>,
+        min_proceeds: Option<BalanceOf<T>>
+    ) -> DispatchResult {
+        Self::ensure_not_paused()?;
+        ensure!(!amount.is_zero(), Error::<T>::InvalidAmount);
+        ensure!(amount >= T::MinTradeAmount::get(), Error::<T>::TradeTooSmall);
+
+        // Retrieve market
+        let mut market = Markets::<T>::get(market_id)
+            .ok_or(Error::<T>::MarketDoesNotExist)?;
+        Self::ensure_tradable(&market)?;
+        ensure!(outcome < market.outcome_count, Error::<T>::InvalidOutcome);
+        ensure!(!Self::is_eliminated(&market, outcome), Error::<T>::OutcomeEliminated);
+
+        // Burn the seller's shares
+        let index = outcome as usize;
+        let mut position = Positions::<T>::get(market_id, &who);
+        position.burn(index, amount).ok_or(Error::<T>::InsufficientShares)?;
+        let mut totals = TotalShares::<T>::get(market_id);
+        totals.burn(index, amount).ok_or(Error::<T>::InsufficientShares)?;
+
+        // Price the trade against the market maker
+        let (proceeds, reserves) = Self::quote_sell(market_id, &market, index, amount)?;
+        let fee = T::TradingFee::get().mul_floor(proceeds);
+        let protocol_fee = T::ProtocolFee::get().mul_floor(proceeds);
+        let creator_fee = market.creator_fee.mul_floor(proceeds);
+        let net = proceeds
+            .checked_sub(&fee)
+            .and_then(|net| net.checked_sub(&protocol_fee))
+            .and_then(|net| net.checked_sub(&creator_fee))
+            .ok_or(Error::<T>::ArithmeticOverflow)?;
+        if let Some(min_proceeds) = min_proceeds {
+            ensure!(net >= min_proceeds, Error::<T>::SlippageExceeded);
+        }
+        market.total_liquidity = market.total_liquidity
+            .checked_sub(&proceeds)
+            .ok_or(Error::<T>::ArithmeticOverflow)?;
+        let fees = CollectedFees::<T>::get(market_id)
+            .checked_add(&fee)
+            .ok_or(Error::<T>::ArithmeticOverflow)?;
+
+        // Store updated state
+        CollectedFees::<T>::insert(market_id, fees);
+        Self::accrue_creator_fee(market_id, creator_fee);
+        if position.is_empty() {
+            HeldMarkets::<T>::remove(&who, market_id);
+        }
+        Positions::<T>::insert(market_id, &who, position);
+        TotalShares::<T>::insert(market_id, totals);
+        let basis = CostBasis::<T>::get(market_id, &who);
+        let remaining = basis.saturating_sub(proceeds);
+        CostBasis::<T>::insert(market_id, &who, remaining);
+        TotalCostBasis::<T>::mutate(market_id, |total| *total = total.saturating_sub(basis - remaining));
+        // Profit is what the seller keeps once every fee is taken
+        Self::record_pnl(&who, net, basis - remaining);
+        let asset = market.collateral_asset;
+        if let Some(reserves) = reserves {
+            Reserves::<T>::insert(market_id, reserves);
+        }
+        Self::put_market(market_id, market);
+        Self::record_price(market_id);
+
+        // Pay the seller out of the market pot, keeping the fees behind
+        let pot = Self::market_account(market_id);
+        Self::transfer_collateral(asset, &pot, &who, net, ExistenceRequirement::AllowDeath)?;
+        Self::pay_protocol_fee(market_id, asset, &pot, protocol_fee)?;
+
+        // Emit event
+        Self::deposit_market_event(market_id, RawEvent::SharesSold(who, market_id, outcome, amount, proceeds, fee));
+
+        Self::debug_assert_solvent(market_id);
+
+        Ok(())
+    }
+
+    // Whether `market` is still awaiting a report past the grace period
+    // after its trading end
+    fn is_stale(market: &MarketOf<T>, now: T::BlockNumber) -> bool {
+        matches!(market.status, MarketStatus::Active | MarketStatus::Closed)
+            && now > market.trading_end.saturating_add(T::AutoResolveGrace::get())
+    }
+
+    // Markets the off-chain worker should flag; closed markets have left
+    // `ActiveMarkets` but still await a report, so every market is scanned
+    fn stale_market_ids(now: T::BlockNumber) -> Vec<T::Hash> {
+        Markets::<T>::iter()
+            .filter(|(market_id, market)| !StaleMarkets::<T>::contains_key(market_id) && Self::is_stale(market, now))
+            .map(|(market_id, _)| market_id)
+            .collect()
+    }
+
+    // Account holding the collateral of a market
+    pub fn market_account(market_id: T::Hash) -> T::AccountId {
+        T::PalletId::get().into_sub_account(market_id)
+    }
+
+    // Whether `MinCreatedPeriod` has passed since the market was created
+    fn activation_period_elapsed(market_id: T::Hash) -> bool {
+        Markets::<T>::get(market_id).map_or(false, |market| {
+            system::Module::<T>::block_number()
+                >= market.creation_block.saturating_add(T::MinCreatedPeriod::get())
+        })
+    }
+
+    // Report `outcome` for a market as its oracle, opening the dispute window
+    fn do_resolve_market(who: T::AccountId, market_id: T::Hash, outcome: MarketOutcome) -> DispatchResult {
+        // Retrieve market
+        let mut market = Markets::<T>::get(market_id)
+            .ok_or(Error::<T>::MarketDoesNotExist)?;
+
+        // Validate market can be resolved, and by whom; committee markets
+        // are resolved through `submit_resolution` only
+        let now = system::Module::<T>::block_number();
+        let oracle = Self::current_oracle(market_id, &market, now);
+        ensure!(oracle == who, Error::<T>::NotAuthorizedOracle);
+        ensure!(!OracleCommittees::<T>::contains_key(market_id), Error::<T>::NotAuthorizedOracle);
+        match market.status {
+            MarketStatus::Active => ensure!(now >= market.trading_end, Error::<T>::TradingStillOpen),
+            // Closed markets may be reported before their trading end
+            MarketStatus::Closed => {}
+            _ => return Err(Error::<T>::MarketNotResolvable.into()),
+        }
+        Self::ensure_outcome_matches(&market, &outcome)?;
+        let outcome = Self::clamp_outcome(&market, outcome);
+
+        // The oracle stands behind the report with a bond
+        let bond = T::ResolutionBond::get();
+        T::Currency::reserve(&who, bond)?;
+        market.resolution_bond = bond;
+
+        // A backup reporting takes over as the market's oracle
+        if market.oracle != oracle {
+            market.oracle = oracle.clone();
+            BackupOracles::<T>::remove(market_id);
+            Self::deposit_event(RawEvent::OracleRotated(market_id, oracle));
+        }
+
+        // Update market status
+        market.status = MarketStatus::Proposed;
+        market.resolution_block = Some(now);
+        market.resolved_outcome = Some(outcome.clone());
+        let liquidity = market.total_liquidity;
+
+        // Store updated market; it is no longer open for trading
+        Self::put_market(market_id, market);
+        ActiveMarkets::<T>::remove(market_id);
+
+        // Emit event
+        Self::deposit_market_event(market_id, RawEvent::MarketResolved(who, market_id, outcome, liquidity));
+
+        Self::debug_assert_solvent(market_id);
+
+        Ok(())
+    }
+
+    // Stop trading on an active market
+    fn halt_trading(who: T::AccountId, market_id: T::Hash, mut market: MarketOf<T>) {
+        // Store updated market; it is no longer open for trading
+        market.status = MarketStatus::Closed;
+        Self::put_market(market_id, market);
+        ActiveMarkets::<T>::remove(market_id);
+
+        // Emit event
+        Self::deposit_event(RawEvent::MarketClosed(who, market_id));
+    }
+
+    // Open a market of `who` for trading once it is deep enough
+    fn do_activate_market(who: &T::AccountId, market_id: T::Hash) -> DispatchResult {
+        // Retrieve market
+        let mut market = Markets::<T>::get(market_id)
+            .ok_or(Error::<T>::MarketDoesNotExist)?;
+
+        // Only the creator may open their market, once it is deep enough
+        ensure!(market.creator == *who, Error::<T>::NotMarketCreator);
+        ensure!(market.status == MarketStatus::Created, Error::<T>::MarketNotActivatable);
+        ensure!(
+            market.total_liquidity >= T::MinActivationLiquidity::get(),
+            Error::<T>::InsufficientLiquidity
+        );
+        ensure!(
+            StatusCounts::get(MarketStatus::Active) < u64::from(T::MaxActiveMarkets::get()),
+            Error::<T>::TooManyActiveMarkets
+        );
+
+        // Update market status
+        market.status = MarketStatus::Active;
+
+        // Store updated market
+        Self::put_market(market_id, market);
+        ActiveMarkets::<T>::insert(market_id, ());
+
+        // Emit event
+        Self::deposit_event(RawEvent::MarketActivated(who.clone(), market_id));
+
+        Ok(())
+    }
+
+    // Deposit `event` with `market_id` as an indexed topic, letting nodes
+    // hand indexers the creation, trade and resolution events of one market
+    fn deposit_market_event(market_id: T::Hash, event: Event<T>) {
+        let event: <T as Config>::Event = event.into();
+        system::Module::<T>::deposit_event_indexed(&[market_id], event.into());
+    }
+
+    // Store `market`, moving it between the `StatusCounts` of its old and
+    // new status and updating `TotalValueLocked`. Every write of a market
+    // goes through here, which also makes it the one place status
+    // transitions are reported, settled markets stop being featured and
+    // the block a market resolved in is recorded.
+    fn put_market(market_id: T::Hash, market: MarketOf<T>) {
+        let old = Markets::<T>::get(market_id);
+        Self::track_market(old.as_ref(), Some(&market));
+        let settling = market.status == MarketStatus::Resolved
+            && old.as_ref().map_or(true, |old| old.status != MarketStatus::Resolved);
+        if settling {
+            SettledAt::<T>::insert(market_id, system::Module::<T>::block_number());
+        }
+        if let Some(old) = old.filter(|old| old.status != market.status) {
+            Self::deposit_event(RawEvent::MarketStatusChanged(market_id, old.status, market.status.clone()));
+        }
+        if market.status == MarketStatus::Resolved || market.status == MarketStatus::Cancelled {
+            Featured::<T>::remove(market_id);
+        }
+        Markets::<T>::insert(market_id, market);
+    }
+
+    // Delete a market, dropping it from the counters
+    fn remove_market(market_id: T::Hash) {
+        if let Some(old) = Markets::<T>::take(market_id) {
+            for (parent_id, _) in old.parent_conditions.iter() {
+                DependentMarkets::<T>::remove(parent_id, market_id);
+            }
+            Self::track_market(Some(&old), None);
+        }
+        Featured::<T>::remove(market_id);
+        SettledAt::<T>::remove(market_id);
+    }
+
+    fn track_market(old: Option<&MarketOf<T>>, new: Option<&MarketOf<T>>) {
+        if old.map(|market| &market.status) != new.map(|market| &market.status) {
+            if let Some(old) = old {
+                StatusCounts::mutate(&old.status, |count| *count = count.saturating_sub(1));
+            }
+            if let Some(new) = new {
+                StatusCounts::mutate(&new.status, |count| *count = count.saturating_add(1));
+            }
+        }
+        let before = old.map_or_else(Zero::zero, |market| market.total_liquidity);
+        let after = new.map_or_else(Zero::zero, |market| market.total_liquidity);
+        if before != after {
+            TotalValueLocked::<T>::mutate(|total| *total = total.saturating_add(after).saturating_sub(before));
+        }
+    }
+
+    // Id of the next market created by `who`. Ids are hashed from the block,
+    // the creator and their creation nonce, so no two creations share one.
+    fn next_market_id(who: &T::AccountId) -> T::Hash {
+        let now = system::Module::<T>::block_number();
+        let nonce = CreationNonce::<T>::mutate(who, |nonce| {
+            let current = *nonce;
+            *nonce = nonce.wrapping_add(1);
+            current
+        });
+        (now, who, nonce).using_encoded(T::Hashing::hash)
+    }
+
+    // Deterministic id of the `index`th genesis market, so every node
+    // building the same chain spec agrees on it
+    pub fn genesis_market_id(index: u32, creator: &T::AccountId) -> T::Hash {
+        (b"genesis", index, creator).using_encoded(T::Hashing::hash)
+    }
+
+    // Validate, fund and store a new market under `market_id`, seeding it with
+    // `initial_liquidity` on top of the market maker's subsidy
+    #[allow(clippy::too_many_arguments)]
+    fn do_create_market(
+        who: &T::AccountId,
+        market_id: T::Hash,
+        market_type: MarketType,
+        mechanism: MarketMechanism,
+        trading_end: T::BlockNumber,
+        liquidity_parameter: BalanceOf<T>,
+        initial_liquidity: BalanceOf<T>,
+        scalar_bounds: Option<(i128, i128)>,
+        outcome_count: Option<u32>,
+        metadata: BoundedVec<u8, T::MaxMetadataLen>,
+        oracle: Option<T::AccountId>,
+        collateral_asset: T::AssetId,
+        category: MarketCategory,
+        denom: BoundedVec<u8, T::MaxDenomLen>,
+        outcome_labels: OutcomeLabelsOf<T>
+    ) -> DispatchResult {
+        let now = system::Module::<T>::block_number();
+        ensure!(!Markets::<T>::contains_key(market_id), Error::<T>::MarketIdCollision);
+
+        // The creator resolves their own market unless another oracle is named
+        let oracle = oracle.unwrap_or_else(|| who.clone());
+        Self::ensure_oracle_allowed(who, &oracle)?;
+
+        // Trading window must end in the future, but not too far out
+        ensure!(trading_end > now, Error::<T>::InvalidTradingEnd);
+        ensure!(
+            trading_end.saturating_sub(now) <= T::MaxMarketLifetime::get(),
+            Error::<T>::LifetimeTooLong
+        );
+
+        Self::ensure_scalar_bounds(&market_type, scalar_bounds, 0)?;
+
+        // Categorical markets choose their number of outcomes
+        let outcome_count = match (&market_type, outcome_count) {
+            (MarketType::Categorical, Some(count)) => {
+                ensure!(count >= 2, Error::<T>::TooFewOutcomes);
+                ensure!(count <= T::MaxOutcomes::get(), Error::<T>::TooManyOutcomes);
+                count
+            }
+            (MarketType::Categorical, None) => return Err(Error::<T>::TooFewOutcomes.into()),
+            (_, Some(_)) => return Err(Error::<T>::OutcomeTypeMismatch.into()),
+            (_, None) => 2,
+        };
+        ensure!(
+            outcome_labels.is_empty() || outcome_labels.len() as u32 == outcome_count,
+            Error::<T>::LabelCountMismatch
+        );
+        match mechanism {
+            MarketMechanism::Lmsr => ensure!(
+                !liquidity_parameter.is_zero(),
+                Error::<T>::InvalidLiquidityParameter
+            ),
+            MarketMechanism::ConstantProduct => ensure!(
+                !liquidity_parameter.is_zero(),
+                Error::<T>::InsufficientSeedLiquidity
+            ),
+        }
+        
+        // The creator funds the market maker: the LMSR worst-case loss
+        // b * ln(n), or one complete set per seed unit for a CPMM pool, in
+        // sets worth the width of the range on scalar markets
+        let outcomes = outcome_count as usize;
+        let sets = match mechanism {
+            MarketMechanism::Lmsr => lmsr::max_loss(
+                liquidity_parameter.saturated_into::<u128>(),
+                outcomes
+            )
+            .ok_or(Error::<T>::PricingFailed)?,
+            MarketMechanism::ConstantProduct => liquidity_parameter.saturated_into::<u128>(),
+        };
+        let subsidy = Self::collateral_for_sets(scalar_bounds, sets)?;
+
+        // The deposit, reap incentive, subsidy and seed must all be covered up
+        // front; the first two are native, the rest is paid in the collateral asset
+        let deposit = T::MarketCreationDeposit::get();
+        let incentive = T::ReapIncentive::get();
+        let funding = subsidy.saturating_add(initial_liquidity);
+        let native_needed = if collateral_asset == T::NativeAssetId::get() {
+            deposit.saturating_add(incentive).saturating_add(funding)
+        } else {
+            ensure!(
+                <T::Assets as fungibles::Inspect<_>>::balance(collateral_asset, who) >= funding,
+                Error::<T>::InsufficientFunds
+            );
+            deposit.saturating_add(incentive)
+        };
+        ensure!(T::Currency::free_balance(who) >= native_needed, Error::<T>::InsufficientFunds);
+
+        // Ensure minimum deposit is paid, and leave the incentive in the pot
+        T::Currency::reserve(who, deposit)?;
+        T::Currency::transfer(
+            who,
+            &Self::market_account(market_id),
+            incentive,
+            ExistenceRequirement::KeepAlive
+        )?;
+        if mechanism == MarketMechanism::ConstantProduct {
+            Reserves::<T>::insert(market_id, sp_std::vec![liquidity_parameter; outcomes]);
+        }
+        Self::transfer_collateral(
+            collateral_asset,
+            who,
+            &Self::market_account(market_id),
+            subsidy,
+            ExistenceRequirement::KeepAlive
+        )?;
+
+        // Create market
+        let mut market = PredictionMarket {
+            id: market_id,
+            creator: who.clone(),
+            oracle,
+            metadata,
+            market_type: market_type.clone(),
+            mechanism,
+            category,
+            collateral_asset,
+            denom: denom.clone(),
+            status: MarketStatus::Created,
+            total_liquidity: subsidy,
+            liquidity_parameter,
+            creation_block: now,
+            trading_end,
+            resolution_block: None,
+            resolved_outcome: None,
+            scalar_bounds,
+            outcome_count,
+            outcome_labels,
+            deposit_refunded: false,
+            position_cap: None,
+            resolution_bond: Zero::zero(),
+            dispute_bond: Zero::zero(),
+            cloned_from: None,
+            parent_conditions: BoundedVec::default(),
+            scale: 0,
+            payout_curve: ScalarCurve::Linear,
+            creator_fee: Permill::zero(),
+            eliminated: 0,
+            max_collateral: None,
+            max_shares: None,
+            auto_activate: false,
+        };
+
+        // Bump the market counter, guarding against overflow
+        let count = MarketCount::get()
+            .checked_add(1)
+            .ok_or(Error::<T>::MarketCountOverflow)?;
+
+        // Index the market under its creator
+        MarketsByCreator::<T>::try_mutate(who, |ids| ids.try_push(market_id))
+            .map_err(|_| Error::<T>::TooManyMarkets)?;
+        MarketsByCategory::<T>::try_mutate(category, |ids| ids.try_push(market_id))
+            .map_err(|_| Error::<T>::TooManyInCategory)?;
+
+        // The creator's funding is the first LP stake
+        MarketCount::put(count);
+        Self::schedule_expiry(market_id, trading_end);
+        LpShares::<T>::insert(market_id, who, subsidy);
+        LpIssuance::<T>::insert(market_id, subsidy);
+
+        // Emit event
+        Self::deposit_market_event(
+            market_id,
+            RawEvent::MarketCreated(who.clone(), market_id, market_type, trading_end, denom.into_inner())
+        );
+
+        // Seed the pool beyond the subsidy if asked to, then store the market
+        if !initial_liquidity.is_zero() {
+            Self::do_add_liquidity(who, market_id, &mut market, initial_liquidity)?;
+        }
+        Self::put_market(market_id, market);
+        Self::debug_assert_solvent(market_id);
+
+        Ok(())
+    }
+
+    // Fund the market maker of `market` with `amount`, minting LP shares
+    // against the current value of the pool
+    fn do_add_liquidity(
+        who: &T::AccountId,
+        market_id: T::Hash,
+        market: &mut MarketOf<T>,
+        amount: BalanceOf<T>
+    ) -> DispatchResult {
+        let issuance = LpIssuance::<T>::get(market_id);
+        let value = Self::pool_value(market_id, market);
+        let minted = if issuance.is_zero() || value.is_zero() {
+            amount
+        } else {
+            amm::mul_div(
+                amount.saturated_into::<u128>(),
+                issuance.saturated_into::<u128>(),
+                value.saturated_into::<u128>()
+            )
+            .ok_or(Error::<T>::PricingFailed)?
+            .saturated_into::<BalanceOf<T>>()
+        };
+
+        // Constant-product pools take the funding as complete sets and hand
+        // back the unbalanced remainder so prices do not move
+        let funding = match market.mechanism {
+            MarketMechanism::Lmsr => None,
+            MarketMechanism::ConstantProduct => Some(
+                amm::add_funding(&Self::pool_reserves(market_id), Self::sets_for_collateral(market.scalar_bounds, amount))
+                    .ok_or(Error::<T>::PricingFailed)?
+            ),
+        };
+        let total_liquidity = market.total_liquidity
+            .checked_add(&amount)
+            .ok_or(Error::<T>::ArithmeticOverflow)?;
+        let new_issuance = issuance.checked_add(&minted).ok_or(Error::<T>::ArithmeticOverflow)?;
+        let held = LpShares::<T>::get(market_id, who)
+            .checked_add(&minted)
+            .ok_or(Error::<T>::ArithmeticOverflow)?;
+
+        // Move collateral into the market pot
+        Self::transfer_collateral(
+            market.collateral_asset,
+            who,
+            &Self::market_account(market_id),
+            amount,
+            ExistenceRequirement::KeepAlive
+        )?;
+
+        // Store updated state
+        if let Some((reserves, returned)) = funding {
+            Self::credit_shares(market_id, who, &returned);
+            Reserves::<T>::insert(
+                market_id,
+                reserves.into_iter().map(|r| r.saturated_into()).collect::<Vec<BalanceOf<T>>>()
+            );
+        }
+        LpShares::<T>::insert(market_id, who, held);
+        LpIssuance::<T>::insert(market_id, new_issuance);
+        market.total_liquidity = total_liquidity;
+
+        // Emit event
+        Self::deposit_event(RawEvent::LiquidityAdded(who.clone(), market_id, amount, minted));
+
+        Ok(())
+    }
+
+    // Outstanding shares per outcome, as the LMSR `q` vector
+    fn outstanding_shares(market_id: T::Hash, market: &MarketOf<T>) -> Vec<u128> {
+        let totals = TotalShares::<T>::get(market_id);
+        (0..market.outcome_count as usize)
+            .map(|i| totals.balance_of(i).saturated_into::<u128>())
+            .collect()
+    }
+
+    // Pool reserves of a constant-product market
+    fn pool_reserves(market_id: T::Hash) -> Vec<u128> {
+        Reserves::<T>::get(market_id)
+            .into_iter()
+            .map(|r| r.saturated_into::<u128>())
+            .collect()
+    }
+
+    // Deposits and bonds live markets should still hold reserved: unrefunded
+    // creation deposits plus outstanding resolution and dispute bonds. Only
+    // built natively, to audit deposit accounting against the balances.
+    #[cfg(feature = "std")]
+    pub fn total_reserved_for_markets() -> BalanceOf<T> {
+        Markets::<T>::iter_values().fold(Zero::zero(), |total: BalanceOf<T>, market| {
+            let deposit = if market.deposit_refunded {
+                Zero::zero()
+            } else {
+                T::MarketCreationDeposit::get()
+            };
+            total
+                .saturating_add(deposit)
+                .saturating_add(market.resolution_bond)
+                .saturating_add(market.dispute_bond)
+        })
+    }
+
+    // Market counts per status and the liquidity they hold, read from the
+    // counters maintained on every write
+    pub fn market_stats() -> MarketStats<BalanceOf<T>> {
+        let statuses = [
+            MarketStatus::Created,
+            MarketStatus::Active,
+            MarketStatus::Closed,
+            MarketStatus::Proposed,
+            MarketStatus::Disputed,
+            MarketStatus::Resolved,
+            MarketStatus::Cancelled,
+        ];
+        MarketStats {
+            status_counts: statuses
+                .iter()
+                .map(|status| (status.clone(), StatusCounts::get(status)))
+                .collect(),
+            total_liquidity: TotalValueLocked::<T>::get(),
+        }
+    }
+
+    // Page through active market ids in storage order, starting after
+    // `start_after` when given
+    pub fn active_market_ids(start_after: Option<T::Hash>, limit: u32) -> Vec<T::Hash> {
+        let keys = match start_after {
+            Some(id) => ActiveMarkets::<T>::iter_keys_from(ActiveMarkets::<T>::hashed_key_for(id)),
+            None => ActiveMarkets::<T>::iter_keys(),
+        };
+        keys.take(limit as usize).collect()
+    }
+
+    // Current marginal price of one outcome, backing `FutarchyApi::market_price`
+    pub fn price_of(market_id: T::Hash, outcome: u32) -> Option<FixedU128> {
+        Self::outcome_prices(market_id)?.get(outcome as usize).copied()
+    }
+
+    // Append the current price of outcome 1 to the market's history if it
+    // moved by more than the epsilon, evicting the oldest point when full
+    fn record_price(market_id: T::Hash) {
+        let price = match Self::price_of(market_id, 1) {
+            Some(price) => price,
+            None => return,
+        };
+        PriceHistory::<T>::mutate(market_id, |history| {
+            if let Some((_, last)) = history.last() {
+                let moved = if price > *last { price - *last } else { *last - price };
+                if moved <= T::PriceHistoryEpsilon::get() {
+                    return;
+                }
+            }
+            if history.len() as u32 >= T::MaxHistoryPoints::get() {
+                history.remove(0);
+            }
+            let _ = history.try_push((system::Module::<T>::block_number(), price));
+        });
+    }
+
+    // Up to `limit` accounts with the highest realized profit, best first
+    pub fn top_traders(limit: u32) -> Vec<(T::AccountId, i128)> {
+        TopTraders::<T>::get().into_iter().take(limit as usize).collect()
+    }
+
+    // Markets currently promoted to front-ends
+    pub fn featured_markets() -> Vec<T::Hash> {
+        Featured::<T>::iter_keys().collect()
+    }
+
+    // Labels of a market's outcomes by index, empty if it has none
+    pub fn outcome_labels(market_id: T::Hash) -> Option<Vec<Vec<u8>>> {
+        Markets::<T>::get(market_id).map(|market| {
+            market.outcome_labels.into_iter().map(|label| label.into_inner()).collect()
+        })
+    }
+
+    // Label front-ends render a market's collateral amounts with
+    pub fn market_denom(market_id: T::Hash) -> Option<Vec<u8>> {
+        Markets::<T>::get(market_id).map(|market| market.denom.into_inner())
+    }
+
+    // Expected metric of a scalar market at current prices,
+    // lower + price(LONG) * (upper - lower), at the market's scale
+    pub fn expected_value(market_id: T::Hash) -> Option<i128> {
+        let (lower, upper) = Self::scaled_bounds(&Markets::<T>::get(market_id)?)?;
+        let long = Self::price_of(market_id, scalar_outcome_index(true) as u32)?;
+        let offset = long.saturating_mul_int(upper.wrapping_sub(lower) as u128);
+        Some(lower.wrapping_add(offset as i128))
+    }
+
+    // Current value of every position `who` holds: live prices for open
+    // markets, settlement values once resolved
+    pub fn account_exposure(who: &T::AccountId) -> BalanceOf<T> {
+        HeldMarkets::<T>::iter_prefix(who).fold(Zero::zero(), |total: BalanceOf<T>, (market_id, _)| {
+            let prices = match Markets::<T>::get(market_id) {
+                // Positions in invalid markets are worth their refund
+                Some(market) if market.status == MarketStatus::Resolved
+                    && market.resolved_outcome == Some(MarketOutcome::Invalid) =>
+                {
+                    return total.saturating_add(Self::invalid_refund(market_id, &market, who));
+                }
+                Some(market) if market.status == MarketStatus::Resolved => Self::outcome_payouts(&market),
+                // Prices are per unit of collateral a set is minted for
+                Some(market) => Self::outcome_prices(market_id).map(|prices| {
+                    let value = FixedU128::saturating_from_integer(Self::set_value(market.scalar_bounds));
+                    prices.into_iter().map(|price| price.saturating_mul(value)).collect()
+                }),
+                None => None,
+            };
+            let value = prices.map_or_else(Zero::zero, |prices| {
+                Self::position_value(&Positions::<T>::get(market_id, who), &prices)
+            });
+            total.saturating_add(value)
+        })
+    }
+
+    // What `claim_winnings` would pay `who` right now, `None` while the
+    // outcome is not final; an undisputed report counts as final once its
+    // dispute window has passed, since the claim finalizes it
+    pub fn winnings_preview(market_id: &T::Hash, who: &T::AccountId) -> Option<BalanceOf<T>> {
+        let market = Markets::<T>::get(market_id)?;
+        let claimable = market.status == MarketStatus::Resolved
+            || (market.status == MarketStatus::Proposed && Self::dispute_window_passed(&market));
+        if !claimable {
+            return None;
+        }
+        let conditions_met = Self::parent_conditions_met(&market).ok()?;
+        if market.resolved_outcome == Some(MarketOutcome::Invalid) || !conditions_met {
+            return Some(Self::invalid_refund(*market_id, &market, who));
+        }
+        let payouts = Self::outcome_payouts(&market)?;
+        let position = Positions::<T>::get(market_id, who);
+        let payout = Self::position_value(&position, &payouts);
+        if payout.is_zero() || T::PayoutRounding::get() != PayoutRounding::DustToLastClaimant {
+            return Some(payout);
+        }
+        let mut remaining = TotalShares::<T>::get(market_id);
+        for (index, _) in payouts.iter().enumerate() {
+            remaining.burn(index, position.balance_of(index))?;
+        }
+        let (released, _) = Self::release_dust(market_id, &position, &remaining, &payouts);
+        Some(payout.saturating_add(released))
+    }
+
+    // Whether every market `market` is conditioned on resolved to the side
+    // it requires. A cancelled parent fails its condition; a parent still
+    // awaiting its outcome leaves the question open. Parents cannot be
+    // reaped while conditioned on, so a missing one never passes.
+    fn parent_conditions_met(market: &MarketOf<T>) -> Result<bool, DispatchError> {
+        for (parent_id, side) in market.parent_conditions.iter() {
+            let parent = match Markets::<T>::get(parent_id) {
+                Some(parent) => parent,
+                None => return Ok(false),
+            };
+            match parent.status {
+                MarketStatus::Resolved => {
+                    if parent.resolved_outcome != Some(MarketOutcome::Binary(*side)) {
+                        return Ok(false);
+                    }
+                }
+                MarketStatus::Cancelled => return Ok(false),
+                _ => return Err(Error::<T>::ConditionsPending.into()),
+            }
+        }
+        Ok(true)
+    }
+
+    // Expected metric of one branch of a decision, once its trading has ended
+    fn decision_branch_value(market_id: T::Hash) -> Result<i128, DispatchError> {
+        let market = Markets::<T>::get(market_id).ok_or(Error::<T>::MarketDoesNotExist)?;
+        ensure!(
+            market.status != MarketStatus::Cancelled && market.status != MarketStatus::Resolved,
+            Error::<T>::MarketNotActive
+        );
+        ensure!(
+            system::Module::<T>::block_number() >= market.trading_end,
+            Error::<T>::TradingStillOpen
+        );
+        Self::expected_value(market_id).ok_or_else(|| Error::<T>::PricingFailed.into())
+    }
+
+    // Value backing the LP shares of a market: collateral above the cost
+    // function C(q) for LMSR markets, the largest reserve for constant-product
+    // pools. C(q) is what traders paid in plus the worst-case loss b * ln(n),
+    // so whatever trades come next the pot still covers the winning side.
+    fn pool_value(market_id: T::Hash, market: &MarketOf<T>) -> BalanceOf<T> {
+        match market.mechanism {
+            MarketMechanism::Lmsr => {
+                let shares = Self::outstanding_shares(market_id, market);
+                let largest = shares.iter().copied().max().unwrap_or_default();
+                let reserve = lmsr::cost(&shares, market.liquidity_parameter.saturated_into::<u128>())
+                    .map_or(u128::MAX, |cost| cost.max(largest))
+                    .saturating_mul(Self::set_value(market.scalar_bounds))
+                    .saturated_into::<BalanceOf<T>>();
+                // The treasury's subsidy is not the providers' to withdraw
+                market.total_liquidity
+                    .saturating_sub(reserve)
+                    .saturating_sub(Subsidies::<T>::get(market_id))
+            }
+            MarketMechanism::ConstantProduct => Self::pool_reserves(market_id)
+                .into_iter()
+                .max()
+                .unwrap_or_default()
+                .saturating_mul(Self::set_value(market.scalar_bounds))
+                .saturated_into(),
+        }
+    }
+
+    // Whether the dispute window of a reported outcome has elapsed
+    fn dispute_window_passed(market: &MarketOf<T>) -> bool {
+        market.resolution_block.map_or(false, |reported| {
+            system::Module::<T>::block_number() >= reported.saturating_add(T::DisputePeriod::get())
+        })
+    }
+
+    // Move an undisputed reported outcome to `Resolved`
+    fn finalize(market_id: T::Hash, market: &mut MarketOf<T>) -> DispatchResult {
+        ensure!(market.status == MarketStatus::Proposed, Error::<T>::MarketNotResolved);
+        ensure!(Self::dispute_window_passed(market), Error::<T>::DisputeWindowOpen);
+
+        market.status = MarketStatus::Resolved;
+        Self::settle_creation_deposit(market, true);
+        Self::settle_resolution_bond(market, true);
+        let owed = Self::unclaimed_winnings(market_id, market);
+        Self::return_subsidy(market_id, market, owed)?;
+        if let Some(outcome) = market.resolved_outcome.as_ref() {
+            T::OnMarketResolved::on_market_resolved(market_id, outcome);
+        }
+        Self::deposit_market_event(market_id, RawEvent::MarketFinalized(market_id));
+
+        Ok(())
+    }
+
+    // Return the creation deposit of an honestly resolved market, or slash it
+    fn settle_creation_deposit(market: &mut MarketOf<T>, honest: bool) {
+        if market.deposit_refunded {
+            return;
+        }
+        market.deposit_refunded = true;
+
+        let deposit = T::MarketCreationDeposit::get();
+        if honest {
+            T::Currency::unreserve(&market.creator, deposit);
+            Self::deposit_event(RawEvent::DepositRefunded(market.creator.clone(), deposit));
+        } else {
+            let _ = T::Currency::slash_reserved(&market.creator, deposit);
+        }
+    }
+
+    // Release the disputer's bond on a dispute that was upheld, or slash it
+    fn settle_dispute_bond(market_id: T::Hash, market: &mut MarketOf<T>, upheld: bool) {
+        let bond = sp_std::mem::replace(&mut market.dispute_bond, Zero::zero());
+        if let Some((disputer, _)) = Disputes::<T>::take(market_id) {
+            if upheld {
+                T::Currency::unreserve(&disputer, bond);
+            } else {
+                let _ = T::Currency::slash_reserved(&disputer, bond);
+            }
+        }
+    }
+
+    // Bond a dispute of `market` would reserve right now: a share of its pot,
+    // but never less than `MinDisputeBond`
+    pub fn dispute_bond(market: &MarketOf<T>) -> BalanceOf<T> {
+        T::DisputeBondPercent::get()
+            .mul_floor(market.total_liquidity)
+            .max(T::MinDisputeBond::get())
+    }
+
+    // Release the oracle's bond on a report that stood, or slash it
+    fn settle_resolution_bond(market: &mut MarketOf<T>, honest: bool) {
+        let bond = sp_std::mem::replace(&mut market.resolution_bond, Zero::zero());
+        if honest {
+            T::Currency::unreserve(&market.oracle, bond);
+        } else {
+            let _ = T::Currency::slash_reserved(&market.oracle, bond);
+        }
+    }
+
+    // Account creating a market on behalf of `origin`, per the creation policy
+    fn ensure_creator(origin: T::Origin) -> Result<T::AccountId, DispatchError> {
+        match T::MarketCreationPolicy::get() {
+            CreationPolicy::Permissionless => Ok(ensure_signed(origin)?),
+            CreationPolicy::WhitelistOnly => {
+                let who = ensure_signed(origin)?;
+                ensure!(ApprovedCreators::<T>::contains_key(&who), Error::<T>::NotAuthorizedCreator);
+                Ok(who)
+            }
+            CreationPolicy::RootOnly => {
+                ensure_root(origin).map_err(|_| Error::<T>::NotAuthorizedCreator)?;
+                Ok(T::TreasuryAccount::get())
+            }
+        }
+    }
+
+    // Ensure `spec` describes `market` with a fresh trading window and only
+    // the parameters a migration may correct changed
+    fn ensure_migratable(market: &MarketOf<T>, spec: &MarketSpecOf<T>) -> DispatchResult {
+        let now = system::Module::<T>::block_number();
+        let outcome_count = match market.market_type {
+            MarketType::Categorical => Some(market.outcome_count),
+            _ => None,
+        };
+        ensure!(
+            spec.market_type == market.market_type
+                && spec.mechanism == market.mechanism
+                && spec.liquidity_parameter == market.liquidity_parameter
+                && spec.outcome_count == outcome_count
+                && spec.collateral_asset.unwrap_or_else(T::NativeAssetId::get) == market.collateral_asset
+                && Self::set_value(spec.scalar_bounds) == Self::set_value(market.scalar_bounds),
+            Error::<T>::MigrationMismatch
+        );
+        ensure!(spec.trading_end > now, Error::<T>::InvalidTradingEnd);
+        ensure!(
+            spec.trading_end.saturating_sub(now) <= T::MaxMarketLifetime::get(),
+            Error::<T>::LifetimeTooLong
+        );
+        Self::ensure_scalar_bounds(&market.market_type, spec.scalar_bounds, market.scale)
+    }
+
+    // Bound outcome labels as given to a creation call; more labels than
+    // `MaxOutcomes` can never match a market's outcome count
+    fn bound_outcome_labels(labels: Vec<Vec<u8>>) -> Result<OutcomeLabelsOf<T>, DispatchError> {
+        labels
+            .into_iter()
+            .map(|label| BoundedVec::<u8, T::MaxLabelLen>::try_from(label).map_err(|_| Error::<T>::LabelTooLong))
+            .collect::<Result<Vec<_>, _>>()?
+            .try_into()
+            .map_err(|_| Error::<T>::LabelCountMismatch.into())
+    }
+
+    // Ensure scalar markets, and only those, carry a non-empty range no
+    // wider than `MaxScalarRange` whose bounds can still be given at `scale`
+    fn ensure_scalar_bounds(market_type: &MarketType, bounds: Option<(i128, i128)>, scale: u8) -> DispatchResult {
+        match (market_type, bounds) {
+            (MarketType::Scalar, Some((lower, upper))) => {
+                ensure!(lower < upper, Error::<T>::InvalidScalarBounds);
+                ensure!(
+                    upper.wrapping_sub(lower) as u128 <= T::MaxScalarRange::get(),
+                    Error::<T>::ScalarRangeTooLarge
+                );
+                ensure!(Self::at_scale((lower, upper), scale).is_some(), Error::<T>::ScaleTooLarge);
+                Ok(())
+            }
+            (MarketType::Scalar, None) | (_, Some(_)) => Err(Error::<T>::InvalidScalarBounds.into()),
+            (_, None) => Ok(()),
+        }
+    }
+
+    // Hand everything held by the pot of `from_id`, collateral and native
+    // reap incentive alike, to the pot of `to_id`
+    fn move_pot(asset: T::AssetId, from_id: T::Hash, to_id: T::Hash) -> DispatchResult {
+        let from = Self::market_account(from_id);
+        let to = Self::market_account(to_id);
+        if asset != T::NativeAssetId::get() {
+            let held = <T::Assets as fungibles::Inspect<_>>::balance(asset, &from);
+            Self::transfer_collateral(asset, &from, &to, held, ExistenceRequirement::AllowDeath)?;
+        }
+        let native = T::Currency::free_balance(&from);
+        T::Currency::transfer(&from, &to, native, ExistenceRequirement::AllowDeath)
+    }
+
+    // Ensure `who` is past the creator cooldown and restart it
+    fn note_creation(who: &T::AccountId) -> DispatchResult {
+        let now = system::Module::<T>::block_number();
+        if let Some(last) = LastCreation::<T>::get(who) {
+            ensure!(
+                now >= last.saturating_add(T::CreatorCooldown::get()),
+                Error::<T>::CreationCooldown
+            );
+        }
+        LastCreation::<T>::insert(who, now);
+        Ok(())
+    }
+
+    // Oracle entitled to report on `market` at `now`: its own oracle during
+    // the first `OracleSlaWindow` after trading ends, then each backup in
+    // turn for a window of its own, the last one keeping the duty
+    fn current_oracle(market_id: T::Hash, market: &MarketOf<T>, now: T::BlockNumber) -> T::AccountId {
+        let window = T::OracleSlaWindow::get();
+        if window.is_zero() {
+            return market.oracle.clone();
+        }
+        let missed: u32 = (now.saturating_sub(market.trading_end) / window).saturated_into();
+        if missed == 0 {
+            return market.oracle.clone();
+        }
+        let backups = BackupOracles::<T>::get(market_id);
+        backups
+            .get((missed as usize).min(backups.len()).saturating_sub(1))
+            .cloned()
+            .unwrap_or_else(|| market.oracle.clone())
+    }
+
+    // Ensure `oracle` may resolve a market created by `creator`
+    fn ensure_oracle_allowed(creator: &T::AccountId, oracle: &T::AccountId) -> DispatchResult {
+        ensure!(
+            T::AllowCreatorResolution::get() || creator != oracle,
+            Error::<T>::CreatorCannotResolve
+        );
+        Ok(())
+    }
+
+    // Ensure a reported outcome fits the market's type and outcome range
+    fn ensure_outcome_matches(market: &MarketOf<T>, outcome: &MarketOutcome) -> DispatchResult {
+        match (&market.market_type, outcome) {
+            (_, MarketOutcome::Invalid) => Ok(()),
+            // Ties only come out of aggregating committee votes
+            (_, MarketOutcome::Tie(_)) => Err(Error::<T>::InvalidOutcome.into()),
+            (MarketType::Binary, MarketOutcome::Binary(_)) => Ok(()),
+            (MarketType::Scalar, MarketOutcome::Scalar(_)) => Ok(()),
+            (MarketType::Categorical, MarketOutcome::Categorical(index)) => {
+                ensure!(*index < market.outcome_count, Error::<T>::InvalidOutcome);
+                ensure!(!Self::is_eliminated(market, *index), Error::<T>::OutcomeEliminated);
+                Ok(())
+            }
+            _ => Err(Error::<T>::OutcomeTypeMismatch.into()),
+        }
+    }
+
+    // Clamp a reported scalar value into the market's range at its scale
+    fn clamp_outcome(market: &MarketOf<T>, outcome: MarketOutcome) -> MarketOutcome {
+        match (outcome, Self::scaled_bounds(market)) {
+            (MarketOutcome::Scalar(value), Some((lower, upper))) => {
+                MarketOutcome::Scalar(value.max(lower).min(upper))
+            }
+            (outcome, _) => outcome,
+        }
+    }
+
+    // Bounds of a scalar market in units of its reported value
+    fn scaled_bounds(market: &MarketOf<T>) -> Option<(i128, i128)> {
+        Self::at_scale(market.scalar_bounds?, market.scale)
+    }
+
+    // Whole-unit `bounds` given with `scale` implied decimal places
+    fn at_scale((lower, upper): (i128, i128), scale: u8) -> Option<(i128, i128)> {
+        let unit = 10i128.checked_pow(scale as u32)?;
+        Some((lower.checked_mul(unit)?, upper.checked_mul(unit)?))
+    }
+
+    // Combine weighted committee votes into one outcome: the lower weighted
+    // median for scalar markets, otherwise the outcome with the most weight,
+    // ties being settled by `TieBreak`. A majority of weight voting invalid
+    // makes the market invalid, short of that those votes are left out.
+    fn aggregate_votes(mut votes: Vec<(MarketOutcome, u32)>) -> Option<MarketOutcome> {
+        let total: u64 = votes.iter().map(|(_, weight)| *weight as u64).sum();
+        let invalid: u64 = votes
+            .iter()
+            .filter(|(vote, _)| *vote == MarketOutcome::Invalid)
+            .map(|(_, weight)| *weight as u64)
+            .sum();
+        if invalid.saturating_mul(2) > total {
+            return Some(MarketOutcome::Invalid);
+        }
+        votes.retain(|(vote, _)| *vote != MarketOutcome::Invalid);
+        let total = total.saturating_sub(invalid);
+        if let Some((MarketOutcome::Scalar(_), _)) = votes.first() {
+            votes.sort_by_key(|(vote, _)| match vote {
+                MarketOutcome::Scalar(value) => *value,
+                _ => 0,
+            });
+            let mut seen = 0u64;
+            return votes.into_iter().find_map(|(vote, weight)| {
+                seen = seen.saturating_add(weight as u64);
+                if seen.saturating_mul(2) >= total { Some(vote) } else { None }
+            });
+        }
+
+        let index = |vote: &MarketOutcome| match vote {
+            MarketOutcome::Binary(outcome) => binary_outcome_index(*outcome) as u32,
+            MarketOutcome::Categorical(index) => *index,
+            MarketOutcome::Scalar(_) | MarketOutcome::Invalid | MarketOutcome::Tie(_) => 0,
+        };
+        let mut tally: Vec<(u32, u64, MarketOutcome)> = Vec::new();
+        for (vote, weight) in votes {
+            let key = index(&vote);
+            match tally.iter_mut().find(|(k, _, _)| *k == key) {
+                Some(entry) => entry.1 = entry.1.saturating_add(weight as u64),
+                None => tally.push((key, weight as u64, vote)),
+            }
+        }
+        tally.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        let top = tally.first()?.1;
+        let tied: Vec<u32> = tally.iter().filter(|(_, weight, _)| *weight == top).map(|(key, _, _)| *key).collect();
+        if tied.len() > 1 {
+            match T::TieBreak::get() {
+                TieBreak::LowestIndex => {}
+                TieBreak::Refund => return Some(MarketOutcome::Invalid),
+                TieBreak::SplitEqually => return Some(MarketOutcome::Tie(tied)),
+            }
+        }
+        tally.into_iter().next().map(|(_, _, vote)| vote)
+    }
+
+    // Collateral paid per share of every outcome of a resolved market;
+    // invalid markets refund cost basis instead, see `invalid_refund`
+    fn outcome_payouts(market: &MarketOf<T>) -> Option<Vec<FixedU128>> {
+        let winner = match market.resolved_outcome.as_ref()? {
+            MarketOutcome::Invalid => return None,
+            // Tied outcomes split one unit of collateral per share
+            MarketOutcome::Tie(tied) => {
+                let share = FixedU128::checked_from_rational(1u128, tied.len() as u128)?;
+                return Some(
+                    (0..market.outcome_count)
+                        .map(|i| if tied.contains(&i) { share } else { FixedU128::zero() })
+                        .collect()
+                );
+            }
+            MarketOutcome::Binary(outcome) => binary_outcome_index(*outcome),
+            MarketOutcome::Categorical(index) => *index as usize,
+            MarketOutcome::Scalar(value) => {
+                let (long, short) = Self::scalar_payouts(market, *value)?;
+                let mut payouts = sp_std::vec![FixedU128::zero(); 2];
+                payouts[scalar_outcome_index(true)] = long;
+                payouts[scalar_outcome_index(false)] = short;
+                return Some(payouts);
+            }
+        };
+        Some(
+            (0..market.outcome_count as usize)
+                .map(|i| if i == winner { FixedU128::one() } else { FixedU128::zero() })
+                .collect()
+        )
+    }
+
+    // LONG and SHORT payout per share of a scalar market reporting `value`
+    // at its scale. A complete set is minted for the width of the range, so
+    // on the linear curve LONG pays value - lower and SHORT upper - value,
+    // fractions of a unit included; SHORT always takes what LONG leaves,
+    // which keeps every set paying out exactly upper - lower.
+    fn scalar_payouts(market: &MarketOf<T>, value: i128) -> Option<(FixedU128, FixedU128)> {
+        let (lower, upper) = Self::scaled_bounds(market)?;
+        let value = value.max(lower).min(upper);
+        let position = FixedU128::checked_from_rational(
+            value.wrapping_sub(lower) as u128,
+            upper.wrapping_sub(lower) as u128
+        )?;
+        let set = FixedU128::checked_from_integer(Self::set_value(market.scalar_bounds))?;
+        let long = market.payout_curve.long_payout(position).saturating_mul(set);
+        Some((long, set.saturating_sub(long)))
+    }
+
+    // Collateral one complete set is minted for: the width of the range of
+    // a scalar market, one unit for any other market
+    fn set_value(scalar_bounds: Option<(i128, i128)>) -> u128 {
+        scalar_bounds.map_or(1, |(lower, upper)| upper.wrapping_sub(lower) as u128)
+    }
+
+    // Collateral backing `sets` complete sets of a market over `scalar_bounds`
+    fn collateral_for_sets(scalar_bounds: Option<(i128, i128)>, sets: u128) -> Result<BalanceOf<T>, DispatchError> {
+        sets.checked_mul(Self::set_value(scalar_bounds))
+            .map(|value| value.saturated_into())
+            .ok_or_else(|| Error::<T>::ArithmeticOverflow.into())
+    }
+
+    // Complete sets `amount` of collateral mints on a market over
+    // `scalar_bounds`, rounded down
+    fn sets_for_collateral(scalar_bounds: Option<(i128, i128)>, amount: BalanceOf<T>) -> u128 {
+        amount.saturated_into::<u128>() / Self::set_value(scalar_bounds)
+    }
+
+    // Settlement value of a position given per-share payouts
+    fn position_value(position: &OutcomeShares<BalanceOf<T>>, payouts: &[FixedU128]) -> BalanceOf<T> {
+        payouts
+            .iter()
+            .enumerate()
+            .fold(Zero::zero(), |acc: BalanceOf<T>, (index, payout)| {
+                acc.saturating_add(payout.saturating_mul_int(position.balance_of(index)))
+            })
+    }
+
+    // Collateral a position's payout is short of its exact value after
+    // rounding down. `payout * shares` only has a fractional part through the
+    // fractional part of the payout, and that only depends on the shares
+    // modulo the fixed-point accuracy, which keeps the product within `u128`.
+    fn rounding_remainder(position: &OutcomeShares<BalanceOf<T>>, payouts: &[FixedU128]) -> FixedU128 {
+        let unit = FixedU128::accuracy();
+        payouts
+            .iter()
+            .enumerate()
+            .fold(FixedU128::zero(), |dust, (index, payout)| {
+                let shares: u128 = position.balance_of(index).saturated_into();
+                let remainder = payout.frac().into_inner() * (shares % unit) % unit;
+                dust.saturating_add(FixedU128::from_inner(remainder))
+            })
+    }
+
+    // Rounding dust of redeeming `position`, leaving `remaining` shares
+    // outstanding: whole units released once no winning share is left to
+    // claim, and the fraction to carry in `PayoutDust`
+    fn release_dust(
+        market_id: T::Hash,
+        position: &OutcomeShares<BalanceOf<T>>,
+        remaining: &OutcomeShares<BalanceOf<T>>,
+        payouts: &[FixedU128]
+    ) -> (BalanceOf<T>, FixedU128) {
+        let dust = PayoutDust::<T>::get(market_id)
+            .saturating_add(Self::rounding_remainder(position, payouts));
+        if !Self::position_value(remaining, payouts).is_zero() {
+            return (Zero::zero(), dust);
+        }
+
+        // Shares too small to claim anything count towards the dust as well
+        let dust = dust.saturating_add(Self::rounding_remainder(remaining, payouts));
+        let whole = dust.into_inner() / FixedU128::accuracy();
+        (whole.saturated_into(), FixedU128::zero())
+    }
+
+    // Collateral `who` gets back from a market resolved invalid: their cost
+    // basis, scaled down pro rata if the pot cannot cover every trader. Each
+    // refund leaves the ratio of pot to outstanding basis as it was, so the
+    // order of claims does not matter.
+    fn invalid_refund(market_id: T::Hash, market: &MarketOf<T>, who: &T::AccountId) -> BalanceOf<T> {
+        let owed = TotalCostBasis::<T>::get(market_id);
+        let refundable = owed.min(market.total_liquidity);
+        amm::mul_div(
+            CostBasis::<T>::get(market_id, who).saturated_into::<u128>(),
+            refundable.saturated_into::<u128>(),
+            owed.saturated_into::<u128>()
+        )
+        .unwrap_or_default()
+        .saturated_into()
+    }
+
+    // Settlement value of all shares of a resolved market not redeemed yet,
+    // or the refunds outstanding on an invalid one
+    fn unclaimed_winnings(market_id: T::Hash, market: &MarketOf<T>) -> BalanceOf<T> {
+        if market.resolved_outcome == Some(MarketOutcome::Invalid) {
+            return TotalCostBasis::<T>::get(market_id).min(market.total_liquidity);
+        }
+        Self::outcome_payouts(market)
+            .map(|payouts| Self::position_value(&TotalShares::<T>::get(market_id), &payouts))
+            .unwrap_or_else(Zero::zero)
+    }
+
+    // Check the pot of `market_id` holds exactly what the market accounts
+    // for, the reap incentive included when the collateral is native, short
+    // of dust below the existential deposit lost when a pot is reaped; and
+    // that its liquidity covers the most its outstanding shares
+    // could still claim: the largest outcome balance before settlement,
+    // the unclaimed winnings after. Called at the end of every extrinsic
+    // moving a market's collateral or shares; only built with debug
+    // assertions.
+    #[cfg(debug_assertions)]
+    fn debug_assert_solvent(market_id: T::Hash) {
+        let market = match Markets::<T>::get(market_id) {
+            Some(market) => market,
+            None => return,
+        };
+        let pot = Self::market_account(market_id);
+        let (held, incentive, dust) = if market.collateral_asset == T::NativeAssetId::get() {
+            (T::Currency::free_balance(&pot), T::ReapIncentive::get(), T::Currency::minimum_balance())
+        } else {
+            (
+                <T::Assets as fungibles::Inspect<_>>::balance(market.collateral_asset, &pot),
+                Zero::zero(),
+                <T::Assets as fungibles::Inspect<_>>::minimum_balance(market.collateral_asset)
+            )
+        };
+        let set_aside = PendingRefunds::<T>::get(market_id)
+            .map_or_else(Zero::zero, |(refundable, _)| refundable)
+            .saturating_add(LpRefunds::<T>::get(market_id).map_or_else(Zero::zero, |(pot, _)| pot));
+        let accounted = market.total_liquidity
+            .saturating_add(CollectedFees::<T>::get(market_id))
+            .saturating_add(CreatorFees::<T>::get(market_id))
+            .saturating_add(set_aside)
+            .saturating_add(incentive);
+        // A migrated market hands its whole pot, incentive included, on to
+        // its replacement and is left cancelled
+        let handed_on = market.status == MarketStatus::Cancelled && accounted == incentive;
+        debug_assert!(
+            held == accounted || (held.is_zero() && (accounted < dust || handed_on)),
+            "pot of market {:?} holds {:?} but accounts for {:?}",
+            market_id, held, accounted
+        );
+
+        let obligations = match market.status {
+            MarketStatus::Resolved => Self::unclaimed_winnings(market_id, &market),
+            MarketStatus::Cancelled => Zero::zero(),
+            _ => Self::outstanding_shares(market_id, &market)
+                .into_iter()
+                .max()
+                .unwrap_or_default()
+                .saturating_mul(Self::set_value(market.scalar_bounds))
+                .saturated_into(),
+        };
+        debug_assert!(
+            market.total_liquidity >= obligations,
+            "market {:?} holds {:?} liquidity against {:?} of obligations",
+            market_id, market.total_liquidity, obligations
+        );
+    }
+
+    #[cfg(not(debug_assertions))]
+    fn debug_assert_solvent(_market_id: T::Hash) {}
+
+    // Move collateral of `asset` between accounts, through `Currency` for
+    // the native asset
+    fn transfer_collateral(
+        asset: T::AssetId,
+        from: &T::AccountId,
+        to: &T::AccountId,
+        amount: BalanceOf<T>,
+        existence: ExistenceRequirement
+    ) -> DispatchResult {
+        if asset == T::NativeAssetId::get() {
+            T::Currency::transfer(from, to, amount, existence)
+        } else {
+            let keep_alive = existence == ExistenceRequirement::KeepAlive;
+            <T::Assets as fungibles::Transfer<_>>::transfer(asset, from, to, amount, keep_alive).map(|_| ())
+        }
+    }
+
+    // Send the protocol's cut of a trade to the treasury
+    fn pay_protocol_fee(
+        market_id: T::Hash,
+        asset: T::AssetId,
+        from: &T::AccountId,
+        amount: BalanceOf<T>
+    ) -> DispatchResult {
+        if amount.is_zero() {
+            return Ok(());
+        }
+        Self::transfer_collateral(
+            asset,
+            from,
+            &T::TreasuryAccount::get(),
+            amount,
+            ExistenceRequirement::AllowDeath
+        )?;
+        Self::deposit_event(RawEvent::ProtocolFeeCollected(market_id, amount));
+        Ok(())
+    }
+
+    // Set aside a creator's cut of a trade; it stays in the market pot
+    // until claimed
+    fn accrue_creator_fee(market_id: T::Hash, amount: BalanceOf<T>) {
+        if !amount.is_zero() {
+            CreatorFees::<T>::mutate(market_id, |fees| *fees = fees.saturating_add(amount));
+        }
+    }
+
+    // Credit per-outcome shares handed back by a constant-product pool
+    fn credit_shares(market_id: T::Hash, who: &T::AccountId, shares: &[u128]) {
+        for (index, &amount) in shares.iter().enumerate().filter(|(_, a)| **a > 0) {
+            let amount = amount.saturated_into::<BalanceOf<T>>();
+            Positions::<T>::mutate(market_id, who, |position| position.mint(index, amount));
+            HeldMarkets::<T>::insert(who, market_id, ());
+            TotalShares::<T>::mutate(market_id, |totals| totals.mint(index, amount));
+        }
+    }
+
+    // Hand the treasury back as much of its subsidy as the pot holds beyond
+    // the `owed` winnings, ahead of the liquidity providers
+    fn return_subsidy(market_id: T::Hash, market: &mut MarketOf<T>, owed: BalanceOf<T>) -> DispatchResult {
+        let subsidy = Subsidies::<T>::take(market_id);
+        if subsidy.is_zero() {
+            return Ok(());
+        }
+        let refund = subsidy.min(market.total_liquidity.saturating_sub(owed));
+        if !refund.is_zero() {
+            Self::transfer_collateral(
+                market.collateral_asset,
+                &Self::market_account(market_id),
+                &T::TreasuryAccount::get(),
+                refund,
+                ExistenceRequirement::AllowDeath
+            )?;
+            market.total_liquidity = market.total_liquidity.saturating_sub(refund);
+        }
+        Self::deposit_event(RawEvent::SubsidyReturned(market_id, refund));
+        Ok(())
+    }
+
+    // Cancel a market, setting what is left of its pot, fees included, aside
+    // for its liquidity providers and releasing the creation deposit and any
+    // dispute bond. Providers are paid and positions cleared in pages by
+    // `do_process_refunds`.
+    fn cancel(market_id: T::Hash, market: &mut MarketOf<T>) -> DispatchResult {
+        Self::return_subsidy(market_id, market, Zero::zero())?;
+        // The creator forfeits their cut of a market that never resolved
+        let fees = CollectedFees::<T>::take(market_id)
+            .saturating_add(CreatorFees::<T>::take(market_id));
+        let issuance = LpIssuance::<T>::take(market_id);
+        if !issuance.is_zero() {
+            LpRefunds::<T>::insert(market_id, (market.total_liquidity.saturating_add(fees), issuance));
+        }
+
+        // Update market status
+        market.status = MarketStatus::Cancelled;
+        market.total_liquidity = Zero::zero();
+        if !market.deposit_refunded {
+            market.deposit_refunded = true;
+            T::Currency::unreserve(&market.creator, T::MarketCreationDeposit::get());
+        }
+        Self::settle_dispute_bond(market_id, market, true);
+        Self::settle_resolution_bond(market, true);
+
+        // Drop the market from the live indices
+        MarketCount::mutate(|count| *count = count.saturating_sub(1));
+        MarketsByCreator::<T>::mutate(&market.creator, |ids| ids.retain(|id| *id != market_id));
+        MarketsByCategory::<T>::mutate(market.category, |ids| ids.retain(|id| *id != market_id));
+        ActiveMarkets::<T>::remove(market_id);
+        TotalCostBasis::<T>::remove(market_id);
+        TotalShares::<T>::remove(market_id);
+        Reserves::<T>::remove(market_id);
+        OracleCommittees::<T>::remove(market_id);
+        BackupOracles::<T>::remove(market_id);
+        let _ = ResolutionVotes::<T>::remove_prefix(market_id, None);
+        StaleMarkets::<T>::remove(market_id);
+
+        Self::deposit_event(RawEvent::MarketCancelled(market.creator.clone(), market_id));
+
+        Ok(())
+    }
+
+    // Call off a market whose question no longer stands, such as a
+    // conditional market whose condition did not materialize: every trader
+    // gets back the net collateral paid for their shares and the market is
+    // cancelled. The first `MaxRefundsPerCall` holders are paid straight
+    // away, the rest through `process_refunds` or `claim_refund`.
+    fn refund_conditional(market_id: T::Hash, market: &mut MarketOf<T>) -> DispatchResult {
+        Self::set_aside_refunds(market_id, market);
+        Self::cancel(market_id, market)?;
+        if Self::refunds_pending(market_id) {
+            Self::do_process_refunds(market_id, market.collateral_asset)?;
+        }
+        Ok(())
+    }
+
+    // Cancel a market its oracle never reported on, leaving its holders and
+    // liquidity providers to claim their refunds
+    fn expire(market_id: T::Hash, market: &mut MarketOf<T>) -> DispatchResult {
+        Self::set_aside_refunds(market_id, market);
+        Self::cancel(market_id, market)?;
+        Self::deposit_event(RawEvent::MarketExpired(market_id));
+        Ok(())
+    }
+
+    // Set aside the collateral holders paid for their shares ahead of
+    // cancelling a market, scaled down pro rata if the pot cannot cover all
+    // of it; the rest goes to the LPs
+    fn set_aside_refunds(market_id: T::Hash, market: &mut MarketOf<T>) {
+        let owed = TotalCostBasis::<T>::get(market_id);
+        if !owed.is_zero() {
+            let refundable = owed.min(market.total_liquidity);
+            market.total_liquidity = market.total_liquidity.saturating_sub(refundable);
+            PendingRefunds::<T>::insert(market_id, (refundable, owed));
+        }
+    }
+
+    // Pay `holder` their pro rata share of the refunds set aside by a
+    // cancelled market and retire their position and cost basis. Each
+    // payout rounds down, so what is left set aside always covers the
+    // holders still owed.
+    fn pay_refund(
+        market_id: T::Hash,
+        asset: T::AssetId,
+        holder: &T::AccountId,
+        basis: BalanceOf<T>
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        let (refundable, owed) = PendingRefunds::<T>::get(market_id)
+            .ok_or(Error::<T>::NothingToClaim)?;
+        let refund = amm::mul_div(
+            basis.saturated_into::<u128>(),
+            refundable.saturated_into::<u128>(),
+            owed.saturated_into::<u128>()
+        )
+        .unwrap_or_default()
+        .saturated_into::<BalanceOf<T>>();
+
+        if !refund.is_zero() {
+            Self::transfer_collateral(
+                asset,
+                &Self::market_account(market_id),
+                holder,
+                refund,
+                ExistenceRequirement::AllowDeath
+            )?;
+            Self::deposit_event(RawEvent::PositionRefunded(holder.clone(), market_id, refund));
+        }
+        CostBasis::<T>::remove(market_id, holder);
+        Positions::<T>::remove(market_id, holder);
+        HeldMarkets::<T>::remove(holder, market_id);
+
+        let owed = owed.saturating_sub(basis);
+        if owed.is_zero() {
+            PendingRefunds::<T>::remove(market_id);
+        } else {
+            PendingRefunds::<T>::insert(market_id, (refundable.saturating_sub(refund), owed));
+        }
+        Ok(refund)
+    }
+
+    // Pay `provider` their pro rata share of what a cancelled market set
+    // aside for its liquidity providers and retire their LP shares
+    fn pay_lp_refund(
+        market_id: T::Hash,
+        asset: T::AssetId,
+        provider: &T::AccountId,
+        lp: BalanceOf<T>
+    ) -> DispatchResult {
+        let (pot, issuance) = LpRefunds::<T>::get(market_id)
+            .ok_or(Error::<T>::NothingToClaim)?;
+        let share = amm::mul_div(
+            pot.saturated_into::<u128>(),
+            lp.saturated_into::<u128>(),
+            issuance.saturated_into::<u128>()
+        )
+        .ok_or(Error::<T>::PricingFailed)?
+        .saturated_into::<BalanceOf<T>>();
+        Self::transfer_collateral(
+            asset,
+            &Self::market_account(market_id),
+            provider,
+            share,
+            ExistenceRequirement::AllowDeath
+        )?;
+        LpShares::<T>::remove(market_id, provider);
+
+        let issuance = issuance.saturating_sub(lp);
+        if issuance.is_zero() {
+            LpRefunds::<T>::remove(market_id);
+        } else {
+            LpRefunds::<T>::insert(market_id, (pot.saturating_sub(share), issuance));
+        }
+        Ok(())
+    }
+
+    // Whether a cancelled market still has holders or liquidity providers to
+    // pay, or positions to clear
+    fn refunds_pending(market_id: T::Hash) -> bool {
+        PendingRefunds::<T>::contains_key(market_id)
+            || LpRefunds::<T>::contains_key(market_id)
+            || CostBasis::<T>::iter_prefix(market_id).next().is_some()
+            || Positions::<T>::iter_prefix(market_id).next().is_some()
+    }
+
+    // Work through the next `MaxRefundsPerCall` items of a cancelled market:
+    // holders' refunds first, then the liquidity providers, then positions
+    // held without a cost basis. Each item drops out of storage once handled,
+    // so every call picks up where the last one stopped.
+    fn do_process_refunds(market_id: T::Hash, asset: T::AssetId) -> DispatchResult {
+        let mut budget = T::MaxRefundsPerCall::get() as usize;
+
+        let batch: Vec<_> = CostBasis::<T>::iter_prefix(market_id).take(budget).collect();
+        for (holder, basis) in batch.iter() {
+            // Holders with nothing owed are left once the refunds run out
+            if PendingRefunds::<T>::contains_key(market_id) {
+                Self::pay_refund(market_id, asset, holder, *basis)?;
+            } else {
+                CostBasis::<T>::remove(market_id, holder);
+                Positions::<T>::remove(market_id, holder);
+                HeldMarkets::<T>::remove(holder, market_id);
+            }
+        }
+        budget = budget.saturating_sub(batch.len());
+
+        let providers: Vec<_> = LpShares::<T>::iter_prefix(market_id).take(budget).collect();
+        for (provider, lp) in providers.iter() {
+            Self::pay_lp_refund(market_id, asset, provider, *lp)?;
+        }
+        budget = budget.saturating_sub(providers.len());
+
+        let holders: Vec<_> = Positions::<T>::iter_prefix(market_id)
+            .take(budget)
+            .map(|(holder, _)| holder)
+            .collect();
+        for holder in holders.iter() {
+            Positions::<T>::remove(market_id, holder);
+            HeldMarkets::<T>::remove(holder, market_id);
+        }
+
+        let remaining = PendingRefunds::<T>::get(market_id).map_or_else(Zero::zero, |(_, owed)| owed);
+        Self::deposit_event(RawEvent::RefundsProgress(market_id, batch.len() as u32, remaining));
+        Ok(())
+    }
+
+    // Queue a market for expiry once the grace period after `trading_end`
+    // has passed; full blocks spill over into the following ones
+    fn schedule_expiry(market_id: T::Hash, trading_end: T::BlockNumber) {
+        let mut at = trading_end.saturating_add(T::AutoResolveGrace::get());
+        while ExpiringMarkets::<T>::mutate(at, |ids| ids.try_push(market_id)).is_err() {
+            at = at.saturating_add(One::one());
+        }
+    }
+
+    // Current marginal price of every outcome
+    pub fn outcome_prices(market_id: T::Hash) -> Option<Vec<FixedU128>> {
+        let market = Markets::<T>::get(market_id)?;
+        let prices = match market.mechanism {
+            MarketMechanism::Lmsr => lmsr::prices(
+                &Self::outstanding_shares(market_id, &market),
+                market.liquidity_parameter.saturated_into::<u128>()
+            ),
+            MarketMechanism::ConstantProduct => amm::prices(&Self::pool_reserves(market_id)),
+        }?;
+        Self::live_prices(&market, prices)
+    }
+
+    // Blocks left until trading on a market ends, `None` once its trading
+    // end has passed or it is no longer open for trading
+    pub fn blocks_until_close(market_id: T::Hash) -> Option<T::BlockNumber> {
+        let market = Markets::<T>::get(market_id)?;
+        if !matches!(market.status, MarketStatus::Created | MarketStatus::Active) {
+            return None;
+        }
+        let now = system::Module::<T>::block_number();
+        (market.trading_end > now).then(|| market.trading_end - now)
+    }
+
+    // Price of `outcome` before and after buying `amount` of it, without
+    // executing the trade. `None` if the market cannot take the trade.
+    pub fn price_impact(
+        market_id: T::Hash,
+        outcome: u32,
+        amount: BalanceOf<T>
+    ) -> Option<(FixedU128, FixedU128)> {
+        let market = Markets::<T>::get(market_id)?;
+        if outcome >= market.outcome_count || Self::is_eliminated(&market, outcome) {
+            return None;
+        }
+        let index = outcome as usize;
+        let before = Self::outcome_prices(market_id)?.get(index).copied()?;
+
+        // Move the curve the way the trade would
+        let prices = match market.mechanism {
+            MarketMechanism::Lmsr => {
+                let mut shares = Self::outstanding_shares(market_id, &market);
+                shares[index] = shares[index].checked_add(amount.saturated_into::<u128>())?;
+                lmsr::prices(&shares, market.liquidity_parameter.saturated_into::<u128>())
+            }
+            MarketMechanism::ConstantProduct => {
+                let (_, reserves) = Self::quote_buy(market_id, &market, index, amount).ok()?;
+                let reserves = reserves?.into_iter().map(|r| r.saturated_into::<u128>()).collect::<Vec<_>>();
+                amm::prices(&reserves)
+            }
+        }?;
+        let after = Self::live_prices(&market, prices)?.get(index).copied()?;
+        Some((before, after))
+    }
+
+    // Curve prices of `market` with eliminated outcomes zeroed
+    fn live_prices(market: &MarketOf<T>, prices: Vec<FixedU128>) -> Option<Vec<FixedU128>> {
+        if market.eliminated == 0 {
+            return Some(prices);
+        }
+
+        // Eliminated outcomes are worth nothing; the survivors share the rest
+        let live = |index: usize| !Self::is_eliminated(market, index as u32);
+        let total = prices
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| live(*index))
+            .fold(FixedU128::zero(), |total, (_, price)| total.saturating_add(*price));
+        prices
+            .into_iter()
+            .enumerate()
+            .map(|(index, price)| if live(index) { price.checked_div(&total) } else { Some(FixedU128::zero()) })
+            .collect()
+    }
+
+    // Whether the oracle ruled out outcome `index` of `market`
+    fn is_eliminated(market: &MarketOf<T>, index: u32) -> bool {
+        index < 32 && market.eliminated & (1 << index) != 0
+    }
+
+    // Cost of buying `amount` shares of `outcome`, plus the new pool reserves
+    // for constant-product markets
+    fn quote_buy(
+        market_id: T::Hash,
+        market: &MarketOf<T>,
+        outcome: usize,
+        amount: BalanceOf<T>
+    ) -> Result<(BalanceOf<T>, Option<Vec<BalanceOf<T>>>), DispatchError> {
+        let amount = amount.saturated_into::<u128>();
+        let (cost, reserves) = match market.mechanism {
+            MarketMechanism::Lmsr => {
+                let cost = lmsr::buy_cost(
+                    &Self::outstanding_shares(market_id, market),
+                    market.liquidity_parameter.saturated_into::<u128>(),
+                    outcome,
+                    amount
+                )
+                .ok_or(Error::<T>::PricingFailed)?;
+                (cost, None)
+            }
+            MarketMechanism::ConstantProduct => {
+                let reserves = Self::pool_reserves(market_id);
+                let cost = amm::buy_cost(&reserves, outcome, amount)
+                    .ok_or(Error::<T>::PricingFailed)?;
+                let after = amm::apply_buy(&reserves, outcome, amount, cost)
+                    .ok_or(Error::<T>::PricingFailed)?;
+                (cost, Some(after))
+            }
+        };
+        Ok((
+            Self::collateral_for_sets(market.scalar_bounds, cost)?,
+            reserves.map(|r| r.into_iter().map(|x| x.saturated_into()).collect())
+        ))
+    }
+
+    // Proceeds of selling `amount` shares of `outcome`, plus the new pool
+    // reserves for constant-product markets
+    fn quote_sell(
+        market_id: T::Hash,
+        market: &MarketOf<T>,
+        outcome: usize,
+        amount: BalanceOf<T>
+    ) -> Result<(BalanceOf<T>, Option<Vec<BalanceOf<T>>>), DispatchError> {
+        let amount = amount.saturated_into::<u128>();
+        let (proceeds, reserves) = match market.mechanism {
+            MarketMechanism::Lmsr => {
+                let proceeds = lmsr::sell_proceeds(
+                    &Self::outstanding_shares(market_id, market),
+                    market.liquidity_parameter.saturated_into::<u128>(),
+                    outcome,
+                    amount
+                )
+                .ok_or(Error::<T>::PricingFailed)?;
+                (proceeds, None)
+            }
+            MarketMechanism::ConstantProduct => {
+                let reserves = Self::pool_reserves(market_id);
+                let proceeds = amm::sell_proceeds(&reserves, outcome, amount)
+                    .ok_or(Error::<T>::PricingFailed)?;
+                let after = amm::apply_sell(&reserves, outcome, amount, proceeds)
+                    .ok_or(Error::<T>::PricingFailed)?;
+                (proceeds, Some(after))
+            }
+        };
+        Ok((
+            Self::collateral_for_sets(market.scalar_bounds, proceeds)?,
+            reserves.map(|r| r.into_iter().map(|x| x.saturated_into()).collect())
+        ))
+    }
+
+    // Ensure trading has not been paused by the pause origin
+    fn ensure_not_paused() -> DispatchResult {
+        ensure!(!Paused::get(), Error::<T>::Paused);
+        Ok(())
+    }
+
+    // Ensure growing the pot of `market` to `total` stays within its cap
+    fn ensure_within_collateral_cap(market: &MarketOf<T>, total: BalanceOf<T>) -> DispatchResult {
+        ensure!(
+            market.max_collateral.map_or(true, |cap| total <= cap),
+            Error::<T>::CollateralCapReached
+        );
+        Ok(())
+    }
+
+    // Ensure an account holding `held` shares of an outcome stays within the
+    // position cap of `market` after taking `amount` more
+    fn ensure_within_position_cap(
+        market: &MarketOf<T>,
+        held: BalanceOf<T>,
+        amount: BalanceOf<T>
+    ) -> DispatchResult {
+        let cap = market.position_cap.unwrap_or_else(T::MaxPositionPerAccount::get);
+        ensure!(
+            held.checked_add(&amount).map_or(false, |total| total <= cap),
+            Error::<T>::PositionLimitExceeded
+        );
+        Ok(())
+    }
+
+    // Ensure `outstanding` shares of an outcome stay within the cap of `market`
+    fn ensure_within_share_cap(market: &MarketOf<T>, outstanding: BalanceOf<T>) -> DispatchResult {
+        ensure!(
+            market.max_shares.map_or(true, |cap| outstanding <= cap),
+            Error::<T>::ShareCapReached
+        );
+        Ok(())
+    }
+
+    // Block inflow after taking in `amount` more, if it stays within budget
+    fn checked_inflow(amount: BalanceOf<T>) -> Result<BalanceOf<T>, DispatchError> {
+        BlockInflow::<T>::get()
+            .checked_add(&amount)
+            .filter(|total| *total <= T::MaxCollateralInflowPerBlock::get())
+            .ok_or_else(|| Error::<T>::InflowLimitReached.into())
+    }
+
+    // Ensure a market is active and its trading window is still open
+    fn ensure_tradable(market: &MarketOf<T>) -> DispatchResult {
+        ensure!(market.status != MarketStatus::Closed, Error::<T>::TradingClosed);
+        ensure!(market.status == MarketStatus::Active, Error::<T>::MarketNotActive);
+        ensure!(
+            system::Module::<T>::block_number() < market.trading_end,
+            Error::<T>::TradingClosed
+        );
+        Ok(())
     }
 }
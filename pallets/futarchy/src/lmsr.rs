@@ -0,0 +1,248 @@
+// Logarithmic Market Scoring Rule
+//
+// Cost function C(q) = b * ln(sum_i exp(q_i / b)), where q_i is the number of
+// outstanding shares of outcome i and b is the liquidity parameter. It is
+// evaluated as
+//
+//     C(q) = max(q) + b * ln(sum_i exp((q_i - max(q)) / b))
+//
+// so that every exponent is non-positive and the log argument lies in [1, n].
+// `exp` and `ln` are bounded series approximations over `FixedU128` so the
+// module stays usable in no_std.
+
+use sp_runtime::{
+    helpers_128bit::multiply_by_rational_with_rounding,
+    traits::{CheckedAdd, CheckedDiv, CheckedMul, One, Zero},
+    FixedPointNumber, FixedU128, Rounding,
+};
+use sp_std::prelude::*;
+
+// Exponents above this are clamped: exp(-40) is below FixedU128 precision
+const MAX_EXPONENT: u128 = 40;
+// Terms used by the Taylor / atanh series
+const SERIES_TERMS: u128 = 30;
+// ln(2) and e with 18 decimals
+const LN_2: FixedU128 = FixedU128::from_inner(693_147_180_559_945_309);
+const E: FixedU128 = FixedU128::from_inner(2_718_281_828_459_045_235);
+
+// exp(x) for 0 <= x <= MAX_EXPONENT
+pub fn exp(x: FixedU128) -> Option<FixedU128> {
+    if x > FixedU128::saturating_from_integer(MAX_EXPONENT) {
+        return None;
+    }
+
+    // Split into integer and fractional parts: exp(x) = e^k * exp(f)
+    let k = x.into_inner() / FixedU128::accuracy();
+    let f = x.frac();
+
+    let mut int_part = FixedU128::one();
+    for _ in 0..k {
+        int_part = int_part.checked_mul(&E)?;
+    }
+
+    // Taylor series of exp(f) for f in [0, 1)
+    let mut term = FixedU128::one();
+    let mut frac_part = FixedU128::one();
+    for n in 1..SERIES_TERMS {
+        term = term.checked_mul(&f)? / FixedU128::saturating_from_integer(n);
+        if term.is_zero() {
+            break;
+        }
+        frac_part = frac_part.checked_add(&term)?;
+    }
+
+    int_part.checked_mul(&frac_part)
+}
+
+// exp(-x) for x >= 0, flushing to zero for large x
+pub fn exp_neg(x: FixedU128) -> FixedU128 {
+    exp(x)
+        .and_then(|e| e.reciprocal())
+        .unwrap_or_else(FixedU128::zero)
+}
+
+// ln(x) for x >= 1
+pub fn ln(x: FixedU128) -> Option<FixedU128> {
+    if x < FixedU128::one() {
+        return None;
+    }
+
+    // Reduce to m in [1, 2): ln(x) = k * ln(2) + ln(m)
+    let two = FixedU128::saturating_from_integer(2u128);
+    let mut m = x;
+    let mut k = 0u128;
+    while m >= two {
+        m = m / two;
+        k += 1;
+    }
+
+    // ln(m) = 2 * atanh(z) with z = (m - 1) / (m + 1) in [0, 1/3)
+    let z = (m - FixedU128::one()).checked_div(&(m + FixedU128::one()))?;
+    let z2 = z.checked_mul(&z)?;
+    let mut power = z;
+    let mut sum = FixedU128::zero();
+    let mut n = 1u128;
+    while n < 2 * SERIES_TERMS {
+        let term = power / FixedU128::saturating_from_integer(n);
+        if term.is_zero() {
+            break;
+        }
+        sum = sum.checked_add(&term)?;
+        power = power.checked_mul(&z2)?;
+        n += 2;
+    }
+
+    FixedU128::saturating_from_integer(k)
+        .checked_mul(&LN_2)?
+        .checked_add(&sum.checked_mul(&two)?)
+}
+
+// Shifted exponentials exp((q_i - max) / b), their sum and the max
+fn shifted_exps(q: &[u128], b: u128) -> Option<(u128, Vec<FixedU128>, FixedU128)> {
+    if b == 0 || q.is_empty() {
+        return None;
+    }
+    let max = q.iter().copied().max()?;
+    let exps: Vec<FixedU128> = q
+        .iter()
+        .map(|&q_i| {
+            FixedU128::checked_from_rational(max - q_i, b)
+                .map(exp_neg)
+                .unwrap_or_else(FixedU128::zero)
+        })
+        .collect();
+    let sum = exps
+        .iter()
+        .try_fold(FixedU128::zero(), |acc, e| acc.checked_add(e))?;
+    Some((max, exps, sum))
+}
+
+// Value of the cost function C(q), rounded down
+pub fn cost(q: &[u128], b: u128) -> Option<u128> {
+    let (max, _, sum) = shifted_exps(q, b)?;
+    max.checked_add(ln(sum)?.saturating_mul_int(b))
+}
+
+// Instantaneous price of every outcome; these sum to one
+pub fn prices(q: &[u128], b: u128) -> Option<Vec<FixedU128>> {
+    let (_, exps, sum) = shifted_exps(q, b)?;
+    exps.into_iter().map(|e| e.checked_div(&sum)).collect()
+}
+
+// Worst-case loss of the market maker, b * ln(n)
+pub fn max_loss(b: u128, outcomes: usize) -> Option<u128> {
+    let n = FixedU128::saturating_from_integer(outcomes as u128);
+    Some(ln(n)?.saturating_mul_int(b))
+}
+
+// Largest increase of b whose worst-case loss `amount` covers, amount / ln(n)
+pub fn liquidity_for(amount: u128, outcomes: usize) -> Option<u128> {
+    let n = FixedU128::saturating_from_integer(outcomes as u128);
+    let ln_n = ln(n)?.into_inner();
+    if ln_n == 0 {
+        return None;
+    }
+    multiply_by_rational_with_rounding(amount, FixedU128::accuracy(), ln_n, Rounding::Down)
+}
+
+// Collateral charged for buying `amount` shares of `outcome`.
+// Rounded up by one unit so rounding never favours the trader.
+pub fn buy_cost(q: &[u128], b: u128, outcome: usize, amount: u128) -> Option<u128> {
+    let before = cost(q, b)?;
+    let mut after_q = q.to_vec();
+    let q_i = after_q.get_mut(outcome)?;
+    *q_i = q_i.checked_add(amount)?;
+    let after = cost(&after_q, b)?;
+    after.checked_sub(before)?.checked_add(1)
+}
+
+// Collateral paid out for selling `amount` shares of `outcome`.
+// Rounded down by one unit so rounding never favours the trader.
+pub fn sell_proceeds(q: &[u128], b: u128, outcome: usize, amount: u128) -> Option<u128> {
+    let before = cost(q, b)?;
+    let mut after_q = q.to_vec();
+    let q_i = after_q.get_mut(outcome)?;
+    *q_i = q_i.checked_sub(amount)?;
+    let after = cost(&after_q, b)?;
+    Some(before.checked_sub(after)?.saturating_sub(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const B: u128 = 1_000;
+
+    // Whether `a` and `b` differ by at most `tolerance`
+    fn close(a: FixedU128, b: FixedU128, tolerance: FixedU128) -> bool {
+        let diff = if a > b { a - b } else { b - a };
+        diff <= tolerance
+    }
+
+    #[test]
+    fn prices_sum_to_one() {
+        let tolerance = FixedU128::from_inner(1_000_000_000);
+        for q in [vec![0, 0], vec![300, 0], vec![0, 50, 900], vec![5_000, 0, 0, 10]] {
+            let sum = prices(&q, B)
+                .unwrap()
+                .into_iter()
+                .fold(FixedU128::zero(), |acc, price| acc + price);
+            assert!(close(sum, FixedU128::one(), tolerance), "{:?} sums to {:?}", q, sum);
+        }
+    }
+
+    #[test]
+    fn buying_raises_the_price() {
+        let before = prices(&[0, 0], B).unwrap();
+        assert_eq!(before[0], before[1]);
+
+        let after = prices(&[100, 0], B).unwrap();
+        assert!(after[0] > before[0]);
+        assert!(after[1] < before[1]);
+    }
+
+    #[test]
+    fn cost_lies_within_max_loss_of_the_largest_outcome() {
+        // An empty book costs exactly the worst-case loss
+        assert_eq!(cost(&[0, 0], B), max_loss(B, 2));
+        assert_eq!(cost(&[0, 0, 0], B), max_loss(B, 3));
+
+        for q in [vec![400, 100], vec![0, 2_000], vec![50, 50, 50]] {
+            let largest = q.iter().copied().max().unwrap();
+            let c = cost(&q, B).unwrap();
+            assert!(c >= largest && c <= largest + max_loss(B, q.len()).unwrap());
+        }
+        assert_eq!(cost(&[], B), None);
+        assert_eq!(cost(&[1, 2], 0), None);
+    }
+
+    #[test]
+    fn buy_and_sell_round_trip_against_the_trader() {
+        let q = [200, 50];
+        let paid = buy_cost(&q, B, 0, 100).unwrap();
+        assert_eq!(paid, cost(&[300, 50], B).unwrap() - cost(&q, B).unwrap() + 1);
+        assert!(paid > 0 && paid < 100);
+
+        // Each leg rounds one unit the market maker's way
+        let received = sell_proceeds(&[300, 50], B, 0, 100).unwrap();
+        assert_eq!(paid, received + 2);
+
+        assert_eq!(sell_proceeds(&[10, 0], B, 0, 11), None);
+        assert_eq!(buy_cost(&q, B, 2, 1), None);
+    }
+
+    #[test]
+    fn max_loss_covers_any_winning_side() {
+        // ln 2 = 0.693...
+        assert_eq!(max_loss(B, 2), Some(693));
+        assert!(max_loss(B, 3).unwrap() > max_loss(B, 2).unwrap());
+
+        // Whatever traders buy, what they paid plus the max loss covers them
+        let paid = buy_cost(&[0, 0], B, 0, 5_000).unwrap();
+        assert!(max_loss(B, 2).unwrap() + paid >= 5_000);
+
+        // and funding `max_loss` buys back about the same liquidity
+        let b = liquidity_for(max_loss(B, 2).unwrap(), 2).unwrap();
+        assert!(b <= B && B - b <= 1);
+    }
+}
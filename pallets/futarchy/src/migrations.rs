@@ -0,0 +1,123 @@
+// Storage migrations for the futarchy pallet
+//
+// Each step is guarded by `StorageVersion` so it runs exactly once, and steps
+// are applied in order until the stored version is current.
+
+use super::*;
+use frame_support::{
+    storage::migration,
+    weights::Weight,
+    Blake2_128Concat,
+};
+
+// Layouts of the original prototype, before markets carried pricing,
+// resolution and oracle data
+pub mod v0 {
+    use super::*;
+
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+    pub enum MarketStatus {
+        Created,
+        Active,
+        Resolved,
+        Cancelled
+    }
+
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+    pub struct PredictionMarket<AccountId, Balance, BlockNumber, Hash> {
+        pub id: Hash,
+        pub creator: AccountId,
+        pub market_type: MarketType,
+        pub status: MarketStatus,
+        pub total_liquidity: Balance,
+        pub creation_block: BlockNumber,
+        pub resolution_block: Option<BlockNumber>,
+    }
+}
+
+// Run every migration step the stored version has not seen yet
+pub fn migrate<T: Config>() -> Weight {
+    let mut weight = T::DbWeight::get().reads(1);
+
+    if StorageVersion::get() == Releases::V0 {
+        weight = weight.saturating_add(migrate_to_v1::<T>());
+    }
+
+    weight
+}
+
+// Translate v0 markets into the current layout.
+//
+// v0 markets never held collateral or shares, so they are carried over with
+// their trading window already closed: open markets can only be resolved or
+// cancelled by their creator. The creator resolves them as oracle, pricing
+// falls back to an LMSR with no liquidity, and every market is indexed under
+// its creator and category and counted in the market stats like a freshly
+// created one.
+pub fn migrate_to_v1<T: Config>() -> Weight {
+    let old_markets: Vec<(T::Hash, v0::PredictionMarket<T::AccountId, BalanceOf<T>, T::BlockNumber, T::Hash>)> =
+        migration::storage_key_iter::<_, _, Blake2_128Concat>(b"FutarchyMarkets", b"Markets").collect();
+    let migrated = old_markets.len() as Weight;
+
+    for (market_id, old) in old_markets {
+        let status = match old.status {
+            v0::MarketStatus::Created => MarketStatus::Created,
+            v0::MarketStatus::Active => MarketStatus::Active,
+            v0::MarketStatus::Resolved => MarketStatus::Resolved,
+            v0::MarketStatus::Cancelled => MarketStatus::Cancelled,
+        };
+
+        // Index live markets the way `create_market` and `activate_market` do
+        if status != MarketStatus::Cancelled {
+            let _ = MarketsByCreator::<T>::try_mutate(&old.creator, |ids| ids.try_push(market_id));
+            let _ = MarketsByCategory::<T>::try_mutate(MarketCategory::Other, |ids| ids.try_push(market_id));
+            MarketCount::mutate(|count| *count = count.saturating_add(1));
+        }
+        if status == MarketStatus::Active {
+            ActiveMarkets::<T>::insert(market_id, ());
+        }
+
+        let market = PredictionMarket {
+            id: old.id,
+            creator: old.creator.clone(),
+            oracle: old.creator,
+            metadata: Default::default(),
+            market_type: old.market_type,
+            mechanism: MarketMechanism::Lmsr,
+            category: MarketCategory::Other,
+            collateral_asset: T::NativeAssetId::get(),
+            denom: BoundedVec::default(),
+            status,
+            total_liquidity: old.total_liquidity,
+            liquidity_parameter: Zero::zero(),
+            creation_block: old.creation_block,
+            trading_end: old.creation_block,
+            resolution_block: old.resolution_block,
+            resolved_outcome: None,
+            scalar_bounds: None,
+            outcome_count: 2,
+            outcome_labels: BoundedVec::default(),
+            deposit_refunded: false,
+            position_cap: None,
+            resolution_bond: Zero::zero(),
+            dispute_bond: Zero::zero(),
+            cloned_from: None,
+            parent_conditions: BoundedVec::default(),
+            scale: 0,
+            payout_curve: ScalarCurve::Linear,
+            creator_fee: Permill::zero(),
+            eliminated: 0,
+            max_collateral: None,
+            max_shares: None,
+            auto_activate: false,
+        };
+        Module::<T>::put_market(market_id, market);
+    }
+
+    StorageVersion::put(Releases::V1);
+
+    T::DbWeight::get().reads_writes(
+        migrated.saturating_mul(6).saturating_add(1),
+        migrated.saturating_mul(7).saturating_add(1)
+    )
+}
@@ -0,0 +1,418 @@
+// Test runtime for the futarchy pallet
+
+use crate as pallet_futarchy;
+use crate::{CreationPolicy, MarketOutcome, OnMarketResolved, PayoutRounding, TieBreak};
+use frame_support::{parameter_types, traits::Get, weights::Weight, PalletId};
+use std::cell::RefCell;
+use frame_system::EnsureRoot;
+use sp_core::H256;
+use sp_runtime::{
+    testing::Header,
+    traits::{BlakeTwo256, IdentityLookup},
+    FixedU128, Permill,
+};
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+pub type AccountId = u64;
+pub type Balance = u64;
+pub type AssetId = u32;
+
+// Well-known accounts
+pub const ALICE: AccountId = 1;
+pub const BOB: AccountId = 2;
+pub const CHARLIE: AccountId = 3;
+pub const DAVE: AccountId = 4;
+pub const TREASURY: AccountId = 99;
+
+// Asset standing for the native currency, and a stablecoin created per test
+pub const NATIVE: AssetId = 0;
+pub const USD: AssetId = 1;
+
+// Balance every well-known account starts with
+pub const INITIAL_BALANCE: Balance = 1_000_000;
+
+frame_support::construct_runtime!(
+    pub enum Test where
+        Block = Block,
+        NodeBlock = Block,
+        UncheckedExtrinsic = UncheckedExtrinsic,
+    {
+        System: frame_system::{Module, Call, Config, Storage, Event<T>},
+        Balances: pallet_balances::{Module, Call, Storage, Config<T>, Event<T>},
+        Assets: pallet_assets::{Module, Call, Storage, Event<T>},
+        Futarchy: pallet_futarchy::{Module, Call, Storage, Config<T>, Event<T>, ValidateUnsigned},
+    }
+);
+
+parameter_types! {
+    pub const BlockHashCount: u64 = 250;
+    pub const SS58Prefix: u8 = 42;
+}
+
+impl frame_system::Config for Test {
+    type BaseCallFilter = ();
+    type BlockWeights = ();
+    type BlockLength = ();
+    type DbWeight = ();
+    type Origin = Origin;
+    type Call = Call;
+    type Index = u64;
+    type BlockNumber = u64;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type AccountId = AccountId;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type Header = Header;
+    type Event = Event;
+    type BlockHashCount = BlockHashCount;
+    type Version = ();
+    type PalletInfo = PalletInfo;
+    type AccountData = pallet_balances::AccountData<Balance>;
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+    type SS58Prefix = SS58Prefix;
+    type OnSetCode = ();
+}
+
+parameter_types! {
+    pub const ExistentialDeposit: Balance = 1;
+    pub const MaxLocks: u32 = 50;
+}
+
+impl pallet_balances::Config for Test {
+    type MaxLocks = MaxLocks;
+    type Balance = Balance;
+    type Event = Event;
+    type DustRemoval = ();
+    type ExistentialDeposit = ExistentialDeposit;
+    type AccountStore = System;
+    type WeightInfo = ();
+}
+
+parameter_types! {
+    pub const AssetDeposit: Balance = 1;
+    pub const ApprovalDeposit: Balance = 1;
+    pub const StringLimit: u32 = 50;
+    pub const MetadataDepositBase: Balance = 1;
+    pub const MetadataDepositPerByte: Balance = 1;
+}
+
+impl pallet_assets::Config for Test {
+    type Event = Event;
+    type Balance = Balance;
+    type AssetId = AssetId;
+    type Currency = Balances;
+    type ForceOrigin = EnsureRoot<AccountId>;
+    type AssetDeposit = AssetDeposit;
+    type MetadataDepositBase = MetadataDepositBase;
+    type MetadataDepositPerByte = MetadataDepositPerByte;
+    type ApprovalDeposit = ApprovalDeposit;
+    type StringLimit = StringLimit;
+    type Freezer = ();
+    type Extra = ();
+    type WeightInfo = ();
+}
+
+thread_local! {
+    static ALLOW_CREATOR_RESOLUTION: RefCell<bool> = RefCell::new(true);
+    static PAYOUT_ROUNDING: RefCell<PayoutRounding> = RefCell::new(PayoutRounding::DustToTreasury);
+    static CREATION_POLICY: RefCell<CreationPolicy> = RefCell::new(CreationPolicy::Permissionless);
+    static TIE_BREAK: RefCell<TieBreak> = RefCell::new(TieBreak::LowestIndex);
+    static MAX_ACTIVE_MARKETS: RefCell<u32> = RefCell::new(100);
+    static MIN_CREATED_PERIOD: RefCell<u64> = RefCell::new(0);
+    static MAX_COLLATERAL_INFLOW: RefCell<Balance> = RefCell::new(Balance::max_value());
+    static CREATOR_COOLDOWN: RefCell<u64> = RefCell::new(0);
+    static LP_WITHDRAWAL_DELAY: RefCell<u64> = RefCell::new(0);
+    static PROTOCOL_FEE: RefCell<Permill> = RefCell::new(Permill::zero());
+    static RESOLVED_MARKETS: RefCell<Vec<(H256, MarketOutcome)>> = RefCell::new(Vec::new());
+}
+
+// Creator resolution switch, toggled per test with `set_allow_creator_resolution`
+pub struct AllowCreatorResolution;
+impl Get<bool> for AllowCreatorResolution {
+    fn get() -> bool {
+        ALLOW_CREATOR_RESOLUTION.with(|allow| *allow.borrow())
+    }
+}
+
+pub fn set_allow_creator_resolution(allow: bool) {
+    ALLOW_CREATOR_RESOLUTION.with(|value| *value.borrow_mut() = allow);
+}
+
+// Payout dust policy, toggled per test with `set_payout_rounding`
+pub struct ConfiguredPayoutRounding;
+impl Get<PayoutRounding> for ConfiguredPayoutRounding {
+    fn get() -> PayoutRounding {
+        PAYOUT_ROUNDING.with(|rounding| *rounding.borrow())
+    }
+}
+
+pub fn set_payout_rounding(rounding: PayoutRounding) {
+    PAYOUT_ROUNDING.with(|value| *value.borrow_mut() = rounding);
+}
+
+// Market creation policy, toggled per test with `set_creation_policy`
+pub struct ConfiguredCreationPolicy;
+impl Get<CreationPolicy> for ConfiguredCreationPolicy {
+    fn get() -> CreationPolicy {
+        CREATION_POLICY.with(|policy| *policy.borrow())
+    }
+}
+
+pub fn set_creation_policy(policy: CreationPolicy) {
+    CREATION_POLICY.with(|value| *value.borrow_mut() = policy);
+}
+
+// Committee tie-break policy, toggled per test with `set_tie_break`
+pub struct ConfiguredTieBreak;
+impl Get<TieBreak> for ConfiguredTieBreak {
+    fn get() -> TieBreak {
+        TIE_BREAK.with(|policy| *policy.borrow())
+    }
+}
+
+pub fn set_tie_break(policy: TieBreak) {
+    TIE_BREAK.with(|value| *value.borrow_mut() = policy);
+}
+
+// Cap on concurrently active markets, lowered per test with
+// `set_max_active_markets`
+pub struct ConfiguredMaxActiveMarkets;
+impl Get<u32> for ConfiguredMaxActiveMarkets {
+    fn get() -> u32 {
+        MAX_ACTIVE_MARKETS.with(|max| *max.borrow())
+    }
+}
+
+pub fn set_max_active_markets(max: u32) {
+    MAX_ACTIVE_MARKETS.with(|value| *value.borrow_mut() = max);
+}
+
+// Wait before activation, raised per test with `set_min_created_period`
+pub struct ConfiguredMinCreatedPeriod;
+impl Get<u64> for ConfiguredMinCreatedPeriod {
+    fn get() -> u64 {
+        MIN_CREATED_PERIOD.with(|period| *period.borrow())
+    }
+}
+
+pub fn set_min_created_period(period: u64) {
+    MIN_CREATED_PERIOD.with(|value| *value.borrow_mut() = period);
+}
+
+// Per-block inflow budget, lowered per test with `set_max_collateral_inflow`
+pub struct ConfiguredMaxCollateralInflow;
+impl Get<Balance> for ConfiguredMaxCollateralInflow {
+    fn get() -> Balance {
+        MAX_COLLATERAL_INFLOW.with(|max| *max.borrow())
+    }
+}
+
+pub fn set_max_collateral_inflow(max: Balance) {
+    MAX_COLLATERAL_INFLOW.with(|value| *value.borrow_mut() = max);
+}
+
+// Wait between creations, raised per test with `set_creator_cooldown`
+pub struct ConfiguredCreatorCooldown;
+impl Get<u64> for ConfiguredCreatorCooldown {
+    fn get() -> u64 {
+        CREATOR_COOLDOWN.with(|cooldown| *cooldown.borrow())
+    }
+}
+
+pub fn set_creator_cooldown(cooldown: u64) {
+    CREATOR_COOLDOWN.with(|value| *value.borrow_mut() = cooldown);
+}
+
+// Lock on resolved pots, raised per test with `set_lp_withdrawal_delay`
+pub struct ConfiguredLpWithdrawalDelay;
+impl Get<u64> for ConfiguredLpWithdrawalDelay {
+    fn get() -> u64 {
+        LP_WITHDRAWAL_DELAY.with(|delay| *delay.borrow())
+    }
+}
+
+pub fn set_lp_withdrawal_delay(delay: u64) {
+    LP_WITHDRAWAL_DELAY.with(|value| *value.borrow_mut() = delay);
+}
+
+// Treasury cut of each trade, raised per test with `set_protocol_fee`
+pub struct ConfiguredProtocolFee;
+impl Get<Permill> for ConfiguredProtocolFee {
+    fn get() -> Permill {
+        PROTOCOL_FEE.with(|fee| *fee.borrow())
+    }
+}
+
+pub fn set_protocol_fee(fee: Permill) {
+    PROTOCOL_FEE.with(|value| *value.borrow_mut() = fee);
+}
+
+// Records every final outcome, read back with `resolved_markets`
+pub struct RecordResolutions;
+impl OnMarketResolved<H256, MarketOutcome> for RecordResolutions {
+    fn on_market_resolved(market_id: H256, outcome: &MarketOutcome) {
+        RESOLVED_MARKETS.with(|resolved| resolved.borrow_mut().push((market_id, outcome.clone())));
+    }
+}
+
+pub fn resolved_markets() -> Vec<(H256, MarketOutcome)> {
+    RESOLVED_MARKETS.with(|resolved| resolved.borrow().clone())
+}
+
+parameter_types! {
+    pub const MarketCreationDeposit: Balance = 100;
+    pub const FutarchyPalletId: PalletId = PalletId(*b"py/futar");
+    pub const MaxOutcomes: u32 = 8;
+    pub const MaxScalarScale: u8 = 6;
+    pub const MaxScalarRange: u128 = 1_000_000;
+    pub const MaxMetadataLen: u32 = 64;
+    pub const MaxDenomLen: u32 = 8;
+    pub const MaxLabelLen: u32 = 16;
+    pub const DisputePeriod: u64 = 10;
+    pub const MinDisputeBond: Balance = 50;
+    pub const DisputeBondPercent: Permill = Permill::from_percent(2);
+    pub const ResolutionBond: Balance = 25;
+    pub const TradingFee: Permill = Permill::from_percent(1);
+    pub const MaxCreatorFee: Permill = Permill::from_percent(5);
+    pub const TreasuryAccount: AccountId = TREASURY;
+    pub const MaxMarketsPerCreator: u32 = 16;
+    pub const MaxPerCategory: u32 = 4;
+    pub const MaxOracles: u32 = 4;
+    pub const MaxConditions: u32 = 4;
+    pub const MaxRedeemBatch: u32 = 8;
+    pub const MaxLeaderboard: u32 = 3;
+    pub const NativeAssetId: AssetId = NATIVE;
+    pub const MinActivationLiquidity: Balance = 500;
+    pub const MaxBatchSize: u32 = 4;
+    pub const MaxExtension: u64 = 20;
+    pub const AutoResolveGrace: u64 = 5;
+    pub const OracleSlaWindow: u64 = 3;
+    pub const MaxAutoResolvePerBlock: u32 = 2;
+    pub const MaxRefundsPerCall: u32 = 2;
+    pub const MaxMarketLifetime: u64 = 100;
+    pub const ReapIncentive: Balance = 10;
+    pub const MaxPositionPerAccount: Balance = 1_000;
+    pub const MinTradeAmount: Balance = 2;
+    pub const MaxHistoryPoints: u32 = 3;
+    // 0.001
+    pub const PriceHistoryEpsilon: FixedU128 = FixedU128::from_inner(1_000_000_000_000_000);
+    pub ExecutionOrigin: Origin = frame_system::RawOrigin::Root.into();
+    pub const MaxExecutionWeight: Weight = 1_000_000_000;
+    pub const UnsignedPriority: u64 = 1 << 20;
+}
+
+impl pallet_futarchy::Config for Test {
+    type Event = Event;
+    type Currency = Balances;
+    type AssetId = AssetId;
+    type Assets = Assets;
+    type NativeAssetId = NativeAssetId;
+    type MarketCreationDeposit = MarketCreationDeposit;
+    type PalletId = FutarchyPalletId;
+    type MaxOutcomes = MaxOutcomes;
+    type MaxScalarScale = MaxScalarScale;
+    type MaxScalarRange = MaxScalarRange;
+    type MaxMetadataLen = MaxMetadataLen;
+    type MaxDenomLen = MaxDenomLen;
+    type MaxLabelLen = MaxLabelLen;
+    type DisputePeriod = DisputePeriod;
+    type LpWithdrawalDelay = ConfiguredLpWithdrawalDelay;
+    type MinDisputeBond = MinDisputeBond;
+    type DisputeBondPercent = DisputeBondPercent;
+    type ResolutionBond = ResolutionBond;
+    type DisputeOrigin = EnsureRoot<AccountId>;
+    type ForceOrigin = EnsureRoot<AccountId>;
+    type PauseOrigin = EnsureRoot<AccountId>;
+    type SubsidyOrigin = EnsureRoot<AccountId>;
+    type CurationOrigin = EnsureRoot<AccountId>;
+    type MarketCreationPolicy = ConfiguredCreationPolicy;
+    type CreatorApprovalOrigin = EnsureRoot<AccountId>;
+    type TradingFee = TradingFee;
+    type ProtocolFee = ConfiguredProtocolFee;
+    type MaxCreatorFee = MaxCreatorFee;
+    type TreasuryAccount = TreasuryAccount;
+    type PayoutRounding = ConfiguredPayoutRounding;
+    type TieBreak = ConfiguredTieBreak;
+    type OnMarketResolved = RecordResolutions;
+    type MaxMarketsPerCreator = MaxMarketsPerCreator;
+    type MaxPerCategory = MaxPerCategory;
+    type MaxOracles = MaxOracles;
+    type MaxConditions = MaxConditions;
+    type MaxRedeemBatch = MaxRedeemBatch;
+    type MaxLeaderboard = MaxLeaderboard;
+    type AllowCreatorResolution = AllowCreatorResolution;
+    type MinActivationLiquidity = MinActivationLiquidity;
+    type MinCreatedPeriod = ConfiguredMinCreatedPeriod;
+    type CreatorCooldown = ConfiguredCreatorCooldown;
+    type MaxBatchSize = MaxBatchSize;
+    type MaxExtension = MaxExtension;
+    type AutoResolveGrace = AutoResolveGrace;
+    type OracleSlaWindow = OracleSlaWindow;
+    type MaxAutoResolvePerBlock = MaxAutoResolvePerBlock;
+    type MaxRefundsPerCall = MaxRefundsPerCall;
+    type MaxMarketLifetime = MaxMarketLifetime;
+    type ReapIncentive = ReapIncentive;
+    type MaxPositionPerAccount = MaxPositionPerAccount;
+    type MinTradeAmount = MinTradeAmount;
+    type MaxHistoryPoints = MaxHistoryPoints;
+    type PriceHistoryEpsilon = PriceHistoryEpsilon;
+    type Call = Call;
+    type ExecutionOrigin = ExecutionOrigin;
+    type MaxExecutionWeight = MaxExecutionWeight;
+    type UnsignedPriority = UnsignedPriority;
+    type MaxActiveMarkets = ConfiguredMaxActiveMarkets;
+    type MaxCollateralInflowPerBlock = ConfiguredMaxCollateralInflow;
+    type WeightInfo = ();
+}
+
+// Unsigned transactions from the off-chain worker go into the test pool
+impl<C> frame_system::offchain::SendTransactionTypes<C> for Test where Call: From<C> {
+    type OverarchingCall = Call;
+    type Extrinsic = UncheckedExtrinsic;
+}
+
+// Build test externalities with funded well-known accounts at block 1
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    new_test_ext_with_markets(vec![])
+}
+
+// As `new_test_ext`, also creating `initial_markets` at genesis
+pub fn new_test_ext_with_markets(
+    initial_markets: Vec<(
+        AccountId,
+        pallet_futarchy::MarketType,
+        pallet_futarchy::MarketMechanism,
+        u64,
+        Balance,
+        Option<(i128, i128)>,
+        Option<u32>
+    )>
+) -> sp_io::TestExternalities {
+    let mut storage = frame_system::GenesisConfig::default()
+        .build_storage::<Test>()
+        .unwrap();
+
+    pallet_balances::GenesisConfig::<Test> {
+        balances: vec![
+            (ALICE, INITIAL_BALANCE),
+            (BOB, INITIAL_BALANCE),
+            (CHARLIE, INITIAL_BALANCE),
+            (DAVE, INITIAL_BALANCE),
+            (TREASURY, 1),
+        ],
+    }
+    .assimilate_storage(&mut storage)
+    .unwrap();
+
+    pallet_futarchy::GenesisConfig::<Test> { initial_markets }
+        .assimilate_storage(&mut storage)
+        .unwrap();
+
+    let mut ext = sp_io::TestExternalities::new(storage);
+    ext.execute_with(|| System::set_block_number(1));
+    ext
+}
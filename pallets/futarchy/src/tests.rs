@@ -0,0 +1,4888 @@
+// Unit tests for the futarchy pallet
+
+use crate::{
+    migrations::v0, mock::*, CollectedFees, CreationPolicy, Error, HeldMarkets, MarketCategory, MarketMechanism, Markets, MarketOutcome, MarketSpecOf, MarketStatus, MarketType, OutcomeShares, PayoutRounding,
+    Positions, RawEvent, Releases, ScalarCurve, StorageVersion, TieBreak, scalar_outcome_index
+};
+use codec::{Decode, Encode};
+use frame_support::{
+    assert_noop, assert_ok,
+    storage::{migration, StorageDoubleMap, StorageMap, StorageValue},
+    traits::{OffchainWorker, OnInitialize, OnRuntimeUpgrade},
+    unsigned::ValidateUnsigned,
+    Blake2_128Concat, StorageHasher
+};
+use sp_core::{
+    offchain::{testing::TestTransactionPoolExt, TransactionPoolExt},
+    H256
+};
+use sp_runtime::{
+    traits::{BlakeTwo256, Hash},
+    transaction_validity::TransactionSource,
+    FixedPointNumber, FixedU128, Permill
+};
+
+// Block on which markets created by `create_binary` stop trading
+const TRADING_END: u64 = 10;
+// LMSR liquidity parameter of markets created by `create_binary`
+const LIQUIDITY: Balance = 1_000;
+
+// Create a binary LMSR market from `who` and return its id
+fn create_binary(who: AccountId) -> H256 {
+    assert_ok!(Futarchy::create_market(
+        Origin::signed(who),
+        MarketType::Binary,
+        MarketMechanism::Lmsr,
+        TRADING_END,
+        LIQUIDITY,
+        0,
+        None,
+        None,
+        b"Will it rain tomorrow?".to_vec(),
+        None,
+        None,
+        MarketCategory::Other,
+        Vec::new(),
+        Vec::new()
+    ));
+    *Futarchy::markets_by_creator(who).last().unwrap()
+}
+
+// Advance to block `n`, running the pallet's block hook on the way
+fn run_to_block(n: u64) {
+    while System::block_number() < n {
+        System::set_block_number(System::block_number() + 1);
+        Futarchy::on_initialize(System::block_number());
+    }
+}
+
+// Create and activate a binary market from `who`
+fn create_active_binary(who: AccountId) -> H256 {
+    let market_id = create_binary(who);
+    assert_ok!(Futarchy::activate_market(Origin::signed(who), market_id));
+    market_id
+}
+
+// Create an LMSR decision market on a metric in [0, 100] from `who`,
+// returning the decision and its enacted and rejected markets
+fn create_decision(who: AccountId) -> (H256, H256, H256) {
+    create_decision_with_call(who, None)
+}
+
+// Create a decision market that enacts `call`
+fn create_decision_with_call(who: AccountId, call: Option<Call>) -> (H256, H256, H256) {
+    let nonce = Futarchy::creation_nonce(who);
+    assert_ok!(Futarchy::create_decision_market(
+        Origin::signed(who),
+        b"Raise the block size".to_vec(),
+        (0, 100),
+        MarketMechanism::Lmsr,
+        TRADING_END,
+        LIQUIDITY,
+        None,
+        call.map(Box::new)
+    ));
+    // The decision takes the creator's next nonce, its two markets the ones after
+    let decision_id = (System::block_number(), who, nonce).using_encoded(BlakeTwo256::hash);
+    let decision = Futarchy::decision_markets(decision_id).unwrap();
+    (decision_id, decision.enacted_market, decision.rejected_market)
+}
+
+#[test]
+fn create_market_reserves_deposit() {
+    new_test_ext().execute_with(|| {
+        create_binary(ALICE);
+
+        assert_eq!(Balances::reserved_balance(ALICE), MarketCreationDeposit::get());
+    });
+}
+
+#[test]
+fn cancel_market_returns_deposit() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_binary(ALICE);
+        let free = Balances::free_balance(ALICE);
+
+        assert_ok!(Futarchy::cancel_market(Origin::signed(ALICE), market_id));
+
+        assert_eq!(Balances::reserved_balance(ALICE), 0);
+        assert!(Balances::free_balance(ALICE) >= free + MarketCreationDeposit::get());
+        assert_eq!(Futarchy::markets(market_id).unwrap().status, MarketStatus::Cancelled);
+        System::assert_has_event(Event::Futarchy(RawEvent::MarketCancelled(ALICE, market_id)));
+    });
+}
+
+#[test]
+fn cancel_market_rejects_resolved_market() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+        System::set_block_number(TRADING_END);
+        assert_ok!(Futarchy::resolve_market(Origin::signed(ALICE), market_id, MarketOutcome::Binary(true)));
+
+        assert_noop!(
+            Futarchy::cancel_market(Origin::signed(ALICE), market_id),
+            Error::<Test>::MarketNotCancellable
+        );
+    });
+}
+
+#[test]
+fn positions_round_trip_through_storage() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_binary(ALICE);
+        assert!(Futarchy::positions(market_id, BOB).is_empty());
+
+        let mut position = OutcomeShares::<Balance>::default();
+        position.mint(0, 30);
+        position.mint(1, 70);
+        Positions::<Test>::insert(market_id, BOB, position.clone());
+
+        let stored = Futarchy::positions(market_id, BOB);
+        assert_eq!(stored, position);
+        assert_eq!(stored.balance_of(0), 30);
+        assert_eq!(stored.balance_of(1), 70);
+        assert_eq!(stored.total(), 100);
+        // A length prefix followed by one balance per outcome
+        assert_eq!(stored.encode(), vec![30u64, 70u64].encode());
+        assert!(Futarchy::positions(market_id, CHARLIE).is_empty());
+    });
+}
+
+#[test]
+fn create_market_stores_market() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_binary(ALICE);
+
+        let market = Futarchy::markets(market_id).unwrap();
+        assert_eq!(market.id, market_id);
+        assert_eq!(market.creator, ALICE);
+        assert_eq!(market.oracle, ALICE);
+        assert_eq!(market.market_type, MarketType::Binary);
+        assert_eq!(market.status, MarketStatus::Created);
+        assert_eq!(market.trading_end, TRADING_END);
+        assert_eq!(market.outcome_count, 2);
+        assert_eq!(Futarchy::market_count(), 1);
+    });
+}
+
+#[test]
+fn market_count_follows_creation_and_cancellation() {
+    new_test_ext().execute_with(|| {
+        let first = create_binary(ALICE);
+        create_binary(BOB);
+        create_binary(CHARLIE);
+        assert_eq!(Futarchy::market_count(), 3);
+
+        assert_ok!(Futarchy::cancel_market(Origin::signed(ALICE), first));
+        assert_eq!(Futarchy::market_count(), 2);
+    });
+}
+
+#[test]
+fn create_market_funds_market_pot() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_binary(ALICE);
+
+        let market = Futarchy::markets(market_id).unwrap();
+        assert!(market.total_liquidity > 0);
+        assert_eq!(
+            Balances::free_balance(Futarchy::market_account(market_id)),
+            market.total_liquidity + ReapIncentive::get()
+        );
+        assert_eq!(
+            Balances::free_balance(ALICE),
+            INITIAL_BALANCE - MarketCreationDeposit::get() - ReapIncentive::get() - market.total_liquidity
+        );
+    });
+}
+
+#[test]
+fn create_market_emits_event() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_binary(ALICE);
+
+        let market = Futarchy::markets(market_id).unwrap();
+        System::assert_last_event(Event::Futarchy(RawEvent::MarketCreated(
+            market.creator,
+            market.id,
+            market.market_type,
+            market.trading_end,
+            Vec::new()
+        )));
+    });
+}
+
+// Create a binary market from ALICE asking the question at `metadata`
+fn create_binary_with_metadata(metadata: Vec<u8>) -> frame_support::dispatch::DispatchResult {
+    Futarchy::create_market(
+        Origin::signed(ALICE),
+        MarketType::Binary,
+        MarketMechanism::Lmsr,
+        TRADING_END,
+        LIQUIDITY,
+        0,
+        None,
+        None,
+        metadata,
+        None,
+        None,
+        MarketCategory::Other,
+        Vec::new(),
+        Vec::new()
+    )
+}
+
+#[test]
+fn create_market_stores_cid_metadata() {
+    new_test_ext().execute_with(|| {
+        let cid = b"QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG".to_vec();
+
+        assert_ok!(create_binary_with_metadata(cid.clone()));
+
+        let market_id = *Futarchy::markets_by_creator(ALICE).last().unwrap();
+        assert_eq!(Futarchy::markets(market_id).unwrap().metadata.into_inner(), cid);
+        assert_noop!(
+            create_binary_with_metadata(vec![0; MaxMetadataLen::get() as usize + 1]),
+            Error::<Test>::MetadataTooLong
+        );
+    });
+}
+
+#[test]
+fn create_market_records_collateral_denom() {
+    new_test_ext().execute_with(|| {
+        let create = |denom: &[u8]| Futarchy::create_market(
+            Origin::signed(ALICE),
+            MarketType::Binary,
+            MarketMechanism::Lmsr,
+            TRADING_END,
+            LIQUIDITY,
+            0,
+            None,
+            None,
+            Vec::new(),
+            None,
+            None,
+            MarketCategory::Other,
+            denom.to_vec(),
+            Vec::new()
+        );
+
+        assert_noop!(create(b"TOOLONGDENOM"), Error::<Test>::DenomTooLong);
+        assert_ok!(create(b"DOT"));
+
+        let market_id = *Futarchy::markets_by_creator(ALICE).last().unwrap();
+        assert_eq!(Futarchy::market_denom(market_id), Some(b"DOT".to_vec()));
+        System::assert_last_event(Event::Futarchy(RawEvent::MarketCreated(
+            ALICE,
+            market_id,
+            MarketType::Binary,
+            TRADING_END,
+            b"DOT".to_vec()
+        )));
+    });
+}
+
+#[test]
+fn create_market_records_outcome_labels() {
+    new_test_ext().execute_with(|| {
+        let create = |labels: Vec<Vec<u8>>| Futarchy::create_market(
+            Origin::signed(ALICE),
+            MarketType::Categorical,
+            MarketMechanism::Lmsr,
+            TRADING_END,
+            LIQUIDITY,
+            0,
+            None,
+            Some(3),
+            Vec::new(),
+            None,
+            None,
+            MarketCategory::Other,
+            Vec::new(),
+            labels
+        );
+        let labels = vec![b"Alice".to_vec(), b"Bob".to_vec(), b"Carol".to_vec()];
+
+        assert_noop!(create(labels[..2].to_vec()), Error::<Test>::LabelCountMismatch);
+        assert_noop!(create(vec![b"x".to_vec(); MaxOutcomes::get() as usize + 1]), Error::<Test>::LabelCountMismatch);
+        assert_noop!(
+            create(vec![labels[0].clone(), labels[1].clone(), vec![0u8; MaxLabelLen::get() as usize + 1]]),
+            Error::<Test>::LabelTooLong
+        );
+        assert_ok!(create(labels.clone()));
+
+        let market_id = *Futarchy::markets_by_creator(ALICE).last().unwrap();
+        assert_eq!(Futarchy::outcome_labels(market_id), Some(labels));
+    });
+}
+
+// Create a categorical LMSR market from ALICE with `outcomes` outcomes
+fn create_categorical(outcomes: Option<u32>) -> frame_support::dispatch::DispatchResult {
+    Futarchy::create_market(
+        Origin::signed(ALICE),
+        MarketType::Categorical,
+        MarketMechanism::Lmsr,
+        TRADING_END,
+        LIQUIDITY,
+        0,
+        None,
+        outcomes,
+        Vec::new(),
+        None,
+        None,
+        MarketCategory::Other,
+        Vec::new(),
+        Vec::new()
+    )
+}
+
+#[test]
+fn categorical_market_needs_two_to_max_outcomes() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(create_categorical(None), Error::<Test>::TooFewOutcomes);
+        assert_noop!(create_categorical(Some(0)), Error::<Test>::TooFewOutcomes);
+        assert_noop!(create_categorical(Some(1)), Error::<Test>::TooFewOutcomes);
+        assert_noop!(create_categorical(Some(MaxOutcomes::get() + 1)), Error::<Test>::TooManyOutcomes);
+        assert_ok!(create_categorical(Some(2)));
+        assert_ok!(create_categorical(Some(MaxOutcomes::get())));
+    });
+}
+
+#[test]
+fn five_way_categorical_market_pays_its_winner() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_categorical(Some(5)));
+        let market_id = *Futarchy::markets_by_creator(ALICE).last().unwrap();
+        assert_ok!(Futarchy::activate_market(Origin::signed(ALICE), market_id));
+        assert_eq!(Futarchy::markets(market_id).unwrap().outcome_count, 5);
+
+        // Every outcome opens at one fifth
+        let prices = Futarchy::outcome_prices(market_id).unwrap();
+        assert_eq!(prices.len(), 5);
+        assert!(prices.windows(2).all(|pair| pair[0] == pair[1]));
+
+        assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), market_id, 3, 100, None));
+        assert_ok!(Futarchy::buy_shares(Origin::signed(CHARLIE), market_id, 4, 100, None));
+        assert_noop!(
+            Futarchy::buy_shares(Origin::signed(BOB), market_id, 5, 100, None),
+            Error::<Test>::InvalidOutcome
+        );
+        let prices = Futarchy::outcome_prices(market_id).unwrap();
+        assert!(prices[3] > prices[0] && prices[3] == prices[4]);
+
+        System::set_block_number(TRADING_END);
+        assert_ok!(Futarchy::resolve_market(Origin::signed(ALICE), market_id, MarketOutcome::Categorical(3)));
+        System::set_block_number(TRADING_END + DisputePeriod::get());
+        let before = Balances::free_balance(BOB);
+        assert_ok!(Futarchy::claim_winnings(Origin::signed(BOB), market_id));
+        assert_eq!(Balances::free_balance(BOB) - before, 100);
+        assert_noop!(
+            Futarchy::claim_winnings(Origin::signed(CHARLIE), market_id),
+            Error::<Test>::NothingToClaim
+        );
+    });
+}
+
+#[test]
+fn scalar_bounds_must_match_market_type() {
+    new_test_ext().execute_with(|| {
+        let create = |market_type: MarketType, bounds: Option<(i128, i128)>| Futarchy::create_market(
+            Origin::signed(ALICE),
+            market_type,
+            MarketMechanism::Lmsr,
+            TRADING_END,
+            LIQUIDITY,
+            0,
+            bounds,
+            None,
+            Vec::new(),
+            None,
+            None,
+            MarketCategory::Other,
+            Vec::new(),
+            Vec::new()
+        );
+
+        // Scalar markets need a non-empty range, other types none at all
+        assert_noop!(create(MarketType::Scalar, None), Error::<Test>::InvalidScalarBounds);
+        assert_noop!(create(MarketType::Scalar, Some((10, 10))), Error::<Test>::InvalidScalarBounds);
+        assert_noop!(create(MarketType::Scalar, Some((10, -10))), Error::<Test>::InvalidScalarBounds);
+        assert_noop!(create(MarketType::Binary, Some((0, 100))), Error::<Test>::InvalidScalarBounds);
+        assert_ok!(create(MarketType::Scalar, Some((-10, 10))));
+    });
+}
+
+#[test]
+fn scalar_range_bounded_by_max_scalar_range() {
+    new_test_ext().execute_with(|| {
+        let create = |bounds: (i128, i128)| Futarchy::create_market(
+            Origin::signed(ALICE),
+            MarketType::Scalar,
+            MarketMechanism::Lmsr,
+            TRADING_END,
+            LIQUIDITY,
+            0,
+            Some(bounds),
+            None,
+            Vec::new(),
+            None,
+            None,
+            MarketCategory::Other,
+            Vec::new(),
+            Vec::new()
+        );
+        let max = MaxScalarRange::get() as i128;
+        // Every set of the widest range is minted for the whole width
+        assert_ok!(Balances::set_balance(Origin::root(), ALICE, 2_000_000_000, 0));
+
+        assert_noop!(create((-1, max)), Error::<Test>::ScalarRangeTooLarge);
+        assert_noop!(create((i128::MIN, i128::MAX)), Error::<Test>::ScalarRangeTooLarge);
+        assert_ok!(create((0, max)));
+        assert_ok!(create((-max / 2, max / 2)));
+    });
+}
+
+#[test]
+fn create_market_rejects_past_trading_end() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            Futarchy::create_market(
+                Origin::signed(ALICE),
+                MarketType::Binary,
+                MarketMechanism::Lmsr,
+                1,
+                LIQUIDITY,
+                0,
+                None,
+                None,
+                Vec::new(),
+                None,
+                None,
+                MarketCategory::Other,
+                Vec::new(),
+                Vec::new()
+            ),
+            Error::<Test>::InvalidTradingEnd
+        );
+    });
+}
+
+#[test]
+fn create_market_seeds_initial_liquidity() {
+    new_test_ext().execute_with(|| {
+        let seed = 5_000;
+        assert_ok!(Futarchy::create_market(
+            Origin::signed(ALICE),
+            MarketType::Categorical,
+            MarketMechanism::ConstantProduct,
+            TRADING_END,
+            LIQUIDITY,
+            seed,
+            None,
+            Some(3),
+            Vec::new(),
+            None,
+            None,
+            MarketCategory::Other,
+            Vec::new(),
+            Vec::new()
+        ));
+        let market_id = *Futarchy::markets_by_creator(ALICE).last().unwrap();
+
+        // The seed is minted one LP share per unit, like the subsidy
+        let market = Futarchy::markets(market_id).unwrap();
+        assert_eq!(Futarchy::lp_shares(market_id, ALICE), LIQUIDITY + seed);
+        assert_eq!(Futarchy::lp_issuance(market_id), LIQUIDITY + seed);
+        assert_eq!(market.total_liquidity, LIQUIDITY + seed);
+        // Funding a balanced pool hands back no loose shares
+        assert!(Futarchy::positions(market_id, ALICE).is_empty());
+        assert_eq!(Futarchy::reserves(market_id), vec![LIQUIDITY + seed; 3]);
+        System::assert_last_event(Event::Futarchy(RawEvent::LiquidityAdded(ALICE, market_id, seed, seed)));
+        assert!(System::events().iter().any(|record| {
+            record.event == Event::Futarchy(RawEvent::MarketCreated(
+                ALICE,
+                market_id,
+                MarketType::Categorical,
+                TRADING_END,
+                Vec::new()
+            ))
+        }));
+    });
+}
+
+#[test]
+fn create_market_requires_funds_for_seed() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            Futarchy::create_market(
+                Origin::signed(ALICE),
+                MarketType::Binary,
+                MarketMechanism::ConstantProduct,
+                TRADING_END,
+                LIQUIDITY,
+                INITIAL_BALANCE,
+                None,
+                None,
+                Vec::new(),
+                None,
+                None,
+                MarketCategory::Other,
+                Vec::new(),
+                Vec::new()
+            ),
+            Error::<Test>::InsufficientFunds
+        );
+    });
+}
+
+#[test]
+fn resolve_market_requires_active_market() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_binary(ALICE);
+        System::set_block_number(TRADING_END);
+
+        assert_noop!(
+            Futarchy::resolve_market(Origin::signed(ALICE), market_id, MarketOutcome::Binary(true)),
+            Error::<Test>::MarketNotResolvable
+        );
+    });
+}
+
+#[test]
+fn resolve_market_requires_oracle() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+        System::set_block_number(TRADING_END);
+
+        assert_noop!(
+            Futarchy::resolve_market(Origin::signed(BOB), market_id, MarketOutcome::Binary(true)),
+            Error::<Test>::NotAuthorizedOracle
+        );
+    });
+}
+
+#[test]
+fn resolve_market_waits_for_trading_end() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+
+        assert_noop!(
+            Futarchy::resolve_market(Origin::signed(ALICE), market_id, MarketOutcome::Binary(true)),
+            Error::<Test>::TradingStillOpen
+        );
+    });
+}
+
+#[test]
+fn trading_end_separates_trading_from_resolution() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+
+        System::set_block_number(TRADING_END - 1);
+        assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), market_id, 1, 100, None));
+        assert_noop!(
+            Futarchy::resolve_market(Origin::signed(ALICE), market_id, MarketOutcome::Binary(true)),
+            Error::<Test>::TradingStillOpen
+        );
+
+        System::set_block_number(TRADING_END);
+        assert_noop!(
+            Futarchy::buy_shares(Origin::signed(BOB), market_id, 1, 100, None),
+            Error::<Test>::TradingClosed
+        );
+        assert_noop!(
+            Futarchy::sell_shares(Origin::signed(BOB), market_id, 1, 100, None),
+            Error::<Test>::TradingClosed
+        );
+        assert_ok!(Futarchy::resolve_market(Origin::signed(ALICE), market_id, MarketOutcome::Binary(true)));
+    });
+}
+
+#[test]
+fn resolve_market_proposes_outcome() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+        System::set_block_number(TRADING_END);
+
+        assert_ok!(Futarchy::resolve_market(Origin::signed(ALICE), market_id, MarketOutcome::Binary(true)));
+
+        let market = Futarchy::markets(market_id).unwrap();
+        assert_eq!(market.status, MarketStatus::Proposed);
+        assert_eq!(market.resolved_outcome, Some(MarketOutcome::Binary(true)));
+        assert_eq!(market.resolution_block, Some(TRADING_END));
+    });
+}
+
+#[test]
+fn resolve_market_emits_outcome_and_liquidity() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+        System::set_block_number(TRADING_END);
+
+        assert_ok!(Futarchy::resolve_market(Origin::signed(ALICE), market_id, MarketOutcome::Binary(false)));
+
+        let market = Futarchy::markets(market_id).unwrap();
+        System::assert_last_event(Event::Futarchy(RawEvent::MarketResolved(
+            ALICE,
+            market_id,
+            MarketOutcome::Binary(false),
+            market.total_liquidity
+        )));
+    });
+}
+
+#[test]
+fn every_market_type_records_its_outcome() {
+    new_test_ext().execute_with(|| {
+        let markets = vec![
+            (ALICE, MarketType::Binary, None, None, MarketOutcome::Binary(true)),
+            (BOB, MarketType::Scalar, Some((0, 100)), None, MarketOutcome::Scalar(42)),
+            (CHARLIE, MarketType::Categorical, None, Some(3), MarketOutcome::Categorical(2)),
+        ];
+        let mut created = Vec::new();
+        for (who, market_type, scalar_bounds, outcome_count, outcome) in markets {
+            assert_ok!(Futarchy::create_market(
+                Origin::signed(who),
+                market_type,
+                MarketMechanism::Lmsr,
+                TRADING_END,
+                LIQUIDITY,
+                0,
+                scalar_bounds,
+                outcome_count,
+                Vec::new(),
+                None,
+                None,
+                MarketCategory::Other,
+                Vec::new(),
+                Vec::new()
+            ));
+            let market_id = *Futarchy::markets_by_creator(who).last().unwrap();
+            assert_ok!(Futarchy::activate_market(Origin::signed(who), market_id));
+            created.push((who, market_id, outcome));
+        }
+        System::set_block_number(TRADING_END);
+
+        for (who, market_id, outcome) in created {
+            assert_ok!(Futarchy::resolve_market(Origin::signed(who), market_id, outcome.clone()));
+
+            let market = Futarchy::markets(market_id).unwrap();
+            assert_eq!(market.status, MarketStatus::Proposed);
+            assert_eq!(market.resolved_outcome, Some(outcome.clone()));
+            System::assert_last_event(Event::Futarchy(RawEvent::MarketResolved(
+                who,
+                market_id,
+                outcome,
+                market.total_liquidity
+            )));
+        }
+    });
+}
+
+#[test]
+fn resolve_market_rejects_outcome_of_another_type() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+        System::set_block_number(TRADING_END);
+
+        assert_noop!(
+            Futarchy::resolve_market(Origin::signed(ALICE), market_id, MarketOutcome::Scalar(1)),
+            Error::<Test>::OutcomeTypeMismatch
+        );
+        assert_noop!(
+            Futarchy::resolve_market(Origin::signed(ALICE), market_id, MarketOutcome::Categorical(0)),
+            Error::<Test>::OutcomeTypeMismatch
+        );
+    });
+}
+
+#[test]
+fn resolve_market_cannot_resolve_twice() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+        System::set_block_number(TRADING_END);
+        assert_ok!(Futarchy::resolve_market(Origin::signed(ALICE), market_id, MarketOutcome::Binary(true)));
+
+        assert_noop!(
+            Futarchy::resolve_market(Origin::signed(ALICE), market_id, MarketOutcome::Binary(false)),
+            Error::<Test>::MarketNotResolvable
+        );
+    });
+}
+
+#[test]
+fn new_chains_start_at_current_storage_version() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(Futarchy::storage_version(), Releases::V1);
+    });
+}
+
+#[test]
+fn migration_translates_v0_markets() {
+    new_test_ext().execute_with(|| {
+        let market_id = H256::repeat_byte(7);
+        let old = v0::PredictionMarket {
+            id: market_id,
+            creator: ALICE,
+            market_type: MarketType::Scalar,
+            status: v0::MarketStatus::Active,
+            total_liquidity: 0u64,
+            creation_block: 1u64,
+            resolution_block: None,
+        };
+        let key = Blake2_128Concat::hash(&market_id.encode());
+        migration::put_storage_value(b"FutarchyMarkets", b"Markets", &key, old);
+        StorageVersion::put(Releases::V0);
+
+        Futarchy::on_runtime_upgrade();
+
+        let market = Futarchy::markets(market_id).unwrap();
+        assert_eq!(market.id, market_id);
+        assert_eq!(market.creator, ALICE);
+        assert_eq!(market.oracle, ALICE);
+        assert_eq!(market.market_type, MarketType::Scalar);
+        assert_eq!(market.status, MarketStatus::Active);
+        assert_eq!(market.creation_block, 1);
+        assert_eq!(market.trading_end, 1);
+        assert_eq!(market.outcome_count, 2);
+        assert!(market.metadata.is_empty());
+        assert!(!market.deposit_refunded);
+        assert_eq!(Futarchy::markets_by_creator(ALICE).into_inner(), vec![market_id]);
+        assert_eq!(Futarchy::markets_by_category(MarketCategory::Other).into_inner(), vec![market_id]);
+        assert_eq!(Futarchy::market_count(), 1);
+        assert!(Futarchy::active_market_ids(None, 10).contains(&market_id));
+        assert_eq!(Futarchy::storage_version(), Releases::V1);
+    });
+}
+
+#[test]
+fn migration_maps_v0_status_indices() {
+    new_test_ext().execute_with(|| {
+        let market_id = H256::repeat_byte(8);
+        let old = v0::PredictionMarket {
+            id: market_id,
+            creator: BOB,
+            market_type: MarketType::Binary,
+            status: v0::MarketStatus::Cancelled,
+            total_liquidity: 0u64,
+            creation_block: 1u64,
+            resolution_block: None,
+        };
+        let key = Blake2_128Concat::hash(&market_id.encode());
+        migration::put_storage_value(b"FutarchyMarkets", b"Markets", &key, old);
+        StorageVersion::put(Releases::V0);
+
+        Futarchy::on_runtime_upgrade();
+
+        // v0 `Cancelled` shares its index with the current `Disputed`
+        assert_eq!(Futarchy::markets(market_id).unwrap().status, MarketStatus::Cancelled);
+        assert!(Futarchy::markets_by_creator(BOB).is_empty());
+        assert!(Futarchy::markets_by_category(MarketCategory::Other).is_empty());
+        assert_eq!(Futarchy::market_count(), 0);
+    });
+}
+
+#[test]
+fn migration_runs_once() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_binary(ALICE);
+
+        Futarchy::on_runtime_upgrade();
+
+        assert_eq!(Futarchy::markets(market_id).unwrap().trading_end, TRADING_END);
+        assert_eq!(Futarchy::markets_by_creator(ALICE).len(), 1);
+        assert_eq!(Futarchy::markets_by_category(MarketCategory::Other).len(), 1);
+        assert_eq!(Futarchy::market_count(), 1);
+    });
+}
+
+#[test]
+fn create_decision_market_opens_conditional_pair() {
+    new_test_ext().execute_with(|| {
+        let (decision_id, enacted, rejected) = create_decision(ALICE);
+
+        for market_id in [enacted, rejected] {
+            let market = Futarchy::markets(market_id).unwrap();
+            assert_eq!(market.market_type, MarketType::Scalar);
+            assert_eq!(market.status, MarketStatus::Active);
+            assert_eq!(market.scalar_bounds, Some((0, 100)));
+        }
+        assert_ne!(enacted, rejected);
+        assert_eq!(Balances::reserved_balance(ALICE), 2 * MarketCreationDeposit::get());
+        System::assert_last_event(Event::Futarchy(RawEvent::DecisionMarketCreated(
+            ALICE,
+            decision_id,
+            enacted,
+            rejected
+        )));
+    });
+}
+
+#[test]
+fn same_policy_can_be_proposed_twice_in_a_block() {
+    new_test_ext().execute_with(|| {
+        let (first, first_enacted, first_rejected) = create_decision(ALICE);
+        let (second, second_enacted, second_rejected) = create_decision(ALICE);
+
+        assert_ne!(first, second);
+        assert_ne!(first_enacted, second_enacted);
+        assert_ne!(first_rejected, second_rejected);
+        assert_eq!(Futarchy::creation_nonce(ALICE), 6);
+    });
+}
+
+#[test]
+fn settle_decision_recommends_higher_expected_metric() {
+    new_test_ext().execute_with(|| {
+        let (decision_id, enacted, _) = create_decision(ALICE);
+        // Traders expect the metric to be higher if the policy is enacted
+        assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), enacted, 1, 500, None));
+        System::set_block_number(TRADING_END);
+
+        assert_ok!(Futarchy::settle_decision(Origin::signed(CHARLIE), decision_id));
+
+        assert_eq!(Futarchy::decision_markets(decision_id).unwrap().recommendation, Some(true));
+        System::assert_last_event(Event::Futarchy(RawEvent::PolicyRecommended(decision_id, true)));
+    });
+}
+
+#[test]
+fn settle_decision_rejects_policy_with_lower_expected_metric() {
+    new_test_ext().execute_with(|| {
+        let (decision_id, enacted, rejected) = create_decision(ALICE);
+        // Traders expect the metric to be higher without the policy
+        assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), rejected, 1, 500, None));
+        System::set_block_number(TRADING_END);
+
+        assert_ok!(Futarchy::settle_decision(Origin::signed(CHARLIE), decision_id));
+
+        assert!(Futarchy::expected_value(rejected) > Futarchy::expected_value(enacted));
+        System::assert_last_event(Event::Futarchy(RawEvent::PolicyRecommended(decision_id, false)));
+    });
+}
+
+#[test]
+fn settle_decision_waits_for_trading_end() {
+    new_test_ext().execute_with(|| {
+        let (decision_id, _, _) = create_decision(ALICE);
+
+        assert_noop!(
+            Futarchy::settle_decision(Origin::signed(CHARLIE), decision_id),
+            Error::<Test>::TradingStillOpen
+        );
+    });
+}
+
+#[test]
+fn settle_decision_only_once() {
+    new_test_ext().execute_with(|| {
+        let (decision_id, _, _) = create_decision(ALICE);
+        System::set_block_number(TRADING_END);
+        assert_ok!(Futarchy::settle_decision(Origin::signed(CHARLIE), decision_id));
+
+        assert_noop!(
+            Futarchy::settle_decision(Origin::signed(CHARLIE), decision_id),
+            Error::<Test>::DecisionAlreadySettled
+        );
+    });
+}
+
+#[test]
+fn settle_decision_refunds_unchosen_branch_at_cost() {
+    new_test_ext().execute_with(|| {
+        let (decision_id, enacted, rejected) = create_decision(ALICE);
+        assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), enacted, 1, 500, None));
+        // DAVE trades on the branch that will not occur
+        assert_ok!(Futarchy::buy_shares(Origin::signed(DAVE), rejected, 0, 100, None));
+        let cost = Futarchy::cost_basis(rejected, DAVE);
+        let fee = TradingFee::get().mul_floor(cost);
+        System::set_block_number(TRADING_END);
+
+        assert_ok!(Futarchy::settle_decision(Origin::signed(CHARLIE), decision_id));
+
+        // Only the trading fee stays with the liquidity providers
+        assert_eq!(Balances::free_balance(DAVE), INITIAL_BALANCE - fee);
+        assert!(Futarchy::positions(rejected, DAVE).is_empty());
+        assert_eq!(Futarchy::markets(rejected).unwrap().status, MarketStatus::Cancelled);
+        assert_eq!(Futarchy::markets(enacted).unwrap().status, MarketStatus::Active);
+        assert!(System::events().iter().any(|record| {
+            record.event == Event::Futarchy(RawEvent::PositionRefunded(DAVE, rejected, cost))
+        }));
+    });
+}
+
+#[test]
+fn selling_reduces_cost_basis() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+        assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), market_id, 1, 200, None));
+        let cost = Futarchy::cost_basis(market_id, BOB);
+
+        assert_ok!(Futarchy::sell_shares(Origin::signed(BOB), market_id, 1, 100, None));
+
+        let basis = Futarchy::cost_basis(market_id, BOB);
+        assert!(basis > 0 && basis < cost);
+    });
+}
+
+// Root-only call whose effect is visible in storage
+fn set_flag_call() -> Call {
+    Call::System(frame_system::Call::set_storage(vec![(b":flag".to_vec(), b"set".to_vec())]))
+}
+
+// Number of times a decision's bound call was executed
+fn executions(decision_id: H256) -> usize {
+    System::events()
+        .iter()
+        .filter(|record| matches!(
+            record.event,
+            Event::Futarchy(RawEvent::ProposalExecuted(id, _)) if id == decision_id
+        ))
+        .count()
+}
+
+#[test]
+fn execute_decision_dispatches_bound_call_once() {
+    new_test_ext().execute_with(|| {
+        let (decision_id, enacted, _) = create_decision_with_call(ALICE, Some(set_flag_call()));
+        assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), enacted, 1, 500, None));
+        System::set_block_number(TRADING_END);
+        assert_ok!(Futarchy::settle_decision(Origin::signed(CHARLIE), decision_id));
+
+        // Execution waits for the dispute window
+        assert_noop!(
+            Futarchy::execute_decision(Origin::signed(CHARLIE), decision_id),
+            Error::<Test>::DisputeWindowOpen
+        );
+        System::set_block_number(TRADING_END + DisputePeriod::get());
+
+        assert_ok!(Futarchy::execute_decision(Origin::signed(CHARLIE), decision_id));
+        assert_eq!(sp_io::storage::get(b":flag"), Some(b"set".to_vec()));
+        System::assert_last_event(Event::Futarchy(RawEvent::ProposalExecuted(decision_id, Ok(()))));
+
+        assert_noop!(
+            Futarchy::execute_decision(Origin::signed(CHARLIE), decision_id),
+            Error::<Test>::NothingToExecute
+        );
+        assert_eq!(executions(decision_id), 1);
+    });
+}
+
+#[test]
+fn execute_decision_never_runs_rejected_policy() {
+    new_test_ext().execute_with(|| {
+        let (decision_id, _, rejected) = create_decision_with_call(ALICE, Some(set_flag_call()));
+        assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), rejected, 1, 500, None));
+        System::set_block_number(TRADING_END);
+        assert_ok!(Futarchy::settle_decision(Origin::signed(CHARLIE), decision_id));
+        System::set_block_number(TRADING_END + DisputePeriod::get());
+
+        assert_noop!(
+            Futarchy::execute_decision(Origin::signed(CHARLIE), decision_id),
+            Error::<Test>::DecisionNotEnacted
+        );
+        assert_eq!(sp_io::storage::get(b":flag"), None);
+        assert_eq!(executions(decision_id), 0);
+    });
+}
+
+// Create a binary market from `who` resolved by `oracle`
+fn create_binary_with_oracle(who: AccountId, oracle: Option<AccountId>) -> frame_support::dispatch::DispatchResult {
+    Futarchy::create_market(
+        Origin::signed(who),
+        MarketType::Binary,
+        MarketMechanism::Lmsr,
+        TRADING_END,
+        LIQUIDITY,
+        0,
+        None,
+        None,
+        Vec::new(),
+        oracle,
+        None,
+        MarketCategory::Other,
+        Vec::new(),
+        Vec::new()
+    )
+}
+
+#[test]
+fn creator_may_resolve_when_allowed() {
+    new_test_ext().execute_with(|| {
+        set_allow_creator_resolution(true);
+
+        assert_ok!(create_binary_with_oracle(ALICE, None));
+        let market_id = *Futarchy::markets_by_creator(ALICE).last().unwrap();
+        assert_eq!(Futarchy::markets(market_id).unwrap().oracle, ALICE);
+
+        assert_ok!(Futarchy::set_oracle(Origin::signed(ALICE), market_id, BOB));
+        assert_ok!(Futarchy::set_oracle(Origin::signed(ALICE), market_id, ALICE));
+    });
+}
+
+#[test]
+fn creator_cannot_resolve_when_disallowed() {
+    new_test_ext().execute_with(|| {
+        set_allow_creator_resolution(false);
+
+        // Defaulting to, or naming, the creator as oracle is rejected
+        assert_noop!(create_binary_with_oracle(ALICE, None), Error::<Test>::CreatorCannotResolve);
+        assert_noop!(create_binary_with_oracle(ALICE, Some(ALICE)), Error::<Test>::CreatorCannotResolve);
+
+        assert_ok!(create_binary_with_oracle(ALICE, Some(BOB)));
+        let market_id = *Futarchy::markets_by_creator(ALICE).last().unwrap();
+        assert_noop!(
+            Futarchy::set_oracle(Origin::signed(ALICE), market_id, ALICE),
+            Error::<Test>::CreatorCannotResolve
+        );
+        assert_ok!(Futarchy::set_oracle(Origin::signed(ALICE), market_id, CHARLIE));
+    });
+}
+
+#[test]
+fn only_the_named_oracle_resolves() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_binary_with_oracle(ALICE, None));
+        let market_id = *Futarchy::markets_by_creator(ALICE).last().unwrap();
+        assert_ok!(Futarchy::set_oracle(Origin::signed(ALICE), market_id, CHARLIE));
+        assert_noop!(
+            Futarchy::set_oracle(Origin::signed(BOB), market_id, BOB),
+            Error::<Test>::NotMarketCreator
+        );
+        assert_ok!(Futarchy::activate_market(Origin::signed(ALICE), market_id));
+        System::set_block_number(TRADING_END);
+
+        for who in [ALICE, BOB, DAVE] {
+            assert_noop!(
+                Futarchy::resolve_market(Origin::signed(who), market_id, MarketOutcome::Binary(true)),
+                Error::<Test>::NotAuthorizedOracle
+            );
+        }
+        assert_ok!(Futarchy::resolve_market(Origin::signed(CHARLIE), market_id, MarketOutcome::Binary(true)));
+    });
+}
+
+// Create a binary constant-product market from `who` holding exactly `seed`
+fn create_pool_with_seed(who: AccountId, seed: Balance) -> H256 {
+    assert_ok!(Futarchy::create_market(
+        Origin::signed(who),
+        MarketType::Binary,
+        MarketMechanism::ConstantProduct,
+        TRADING_END,
+        seed,
+        0,
+        None,
+        None,
+        Vec::new(),
+        None,
+        None,
+        MarketCategory::Other,
+        Vec::new(),
+        Vec::new()
+    ));
+    *Futarchy::markets_by_creator(who).last().unwrap()
+}
+
+#[test]
+fn constant_product_pool_needs_a_seed() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            Futarchy::create_market(
+                Origin::signed(ALICE),
+                MarketType::Binary,
+                MarketMechanism::ConstantProduct,
+                TRADING_END,
+                0,
+                0,
+                None,
+                None,
+                Vec::new(),
+                None,
+                None,
+                MarketCategory::Other,
+                Vec::new(),
+                Vec::new()
+            ),
+            Error::<Test>::InsufficientSeedLiquidity
+        );
+    });
+}
+
+#[test]
+fn activate_market_opens_market_once() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_binary(ALICE);
+
+        assert_ok!(Futarchy::activate_market(Origin::signed(ALICE), market_id));
+        assert_eq!(Futarchy::markets(market_id).unwrap().status, MarketStatus::Active);
+        System::assert_last_event(Event::Futarchy(RawEvent::MarketActivated(ALICE, market_id)));
+
+        assert_noop!(
+            Futarchy::activate_market(Origin::signed(ALICE), market_id),
+            Error::<Test>::MarketNotActivatable
+        );
+    });
+}
+
+#[test]
+fn active_market_ids_lists_only_active_markets() {
+    new_test_ext().execute_with(|| {
+        let first = create_active_binary(ALICE);
+        let second = create_active_binary(BOB);
+        let resolved = create_active_binary(CHARLIE);
+        let cancelled = create_active_binary(DAVE);
+        // A market that never opened is not listed either
+        assert_ok!(Futarchy::create_markets_batch(Origin::signed(DAVE), vec![binary_spec(TRADING_END)].try_into().unwrap()));
+
+        System::set_block_number(TRADING_END);
+        assert_ok!(Futarchy::resolve_market(Origin::signed(CHARLIE), resolved, MarketOutcome::Binary(true)));
+        assert_ok!(Futarchy::cancel_market(Origin::signed(DAVE), cancelled));
+
+        let mut listed = Futarchy::active_market_ids(None, 10);
+        listed.sort();
+        let mut expected = vec![first, second];
+        expected.sort();
+        assert_eq!(listed, expected);
+
+        // Pages pick up after the last id seen
+        let page = Futarchy::active_market_ids(None, 1);
+        assert_eq!(page.len(), 1);
+        let rest = Futarchy::active_market_ids(Some(page[0]), 10);
+        assert_eq!(rest.len(), 1);
+        assert_ne!(rest[0], page[0]);
+    });
+}
+
+#[test]
+fn activate_market_rejects_liquidity_below_threshold() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_pool_with_seed(ALICE, MinActivationLiquidity::get() - 1);
+
+        assert_noop!(
+            Futarchy::activate_market(Origin::signed(ALICE), market_id),
+            Error::<Test>::InsufficientLiquidity
+        );
+    });
+}
+
+#[test]
+fn activate_market_accepts_liquidity_at_threshold() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_pool_with_seed(ALICE, MinActivationLiquidity::get());
+
+        assert_ok!(Futarchy::activate_market(Origin::signed(ALICE), market_id));
+        assert_eq!(Futarchy::markets(market_id).unwrap().status, MarketStatus::Active);
+    });
+}
+
+#[test]
+fn activate_market_accepts_liquidity_above_threshold() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_pool_with_seed(ALICE, MinActivationLiquidity::get() + 1);
+
+        assert_ok!(Futarchy::activate_market(Origin::signed(ALICE), market_id));
+    });
+}
+
+#[test]
+fn topping_up_liquidity_unlocks_activation() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_pool_with_seed(ALICE, MinActivationLiquidity::get() - 1);
+        assert_ok!(Futarchy::add_liquidity(Origin::signed(BOB), market_id, 1));
+
+        assert_ok!(Futarchy::activate_market(Origin::signed(ALICE), market_id));
+    });
+}
+
+#[test]
+fn auto_activate_opens_market_once_liquidity_is_deep_enough() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_pool_with_seed(ALICE, MinActivationLiquidity::get() - 2);
+        assert_noop!(
+            Futarchy::set_auto_activate(Origin::signed(BOB), market_id, true),
+            Error::<Test>::NotMarketCreator
+        );
+        assert_ok!(Futarchy::set_auto_activate(Origin::signed(ALICE), market_id, true));
+
+        // Still short of the threshold
+        assert_ok!(Futarchy::add_liquidity(Origin::signed(BOB), market_id, 1));
+        assert_eq!(Futarchy::markets(market_id).unwrap().status, MarketStatus::Created);
+
+        // Crossing it opens the market, later deposits leave it open
+        assert_ok!(Futarchy::add_liquidity(Origin::signed(BOB), market_id, 1));
+        assert_eq!(Futarchy::markets(market_id).unwrap().status, MarketStatus::Active);
+        assert!(crate::ActiveMarkets::<Test>::contains_key(market_id));
+        assert_ok!(Futarchy::add_liquidity(Origin::signed(BOB), market_id, 1));
+
+        let activations = System::events()
+            .iter()
+            .filter(|record| record.event == Event::Futarchy(RawEvent::MarketActivated(ALICE, market_id)))
+            .count();
+        assert_eq!(activations, 1);
+        assert_noop!(
+            Futarchy::set_auto_activate(Origin::signed(ALICE), market_id, false),
+            Error::<Test>::MarketNotActivatable
+        );
+    });
+}
+
+#[test]
+fn auto_activation_failure_rejects_the_deposit() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_pool_with_seed(ALICE, MinActivationLiquidity::get() - 1);
+        assert_ok!(Futarchy::set_auto_activate(Origin::signed(ALICE), market_id, true));
+        set_max_active_markets(0);
+
+        // Deep enough but over the active cap, so nothing is taken
+        assert_noop!(
+            Futarchy::add_liquidity(Origin::signed(BOB), market_id, 1),
+            Error::<Test>::TooManyActiveMarkets
+        );
+
+        set_max_active_markets(1);
+        assert_ok!(Futarchy::add_liquidity(Origin::signed(BOB), market_id, 1));
+        assert_eq!(Futarchy::markets(market_id).unwrap().status, MarketStatus::Active);
+    });
+}
+
+// Spec of a binary LMSR market closing at `trading_end`
+fn binary_spec(trading_end: u64) -> MarketSpecOf<Test> {
+    MarketSpecOf::<Test> {
+        market_type: MarketType::Binary,
+        mechanism: MarketMechanism::Lmsr,
+        trading_end,
+        liquidity_parameter: LIQUIDITY,
+        scalar_bounds: None,
+        outcome_count: None,
+        metadata: b"Bracket match".to_vec(),
+        oracle: None,
+        collateral_asset: None,
+        category: MarketCategory::Sports,
+        denom: Vec::new(),
+        outcome_labels: Vec::new(),
+    }
+}
+
+#[test]
+fn create_markets_batch_creates_every_market() {
+    new_test_ext().execute_with(|| {
+        let specs = vec![binary_spec(TRADING_END); 3];
+
+        assert_ok!(Futarchy::create_markets_batch(Origin::signed(ALICE), specs.try_into().unwrap()));
+
+        let market_ids = Futarchy::markets_by_creator(ALICE).into_inner();
+        assert_eq!(market_ids.len(), 3);
+        assert!(market_ids.iter().all(|id| Futarchy::markets(id).is_some()));
+        assert_eq!(Futarchy::market_count(), 3);
+        assert_eq!(Balances::reserved_balance(ALICE), 3 * MarketCreationDeposit::get());
+        System::assert_last_event(Event::Futarchy(RawEvent::MarketsBatchCreated(ALICE, market_ids)));
+    });
+}
+
+#[test]
+fn create_markets_batch_is_atomic() {
+    new_test_ext().execute_with(|| {
+        // The last market would close before it opens
+        let specs = vec![binary_spec(TRADING_END), binary_spec(TRADING_END), binary_spec(1)];
+
+        assert_noop!(
+            Futarchy::create_markets_batch(Origin::signed(ALICE), specs.try_into().unwrap()),
+            Error::<Test>::InvalidTradingEnd
+        );
+        assert_eq!(Futarchy::market_count(), 0);
+        assert_eq!(Balances::reserved_balance(ALICE), 0);
+    });
+}
+
+#[test]
+fn extend_trading_moves_trading_end() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+
+        assert_ok!(Futarchy::extend_trading(Origin::signed(ALICE), market_id, TRADING_END + 5));
+
+        assert_eq!(Futarchy::markets(market_id).unwrap().trading_end, TRADING_END + 5);
+        System::assert_last_event(Event::Futarchy(RawEvent::TradingExtended(market_id, TRADING_END + 5)));
+
+        // Trading stays open past the original end
+        System::set_block_number(TRADING_END);
+        assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), market_id, 1, 10, None));
+    });
+}
+
+#[test]
+fn extend_trading_rejects_shortening() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+
+        assert_noop!(
+            Futarchy::extend_trading(Origin::signed(ALICE), market_id, TRADING_END - 1),
+            Error::<Test>::TradingEndNotExtended
+        );
+        assert_noop!(
+            Futarchy::extend_trading(Origin::signed(ALICE), market_id, TRADING_END),
+            Error::<Test>::TradingEndNotExtended
+        );
+    });
+}
+
+#[test]
+fn extend_trading_rejects_extension_past_maximum() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+        let limit = System::block_number() + MaxExtension::get();
+
+        assert_noop!(
+            Futarchy::extend_trading(Origin::signed(ALICE), market_id, limit + 1),
+            Error::<Test>::ExtensionTooLong
+        );
+        assert_ok!(Futarchy::extend_trading(Origin::signed(ALICE), market_id, limit));
+    });
+}
+
+#[test]
+fn extend_trading_requires_active_market() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+        System::set_block_number(TRADING_END);
+        assert_ok!(Futarchy::resolve_market(Origin::signed(ALICE), market_id, MarketOutcome::Binary(true)));
+
+        assert_noop!(
+            Futarchy::extend_trading(Origin::signed(ALICE), market_id, TRADING_END + 5),
+            Error::<Test>::MarketNotActive
+        );
+    });
+}
+
+#[test]
+fn extend_trading_requires_creator() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+
+        assert_noop!(
+            Futarchy::extend_trading(Origin::signed(BOB), market_id, TRADING_END + 5),
+            Error::<Test>::NotMarketCreator
+        );
+    });
+}
+
+#[test]
+fn unreported_market_expires_after_grace_period() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+        let before = Balances::free_balance(BOB);
+        assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), market_id, 1, 50, None));
+        let cost = before - Balances::free_balance(BOB);
+
+        // Still open to a late report during the grace period
+        run_to_block(TRADING_END + AutoResolveGrace::get() - 1);
+        assert_eq!(Futarchy::markets(market_id).unwrap().status, MarketStatus::Active);
+
+        run_to_block(TRADING_END + AutoResolveGrace::get());
+        assert_eq!(Futarchy::markets(market_id).unwrap().status, MarketStatus::Cancelled);
+        System::assert_has_event(Event::Futarchy(RawEvent::MarketExpired(market_id)));
+        System::assert_last_event(Event::Futarchy(RawEvent::MarketStatusChanged(
+            market_id,
+            MarketStatus::Active,
+            MarketStatus::Cancelled
+        )));
+
+        // Holders get back what they paid
+        assert_ok!(Futarchy::claim_refund(Origin::signed(BOB), market_id));
+        assert_eq!(Balances::free_balance(BOB), before);
+        System::assert_last_event(Event::Futarchy(RawEvent::PositionRefunded(BOB, market_id, cost)));
+        assert_noop!(
+            Futarchy::claim_refund(Origin::signed(BOB), market_id),
+            Error::<Test>::NothingToClaim
+        );
+
+        // The block hook leaves the liquidity provider to `process_refunds`
+        assert!(Futarchy::lp_refunds(market_id).is_some());
+        assert_ok!(Futarchy::process_refunds(Origin::signed(BOB), market_id));
+        assert_eq!(Futarchy::lp_refunds(market_id), None);
+        assert_eq!(Futarchy::lp_shares(market_id, ALICE), 0);
+    });
+}
+
+#[test]
+fn reported_market_does_not_expire() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+
+        run_to_block(TRADING_END);
+        assert_ok!(Futarchy::resolve_market(Origin::signed(ALICE), market_id, MarketOutcome::Binary(true)));
+
+        run_to_block(TRADING_END + AutoResolveGrace::get());
+        assert_eq!(Futarchy::markets(market_id).unwrap().status, MarketStatus::Proposed);
+    });
+}
+
+#[test]
+fn expiry_spills_over_full_blocks() {
+    new_test_ext().execute_with(|| {
+        let first = create_active_binary(ALICE);
+        let second = create_active_binary(BOB);
+        let third = create_active_binary(CHARLIE);
+        let expiry = TRADING_END + AutoResolveGrace::get();
+
+        assert_eq!(Futarchy::expiring_markets(expiry).into_inner(), vec![first, second]);
+        assert_eq!(Futarchy::expiring_markets(expiry + 1).into_inner(), vec![third]);
+
+        run_to_block(expiry);
+        assert_eq!(Futarchy::markets(second).unwrap().status, MarketStatus::Cancelled);
+        assert_eq!(Futarchy::markets(third).unwrap().status, MarketStatus::Active);
+
+        run_to_block(expiry + 1);
+        assert_eq!(Futarchy::markets(third).unwrap().status, MarketStatus::Cancelled);
+    });
+}
+
+#[test]
+fn transfer_shares_moves_part_of_a_position() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+        assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), market_id, 1, 50, None));
+        let basis = Futarchy::cost_basis(market_id, BOB);
+
+        assert_ok!(Futarchy::transfer_shares(Origin::signed(BOB), market_id, CHARLIE, 1, 20));
+
+        assert_eq!(Futarchy::positions(market_id, BOB).balance_of(1), 30);
+        assert_eq!(Futarchy::positions(market_id, CHARLIE).balance_of(1), 20);
+        assert_eq!(Futarchy::total_shares(market_id).balance_of(1), 50);
+        assert_eq!(
+            Futarchy::cost_basis(market_id, BOB) + Futarchy::cost_basis(market_id, CHARLIE),
+            basis
+        );
+        System::assert_last_event(Event::Futarchy(RawEvent::SharesTransferred(BOB, CHARLIE, market_id, 1, 20)));
+    });
+}
+
+#[test]
+fn transfer_shares_moves_whole_position() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+        assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), market_id, 0, 50, None));
+        let basis = Futarchy::cost_basis(market_id, BOB);
+
+        assert_ok!(Futarchy::transfer_shares(Origin::signed(BOB), market_id, CHARLIE, 0, 50));
+
+        assert!(Futarchy::positions(market_id, BOB).is_empty());
+        assert_eq!(Futarchy::positions(market_id, CHARLIE).balance_of(0), 50);
+        assert_eq!(Futarchy::cost_basis(market_id, BOB), 0);
+        assert_eq!(Futarchy::cost_basis(market_id, CHARLIE), basis);
+    });
+}
+
+#[test]
+fn transfer_shares_rejects_more_than_held() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+        assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), market_id, 1, 50, None));
+
+        assert_noop!(
+            Futarchy::transfer_shares(Origin::signed(BOB), market_id, CHARLIE, 1, 51),
+            Error::<Test>::InsufficientShares
+        );
+        assert_noop!(
+            Futarchy::transfer_shares(Origin::signed(BOB), market_id, CHARLIE, 0, 1),
+            Error::<Test>::InsufficientShares
+        );
+    });
+}
+
+#[test]
+fn transfer_shares_rejects_resolved_market() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+        assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), market_id, 1, 50, None));
+        System::set_block_number(TRADING_END);
+        assert_ok!(Futarchy::resolve_market(Origin::signed(ALICE), market_id, MarketOutcome::Binary(true)));
+        System::set_block_number(TRADING_END + DisputePeriod::get());
+        assert_ok!(Futarchy::finalize_market(Origin::signed(ALICE), market_id));
+
+        assert_noop!(
+            Futarchy::transfer_shares(Origin::signed(BOB), market_id, CHARLIE, 1, 10),
+            Error::<Test>::MarketSettled
+        );
+    });
+}
+
+// Create `USD` and hand ALICE and BOB some of it
+fn setup_usd() {
+    assert_ok!(Assets::force_create(Origin::root(), USD, TREASURY, true, 1));
+    for who in [ALICE, BOB] {
+        assert_ok!(Assets::mint(Origin::signed(TREASURY), USD, who, INITIAL_BALANCE));
+    }
+}
+
+#[test]
+fn market_in_custom_asset_trades_that_asset() {
+    new_test_ext().execute_with(|| {
+        setup_usd();
+        assert_ok!(Futarchy::create_market(
+            Origin::signed(ALICE),
+            MarketType::Binary,
+            MarketMechanism::Lmsr,
+            TRADING_END,
+            LIQUIDITY,
+            0,
+            None,
+            None,
+            Vec::new(),
+            None,
+            Some(USD),
+            MarketCategory::Other,
+            Vec::new(),
+            Vec::new()
+        ));
+        let market_id = *Futarchy::markets_by_creator(ALICE).last().unwrap();
+        let pot = Futarchy::market_account(market_id);
+
+        // The subsidy is paid in USD, only the deposit and incentive in the native token
+        assert_eq!(
+            Balances::free_balance(ALICE),
+            INITIAL_BALANCE - MarketCreationDeposit::get() - ReapIncentive::get()
+        );
+        let subsidy = Assets::balance(USD, pot);
+        assert!(subsidy > 0);
+        assert_eq!(Assets::balance(USD, ALICE), INITIAL_BALANCE - subsidy);
+
+        assert_ok!(Futarchy::activate_market(Origin::signed(ALICE), market_id));
+        assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), market_id, 1, 50, None));
+        let paid = INITIAL_BALANCE - Assets::balance(USD, BOB);
+        assert!(paid > 0);
+        assert_eq!(Assets::balance(USD, pot), subsidy + paid);
+        assert_eq!(Balances::free_balance(BOB), INITIAL_BALANCE);
+
+        assert_ok!(Futarchy::sell_shares(Origin::signed(BOB), market_id, 1, 50, None));
+        assert!(Assets::balance(USD, BOB) > INITIAL_BALANCE - paid);
+        assert_eq!(Balances::free_balance(BOB), INITIAL_BALANCE);
+    });
+}
+
+#[test]
+fn market_in_custom_asset_requires_asset_funds() {
+    new_test_ext().execute_with(|| {
+        setup_usd();
+        assert_noop!(
+            Futarchy::create_market(
+                Origin::signed(CHARLIE),
+                MarketType::Binary,
+                MarketMechanism::Lmsr,
+                TRADING_END,
+                LIQUIDITY,
+                0,
+                None,
+                None,
+                Vec::new(),
+                None,
+                Some(USD),
+                MarketCategory::Other,
+                Vec::new(),
+                Vec::new()
+            ),
+            Error::<Test>::InsufficientFunds
+        );
+    });
+}
+
+#[test]
+fn account_exposure_sums_positions_at_live_prices() {
+    new_test_ext().execute_with(|| {
+        let first = create_active_binary(ALICE);
+        let second = create_active_binary(CHARLIE);
+        assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), first, 1, 80, None));
+        assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), second, 0, 30, None));
+        assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), second, 1, 20, None));
+
+        let valuation = |market_id| {
+            let position = Futarchy::positions(market_id, BOB);
+            Futarchy::outcome_prices(market_id)
+                .unwrap()
+                .iter()
+                .enumerate()
+                .map(|(index, price)| price.saturating_mul_int(position.balance_of(index)))
+                .sum::<Balance>()
+        };
+        let expected = valuation(first) + valuation(second);
+        assert!(expected > 0);
+        assert_eq!(Futarchy::account_exposure(&BOB), expected);
+
+        // Selling out drops the market from the account's index
+        assert_ok!(Futarchy::sell_shares(Origin::signed(BOB), first, 1, 80, None));
+        assert!(!HeldMarkets::<Test>::contains_key(BOB, first));
+        assert_eq!(Futarchy::account_exposure(&BOB), valuation(second));
+    });
+}
+
+// Price of buying `amount` shares of `outcome` right now, fees included
+fn buy_quote(market_id: H256, outcome: u32, amount: Balance) -> Balance {
+    let market = Futarchy::markets(market_id).unwrap();
+    let (cost, _) = Futarchy::quote_buy(market_id, &market, outcome as usize, amount).unwrap();
+    cost + TradingFee::get().mul_floor(cost)
+}
+
+// Payout of selling `amount` shares of `outcome` right now, net of fees
+fn sell_quote(market_id: H256, outcome: u32, amount: Balance) -> Balance {
+    let market = Futarchy::markets(market_id).unwrap();
+    let (proceeds, _) = Futarchy::quote_sell(market_id, &market, outcome as usize, amount).unwrap();
+    proceeds - TradingFee::get().mul_floor(proceeds)
+}
+
+#[test]
+fn realized_pnl_tracks_sales_and_claims() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+
+        // DAVE sells into BOB's demand for more than he paid
+        assert_ok!(Futarchy::buy_shares(Origin::signed(DAVE), market_id, 1, 50, None));
+        let dave_basis = Futarchy::cost_basis(market_id, DAVE);
+        assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), market_id, 1, 100, None));
+        let market = Futarchy::markets(market_id).unwrap();
+        let (proceeds, _) = Futarchy::quote_sell(market_id, &market, 1, 50).unwrap();
+        let before = Balances::free_balance(DAVE);
+        assert_ok!(Futarchy::sell_shares(Origin::signed(DAVE), market_id, 1, 50, None));
+        assert!(proceeds > dave_basis);
+        // Only what DAVE actually received counts, fees excluded
+        let received = Balances::free_balance(DAVE) - before;
+        assert_eq!(received, proceeds - TradingFee::get().mul_floor(proceeds));
+        assert_eq!(Futarchy::realized_pnl(DAVE), received as i128 - dave_basis as i128);
+
+        // CHARLIE mostly backs the losing side
+        assert_ok!(Futarchy::buy_shares(Origin::signed(CHARLIE), market_id, 0, 100, None));
+        assert_ok!(Futarchy::buy_shares(Origin::signed(CHARLIE), market_id, 1, 10, None));
+        let bob_basis = Futarchy::cost_basis(market_id, BOB);
+        let charlie_basis = Futarchy::cost_basis(market_id, CHARLIE);
+
+        System::set_block_number(TRADING_END);
+        assert_ok!(Futarchy::resolve_market(Origin::signed(ALICE), market_id, MarketOutcome::Binary(true)));
+        System::set_block_number(TRADING_END + DisputePeriod::get());
+        assert_ok!(Futarchy::claim_winnings(Origin::signed(BOB), market_id));
+        assert_ok!(Futarchy::claim_winnings(Origin::signed(CHARLIE), market_id));
+
+        let bob = 100 - bob_basis as i128;
+        let charlie = 10 - charlie_basis as i128;
+        assert!(bob > 0 && charlie < 0);
+        assert_eq!(Futarchy::realized_pnl(BOB), bob);
+        assert_eq!(Futarchy::realized_pnl(CHARLIE), charlie);
+
+        // The leaderboard ranks them best first
+        let top = Futarchy::top_traders(3);
+        assert_eq!(top.len(), 3);
+        assert!(top.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+        assert_eq!(top[2], (CHARLIE, charlie));
+        assert_eq!(Futarchy::top_traders(1), top[..1].to_vec());
+    });
+}
+
+#[test]
+fn round_trip_sale_realizes_its_fees_as_a_loss() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+        assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), market_id, 1, 500, None));
+        let basis = Futarchy::cost_basis(market_id, BOB);
+
+        let before = Balances::free_balance(BOB);
+        assert_ok!(Futarchy::sell_shares(Origin::signed(BOB), market_id, 1, 500, None));
+        let received = Balances::free_balance(BOB) - before;
+        let retired = basis - Futarchy::cost_basis(market_id, BOB);
+
+        // Selling straight back recovers the cost, less the selling fee
+        assert!(received < retired);
+        assert_eq!(Futarchy::realized_pnl(BOB), received as i128 - retired as i128);
+    });
+}
+
+#[test]
+fn settling_a_losing_position_books_the_loss() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+        assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), market_id, 1, 100, None));
+        assert_ok!(Futarchy::buy_shares(Origin::signed(CHARLIE), market_id, 0, 100, None));
+        let basis = Futarchy::cost_basis(market_id, CHARLIE);
+
+        System::set_block_number(TRADING_END);
+        assert_ok!(Futarchy::resolve_market(Origin::signed(ALICE), market_id, MarketOutcome::Binary(true)));
+        System::set_block_number(TRADING_END + DisputePeriod::get());
+
+        // A losing position has nothing to claim, and a winning one cannot
+        // be written off
+        assert_noop!(
+            Futarchy::claim_winnings(Origin::signed(CHARLIE), market_id),
+            Error::<Test>::NothingToClaim
+        );
+        assert_noop!(
+            Futarchy::settle_position(Origin::signed(BOB), market_id),
+            Error::<Test>::PositionPaysOut
+        );
+
+        let before = Balances::free_balance(CHARLIE);
+        assert_ok!(Futarchy::settle_position(Origin::signed(CHARLIE), market_id));
+        System::assert_last_event(Event::Futarchy(RawEvent::PositionSettled(CHARLIE, market_id, basis)));
+
+        // The whole stake is realized as a loss, and the position is gone
+        assert_eq!(Balances::free_balance(CHARLIE), before);
+        assert_eq!(Futarchy::realized_pnl(CHARLIE), -(basis as i128));
+        assert_eq!(Futarchy::cost_basis(market_id, CHARLIE), 0);
+        assert!(Futarchy::positions(market_id, CHARLIE).is_empty());
+        assert!(!HeldMarkets::<Test>::contains_key(CHARLIE, market_id));
+        assert_noop!(
+            Futarchy::settle_position(Origin::signed(CHARLIE), market_id),
+            Error::<Test>::NoPosition
+        );
+
+        // BOB's winnings are untouched
+        assert_ok!(Futarchy::claim_winnings(Origin::signed(BOB), market_id));
+    });
+}
+
+#[test]
+fn buy_shares_mints_position_and_deepens_pot() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+        let market = Futarchy::markets(market_id).unwrap();
+        let (cost, _) = Futarchy::quote_buy(market_id, &market, 1, 100).unwrap();
+
+        assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), market_id, 1, 100, None));
+
+        assert_eq!(Futarchy::positions(market_id, BOB).balance_of(1), 100);
+        assert_eq!(Futarchy::positions(market_id, BOB).balance_of(0), 0);
+        assert_eq!(Futarchy::markets(market_id).unwrap().total_liquidity, market.total_liquidity + cost);
+        System::assert_has_event(Event::Futarchy(RawEvent::SharesBought(
+            BOB,
+            market_id,
+            1,
+            100,
+            cost,
+            TradingFee::get().mul_floor(cost)
+        )));
+        assert_noop!(
+            Futarchy::buy_shares(Origin::signed(BOB), market_id, 1, 0, None),
+            Error::<Test>::InvalidAmount
+        );
+    });
+}
+
+#[test]
+fn buy_shares_within_max_cost() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+        let quote = buy_quote(market_id, 1, 50);
+
+        assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), market_id, 1, 50, Some(quote)));
+        assert_eq!(Balances::free_balance(BOB), INITIAL_BALANCE - quote);
+    });
+}
+
+#[test]
+fn buy_shares_rejects_price_move_past_max_cost() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+        let quote = buy_quote(market_id, 1, 50);
+
+        // Another trader moves the price up before the order lands
+        assert_ok!(Futarchy::buy_shares(Origin::signed(CHARLIE), market_id, 1, 200, None));
+
+        assert_noop!(
+            Futarchy::buy_shares(Origin::signed(BOB), market_id, 1, 50, Some(quote)),
+            Error::<Test>::SlippageExceeded
+        );
+    });
+}
+
+#[test]
+fn sell_shares_burns_part_or_all_of_a_position() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+        assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), market_id, 1, 100, None));
+
+        let market = Futarchy::markets(market_id).unwrap();
+        let (proceeds, _) = Futarchy::quote_sell(market_id, &market, 1, 40).unwrap();
+        assert_ok!(Futarchy::sell_shares(Origin::signed(BOB), market_id, 1, 40, None));
+        assert_eq!(Futarchy::positions(market_id, BOB).balance_of(1), 60);
+        assert_eq!(Futarchy::markets(market_id).unwrap().total_liquidity, market.total_liquidity - proceeds);
+
+        // Selling more than is left fails, selling the rest empties the position
+        assert_noop!(
+            Futarchy::sell_shares(Origin::signed(BOB), market_id, 1, 61, None),
+            Error::<Test>::InsufficientShares
+        );
+        assert_ok!(Futarchy::sell_shares(Origin::signed(BOB), market_id, 1, 60, None));
+        assert!(Futarchy::positions(market_id, BOB).is_empty());
+    });
+}
+
+#[test]
+fn sell_shares_within_min_proceeds() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+        assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), market_id, 1, 100, None));
+        let balance = Balances::free_balance(BOB);
+        let quote = sell_quote(market_id, 1, 100);
+
+        assert_ok!(Futarchy::sell_shares(Origin::signed(BOB), market_id, 1, 100, Some(quote)));
+        assert_eq!(Balances::free_balance(BOB), balance + quote);
+    });
+}
+
+#[test]
+fn sell_shares_rejects_price_move_past_min_proceeds() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+        assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), market_id, 1, 100, None));
+        let quote = sell_quote(market_id, 1, 100);
+
+        // Another trader moves the price down before the order lands
+        assert_ok!(Futarchy::buy_shares(Origin::signed(CHARLIE), market_id, 0, 200, None));
+
+        assert_noop!(
+            Futarchy::sell_shares(Origin::signed(BOB), market_id, 1, 100, Some(quote)),
+            Error::<Test>::SlippageExceeded
+        );
+    });
+}
+
+#[test]
+fn close_market_halts_trading() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+        assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), market_id, 1, 50, None));
+        let pot = Balances::free_balance(Futarchy::market_account(market_id));
+
+        assert_ok!(Futarchy::close_market(Origin::signed(ALICE), market_id));
+        assert_eq!(Futarchy::markets(market_id).unwrap().status, MarketStatus::Closed);
+        System::assert_last_event(Event::Futarchy(RawEvent::MarketClosed(ALICE, market_id)));
+
+        assert_noop!(
+            Futarchy::buy_shares(Origin::signed(BOB), market_id, 1, 10, None),
+            Error::<Test>::TradingClosed
+        );
+        assert_noop!(
+            Futarchy::sell_shares(Origin::signed(BOB), market_id, 1, 10, None),
+            Error::<Test>::TradingClosed
+        );
+        // Funds stay where they are
+        let lp = Futarchy::lp_shares(market_id, ALICE);
+        assert_noop!(
+            Futarchy::remove_liquidity(Origin::signed(ALICE), market_id, lp),
+            Error::<Test>::LiquidityLocked
+        );
+        assert_eq!(Balances::free_balance(Futarchy::market_account(market_id)), pot);
+    });
+}
+
+#[test]
+fn closed_market_can_be_resolved_early() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+        assert_ok!(Futarchy::close_market(Origin::signed(ALICE), market_id));
+
+        // No need to wait for the trading end once closed
+        assert!(System::block_number() < TRADING_END);
+        assert_ok!(Futarchy::resolve_market(Origin::signed(ALICE), market_id, MarketOutcome::Binary(true)));
+        assert_eq!(Futarchy::markets(market_id).unwrap().status, MarketStatus::Proposed);
+    });
+}
+
+#[test]
+fn close_market_requires_creator_or_oracle() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_binary_with_oracle(ALICE, Some(CHARLIE)));
+        let market_id = *Futarchy::markets_by_creator(ALICE).last().unwrap();
+        assert_ok!(Futarchy::activate_market(Origin::signed(ALICE), market_id));
+
+        assert_noop!(
+            Futarchy::close_market(Origin::signed(BOB), market_id),
+            Error::<Test>::NotCreatorOrOracle
+        );
+        assert_ok!(Futarchy::close_market(Origin::signed(CHARLIE), market_id));
+        assert_noop!(
+            Futarchy::close_market(Origin::signed(ALICE), market_id),
+            Error::<Test>::MarketNotActive
+        );
+    });
+}
+
+#[test]
+fn create_market_rejects_lifetime_past_maximum() {
+    new_test_ext().execute_with(|| {
+        let too_long = System::block_number() + MaxMarketLifetime::get() + 1;
+        assert_noop!(
+            Futarchy::create_market(
+                Origin::signed(ALICE),
+                MarketType::Binary,
+                MarketMechanism::Lmsr,
+                too_long,
+                LIQUIDITY,
+                0,
+                None,
+                None,
+                Vec::new(),
+                None,
+                None,
+                MarketCategory::Other,
+                Vec::new(),
+                Vec::new()
+            ),
+            Error::<Test>::LifetimeTooLong
+        );
+    });
+}
+
+#[test]
+fn reap_market_deletes_settled_market() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+        assert_ok!(Futarchy::cancel_market(Origin::signed(ALICE), market_id));
+
+        // Not before the lifetime has passed
+        assert_noop!(
+            Futarchy::reap_market(Origin::signed(DAVE), market_id),
+            Error::<Test>::MarketNotReapable
+        );
+
+        System::set_block_number(1 + MaxMarketLifetime::get());
+        assert_ok!(Futarchy::reap_market(Origin::signed(DAVE), market_id));
+
+        assert!(Futarchy::markets(market_id).is_none());
+        assert_eq!(Balances::free_balance(DAVE), INITIAL_BALANCE + ReapIncentive::get());
+        assert_eq!(Balances::free_balance(Futarchy::market_account(market_id)), 0);
+        System::assert_last_event(Event::Futarchy(RawEvent::MarketReaped(DAVE, market_id, ReapIncentive::get())));
+    });
+}
+
+#[test]
+fn reap_market_waits_for_claims() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+        assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), market_id, 1, 50, None));
+        System::set_block_number(TRADING_END);
+        assert_ok!(Futarchy::resolve_market(Origin::signed(ALICE), market_id, MarketOutcome::Binary(true)));
+        System::set_block_number(1 + MaxMarketLifetime::get());
+        assert_ok!(Futarchy::finalize_market(Origin::signed(ALICE), market_id));
+
+        // BOB has yet to redeem the winning shares
+        assert_noop!(
+            Futarchy::reap_market(Origin::signed(DAVE), market_id),
+            Error::<Test>::OutstandingClaims
+        );
+        assert!(!Futarchy::price_history(market_id).is_empty());
+
+        assert_ok!(Futarchy::claim_winnings(Origin::signed(BOB), market_id));
+        let lp = Futarchy::lp_shares(market_id, ALICE);
+        assert_ok!(Futarchy::remove_liquidity(Origin::signed(ALICE), market_id, lp));
+        assert_ok!(Futarchy::reap_market(Origin::signed(DAVE), market_id));
+        assert!(Futarchy::markets(market_id).is_none());
+        assert!(Futarchy::markets_by_creator(ALICE).is_empty());
+        assert!(Futarchy::price_history(market_id).is_empty());
+    });
+}
+
+#[test]
+fn reap_market_rejects_open_market() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+        System::set_block_number(1 + MaxMarketLifetime::get());
+
+        assert_noop!(
+            Futarchy::reap_market(Origin::signed(DAVE), market_id),
+            Error::<Test>::MarketNotReapable
+        );
+    });
+}
+
+#[test]
+fn reap_market_keeps_parents_of_conditioned_markets() {
+    new_test_ext().execute_with(|| {
+        let parent = create_active_binary(ALICE);
+        let child = create_binary(ALICE);
+        assert_ok!(Futarchy::set_parent_conditions(
+            Origin::signed(ALICE),
+            child,
+            vec![(parent, true)].try_into().unwrap()
+        ));
+        assert!(crate::DependentMarkets::<Test>::contains_key(parent, child));
+        assert_ok!(Futarchy::cancel_market(Origin::signed(ALICE), parent));
+        System::set_block_number(1 + MaxMarketLifetime::get());
+
+        // The child still needs the parent's outcome
+        assert_noop!(
+            Futarchy::reap_market(Origin::signed(DAVE), parent),
+            Error::<Test>::ConditionDependents
+        );
+
+        // Once the child is gone the parent can go too
+        assert_ok!(Futarchy::cancel_market(Origin::signed(ALICE), child));
+        assert_ok!(Futarchy::reap_market(Origin::signed(DAVE), child));
+        assert!(!crate::DependentMarkets::<Test>::contains_key(parent, child));
+        assert_ok!(Futarchy::reap_market(Origin::signed(DAVE), parent));
+    });
+}
+
+// Push the recorded liquidity of a market to just below the largest balance
+fn saturate_liquidity(market_id: H256) {
+    Markets::<Test>::mutate(market_id, |market| {
+        market.as_mut().unwrap().total_liquidity = Balance::max_value() - 1;
+    });
+}
+
+#[test]
+fn buy_shares_fails_on_liquidity_overflow() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+        saturate_liquidity(market_id);
+
+        assert_noop!(
+            Futarchy::buy_shares(Origin::signed(BOB), market_id, 1, 50, None),
+            Error::<Test>::ArithmeticOverflow
+        );
+        assert_eq!(Futarchy::markets(market_id).unwrap().total_liquidity, Balance::max_value() - 1);
+    });
+}
+
+#[test]
+fn buy_shares_fails_on_fee_overflow() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+        CollectedFees::<Test>::insert(market_id, Balance::max_value());
+
+        assert_noop!(
+            Futarchy::buy_shares(Origin::signed(BOB), market_id, 1, 50, None),
+            Error::<Test>::ArithmeticOverflow
+        );
+    });
+}
+
+#[test]
+fn add_liquidity_fails_on_liquidity_overflow() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+        saturate_liquidity(market_id);
+
+        assert_noop!(
+            Futarchy::add_liquidity(Origin::signed(BOB), market_id, 100),
+            Error::<Test>::ArithmeticOverflow
+        );
+    });
+}
+
+#[test]
+fn buy_shares_enforces_global_position_cap() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+        assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), market_id, 1, MaxPositionPerAccount::get() - 10, None));
+
+        assert_noop!(
+            Futarchy::buy_shares(Origin::signed(BOB), market_id, 1, 11, None),
+            Error::<Test>::PositionLimitExceeded
+        );
+        // The cap applies per outcome and per account
+        assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), market_id, 1, 10, None));
+        assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), market_id, 0, 11, None));
+        assert_ok!(Futarchy::buy_shares(Origin::signed(CHARLIE), market_id, 1, 11, None));
+    });
+}
+
+#[test]
+fn market_position_cap_overrides_global_cap() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_binary(ALICE);
+        assert_noop!(
+            Futarchy::set_position_cap(Origin::signed(BOB), market_id, Some(100)),
+            Error::<Test>::NotMarketCreator
+        );
+        assert_ok!(Futarchy::set_position_cap(Origin::signed(ALICE), market_id, Some(100)));
+        System::assert_last_event(Event::Futarchy(RawEvent::PositionCapSet(market_id, Some(100))));
+        assert_ok!(Futarchy::activate_market(Origin::signed(ALICE), market_id));
+
+        assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), market_id, 1, 100, None));
+        assert_noop!(
+            Futarchy::buy_shares(Origin::signed(BOB), market_id, 1, MinTradeAmount::get(), None),
+            Error::<Test>::PositionLimitExceeded
+        );
+
+        // The cap is fixed once trading opens
+        assert_noop!(
+            Futarchy::set_position_cap(Origin::signed(ALICE), market_id, None),
+            Error::<Test>::MarketNotActivatable
+        );
+    });
+}
+
+#[test]
+fn position_cap_applies_to_transfers_and_splits() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_binary(ALICE);
+        assert_ok!(Futarchy::set_position_cap(Origin::signed(ALICE), market_id, Some(100)));
+        assert_ok!(Futarchy::activate_market(Origin::signed(ALICE), market_id));
+        assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), market_id, 1, 100, None));
+        assert_ok!(Futarchy::buy_shares(Origin::signed(CHARLIE), market_id, 1, 60, None));
+
+        // CHARLIE cannot be handed more than the cap
+        assert_noop!(
+            Futarchy::transfer_shares(Origin::signed(BOB), market_id, CHARLIE, 1, 41),
+            Error::<Test>::PositionLimitExceeded
+        );
+        assert_ok!(Futarchy::transfer_shares(Origin::signed(BOB), market_id, CHARLIE, 1, 40));
+
+        // nor mint past it
+        assert_noop!(
+            Futarchy::split_collateral(Origin::signed(CHARLIE), market_id, 1),
+            Error::<Test>::PositionLimitExceeded
+        );
+        assert_ok!(Futarchy::split_collateral(Origin::signed(BOB), market_id, 40));
+    });
+}
+
+#[test]
+fn price_of_moves_with_each_trade() {
+    new_test_ext().execute_with(|| {
+        let pool = create_pool_with_seed(BOB, 2_000);
+        assert_ok!(Futarchy::activate_market(Origin::signed(BOB), pool));
+
+        for market_id in [create_active_binary(ALICE), pool] {
+            // Every YES buy raises the YES price and lowers the NO price
+            let prices_now = || (Futarchy::price_of(market_id, 1).unwrap(), Futarchy::price_of(market_id, 0).unwrap());
+            let mut prices = vec![prices_now()];
+            for who in [CHARLIE, DAVE, CHARLIE] {
+                assert_ok!(Futarchy::buy_shares(Origin::signed(who), market_id, 1, 100, None));
+                prices.push(prices_now());
+            }
+            assert!(prices.windows(2).all(|pair| pair[1].0 > pair[0].0 && pair[1].1 < pair[0].1));
+
+            // Selling YES back moves it down again
+            assert_ok!(Futarchy::sell_shares(Origin::signed(DAVE), market_id, 1, 100, None));
+            assert!(Futarchy::price_of(market_id, 1).unwrap() < prices.last().unwrap().0);
+            assert_eq!(Futarchy::price_of(market_id, 2), None);
+        }
+    });
+}
+
+#[test]
+fn trades_record_price_history() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+
+        for (block, outcome, amount) in [(2, 1, 100), (3, 1, 100), (4, 0, 50), (5, 1, 80)] {
+            System::set_block_number(block);
+            assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), market_id, outcome, amount, None));
+        }
+
+        // Only the latest points are kept, oldest first
+        let history = Futarchy::price_history(market_id);
+        assert_eq!(history.len() as u32, MaxHistoryPoints::get());
+        let blocks: Vec<u64> = history.iter().map(|(block, _)| *block).collect();
+        assert_eq!(blocks, vec![3, 4, 5]);
+        assert_eq!(history.last().unwrap().1, Futarchy::price_of(market_id, 1).unwrap());
+        assert!(history[1].1 < history[0].1);
+    });
+}
+
+#[test]
+fn price_history_skips_moves_within_epsilon() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+        assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), market_id, 1, 100, None));
+
+        // The smallest trade barely moves a market this deep
+        System::set_block_number(2);
+        assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), market_id, 1, MinTradeAmount::get(), None));
+
+        assert_eq!(Futarchy::price_history(market_id).len(), 1);
+    });
+}
+
+#[test]
+fn force_cancel_market_requires_force_origin() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+
+        assert_noop!(
+            Futarchy::force_cancel_market(Origin::signed(ALICE), market_id),
+            sp_runtime::DispatchError::BadOrigin
+        );
+    });
+}
+
+#[test]
+fn force_cancel_market_refunds_traders_and_slashes_creator() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+        assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), market_id, 1, 50, None));
+        assert_ok!(Futarchy::buy_shares(Origin::signed(CHARLIE), market_id, 0, 30, None));
+        let bob_cost = Futarchy::cost_basis(market_id, BOB);
+        let bob_before = Balances::free_balance(BOB);
+
+        assert_ok!(Futarchy::force_cancel_market(Origin::root(), market_id));
+
+        assert_eq!(Futarchy::markets(market_id).unwrap().status, MarketStatus::Cancelled);
+        System::assert_last_event(Event::Futarchy(RawEvent::MarketForceCancelled(market_id)));
+
+        // Traders get their cost back, the deposit goes to the treasury
+        assert_eq!(Balances::free_balance(BOB), bob_before + bob_cost);
+        assert!(Futarchy::positions(market_id, CHARLIE).is_empty());
+        assert_eq!(Balances::reserved_balance(ALICE), 0);
+        assert_eq!(Balances::free_balance(TREASURY), 1 + MarketCreationDeposit::get());
+    });
+}
+
+#[test]
+fn migrate_market_reconstructs_positions() {
+    new_test_ext().execute_with(|| {
+        let from_id = create_active_binary(ALICE);
+        assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), from_id, 1, 50, None));
+        assert_ok!(Futarchy::buy_shares(Origin::signed(CHARLIE), from_id, 0, 30, None));
+        assert_ok!(Futarchy::add_liquidity(Origin::signed(CHARLIE), from_id, 200));
+        let before = Futarchy::markets(from_id).unwrap();
+        let prices = Futarchy::outcome_prices(from_id).unwrap();
+        let pot = Balances::free_balance(Futarchy::market_account(from_id));
+        let (bob, charlie) = (Futarchy::positions(from_id, BOB), Futarchy::positions(from_id, CHARLIE));
+        let lp = (Futarchy::lp_shares(from_id, ALICE), Futarchy::lp_shares(from_id, CHARLIE));
+
+        // Only the correctable parameters may change
+        let mut spec = binary_spec(TRADING_END + 20);
+        spec.liquidity_parameter = LIQUIDITY * 2;
+        assert_noop!(
+            Futarchy::migrate_market(Origin::root(), from_id, spec.clone(), 2, 2, 0),
+            Error::<Test>::MigrationMismatch
+        );
+        spec.liquidity_parameter = LIQUIDITY;
+        spec.oracle = Some(DAVE);
+        assert_noop!(
+            Futarchy::migrate_market(Origin::signed(ALICE), from_id, spec.clone(), 2, 2, 0),
+            sp_runtime::DispatchError::BadOrigin
+        );
+
+        // BOB and CHARLIE hold shares, ALICE and CHARLIE provide liquidity
+        assert_noop!(
+            Futarchy::migrate_market(Origin::root(), from_id, spec.clone(), 1, 2, 0),
+            Error::<Test>::MigrationTooLarge
+        );
+        assert_noop!(
+            Futarchy::migrate_market(Origin::root(), from_id, spec.clone(), 2, 1, 0),
+            Error::<Test>::MigrationTooLarge
+        );
+        assert_ok!(Futarchy::migrate_market(Origin::root(), from_id, spec, 2, 2, 0));
+
+        let market_id = *Futarchy::markets_by_creator(ALICE).last().unwrap();
+        System::assert_last_event(Event::Futarchy(RawEvent::MarketMigrated(from_id, market_id)));
+        assert_eq!(Futarchy::markets(from_id).unwrap().status, MarketStatus::Cancelled);
+        assert_eq!(Futarchy::markets_by_creator(ALICE).into_inner(), vec![market_id]);
+
+        // The replacement takes over the pot, pool and every participant
+        let market = Futarchy::markets(market_id).unwrap();
+        assert_eq!(market.status, MarketStatus::Active);
+        assert_eq!(market.trading_end, TRADING_END + 20);
+        assert_eq!(market.oracle, DAVE);
+        assert_eq!(market.metadata, before.metadata);
+        assert_eq!(market.total_liquidity, before.total_liquidity);
+        assert_eq!(Futarchy::outcome_prices(market_id).unwrap(), prices);
+        assert_eq!(Balances::free_balance(Futarchy::market_account(market_id)), pot);
+        assert_eq!(Balances::free_balance(Futarchy::market_account(from_id)), 0);
+        assert_eq!((Futarchy::positions(market_id, BOB), Futarchy::positions(market_id, CHARLIE)), (bob, charlie));
+        assert_eq!((Futarchy::lp_shares(market_id, ALICE), Futarchy::lp_shares(market_id, CHARLIE)), lp);
+        assert!(Futarchy::positions(from_id, BOB).is_empty());
+        assert!(HeldMarkets::<Test>::contains_key(BOB, market_id));
+        assert!(!HeldMarkets::<Test>::contains_key(BOB, from_id));
+        assert_eq!(Balances::reserved_balance(ALICE), MarketCreationDeposit::get());
+
+        // Trading carries on in the replacement
+        assert_ok!(Futarchy::sell_shares(Origin::signed(BOB), market_id, 1, 50, None));
+    });
+}
+
+#[test]
+fn migrate_market_keeps_oracles_and_featured_flag() {
+    new_test_ext().execute_with(|| {
+        let from_id = create_with_backup_oracles();
+        assert_ok!(Futarchy::set_featured(Origin::root(), from_id, true));
+        assert_ok!(Futarchy::migrate_market(Origin::root(), from_id, binary_spec(TRADING_END + 20), 0, 1, 0));
+        let market_id = *Futarchy::markets_by_creator(ALICE).last().unwrap();
+        assert_eq!(Futarchy::backup_oracles(market_id).into_inner(), vec![BOB, DAVE]);
+        assert_eq!(Futarchy::featured_markets(), vec![market_id]);
+
+        let from_id = create_committee_binary([1, 1, 1], 2);
+        let committee = Futarchy::oracle_committee(from_id);
+        assert_ok!(Futarchy::migrate_market(Origin::root(), from_id, binary_spec(TRADING_END + 20), 0, 1, 0));
+        let market_id = *Futarchy::markets_by_creator(ALICE).last().unwrap();
+        assert_eq!(Futarchy::oracle_committee(market_id), committee);
+        System::set_block_number(TRADING_END + 20);
+        assert_ok!(Futarchy::submit_resolution(Origin::signed(BOB), market_id, MarketOutcome::Binary(true)));
+    });
+}
+
+#[test]
+fn migrate_market_carries_conditions_over() {
+    new_test_ext().execute_with(|| {
+        let parent = create_active_binary(ALICE);
+        let child = create_binary(ALICE);
+        assert_ok!(Futarchy::set_parent_conditions(
+            Origin::signed(ALICE),
+            child,
+            vec![(parent, true)].try_into().unwrap()
+        ));
+
+        // A dependent market has to be declared like any holder
+        let spec = binary_spec(TRADING_END + 20);
+        assert_noop!(
+            Futarchy::migrate_market(Origin::root(), parent, spec.clone(), 0, 1, 0),
+            Error::<Test>::MigrationTooLarge
+        );
+        assert_ok!(Futarchy::migrate_market(Origin::root(), parent, spec.clone(), 0, 1, 1));
+        let new_parent = *Futarchy::markets_by_creator(ALICE).last().unwrap();
+
+        // The child now waits on the replacement, leaving the source free to go
+        assert_eq!(Futarchy::markets(child).unwrap().parent_conditions.into_inner(), vec![(new_parent, true)]);
+        assert!(crate::DependentMarkets::<Test>::contains_key(new_parent, child));
+        assert!(!crate::DependentMarkets::<Test>::contains_key(parent, child));
+
+        // and a migrated child takes its own conditions along
+        assert_ok!(Futarchy::migrate_market(Origin::root(), child, spec, 0, 1, 0));
+        let new_child = *Futarchy::markets_by_creator(ALICE).last().unwrap();
+        assert_eq!(Futarchy::markets(new_child).unwrap().parent_conditions.into_inner(), vec![(new_parent, true)]);
+        assert!(Futarchy::markets(child).unwrap().parent_conditions.is_empty());
+        assert!(crate::DependentMarkets::<Test>::contains_key(new_parent, new_child));
+        assert!(!crate::DependentMarkets::<Test>::contains_key(new_parent, child));
+    });
+}
+
+#[test]
+fn migrate_market_reopens_closed_market_within_active_cap() {
+    new_test_ext().execute_with(|| {
+        set_max_active_markets(1);
+        let from_id = create_active_binary(ALICE);
+        assert_ok!(Futarchy::close_market(Origin::signed(ALICE), from_id));
+        create_active_binary(BOB);
+
+        assert_noop!(
+            Futarchy::migrate_market(Origin::root(), from_id, binary_spec(TRADING_END + 20), 0, 1, 0),
+            Error::<Test>::TooManyActiveMarkets
+        );
+    });
+}
+
+#[test]
+fn refunds_are_paid_in_pages() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+        for who in [ALICE, BOB, CHARLIE, DAVE] {
+            assert_ok!(Futarchy::buy_shares(Origin::signed(who), market_id, 1, 20, None));
+        }
+        let owed = Futarchy::total_cost_basis(market_id);
+
+        // Cancelling refunds the first `MaxRefundsPerCall` holders
+        assert_ok!(Futarchy::force_cancel_market(Origin::root(), market_id));
+        let (_, remaining) = Futarchy::pending_refunds(market_id).unwrap();
+        assert!(remaining > 0 && remaining < owed);
+        assert!(System::events().iter().any(|record| {
+            record.event == Event::Futarchy(RawEvent::RefundsProgress(market_id, 2, remaining))
+        }));
+
+        // The market cannot be reaped while holders are still owed
+        System::set_block_number(1 + MaxMarketLifetime::get());
+        assert_noop!(
+            Futarchy::reap_market(Origin::signed(BOB), market_id),
+            Error::<Test>::OutstandingClaims
+        );
+
+        // A second call finishes the holders
+        assert_ok!(Futarchy::process_refunds(Origin::signed(BOB), market_id));
+        System::assert_has_event(Event::Futarchy(RawEvent::RefundsProgress(market_id, 2, 0)));
+        assert_eq!(Futarchy::pending_refunds(market_id), None);
+        for who in [ALICE, BOB, CHARLIE, DAVE] {
+            assert_eq!(Futarchy::cost_basis(market_id, who), 0);
+            assert!(Futarchy::positions(market_id, who).is_empty());
+        }
+
+        // and a third pays the liquidity provider what is left of the pot
+        let (lp_pot, _) = Futarchy::lp_refunds(market_id).unwrap();
+        let before = Balances::free_balance(ALICE);
+        assert_ok!(Futarchy::process_refunds(Origin::signed(BOB), market_id));
+        assert_eq!(Balances::free_balance(ALICE), before + lp_pot);
+        assert_eq!(Futarchy::lp_shares(market_id, ALICE), 0);
+        assert_eq!(Futarchy::lp_refunds(market_id), None);
+        assert_noop!(
+            Futarchy::process_refunds(Origin::signed(BOB), market_id),
+            Error::<Test>::NothingToClaim
+        );
+        assert_ok!(Futarchy::reap_market(Origin::signed(BOB), market_id));
+    });
+}
+
+#[test]
+fn curation_origin_features_markets() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+
+        assert_noop!(
+            Futarchy::set_featured(Origin::signed(ALICE), market_id, true),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_ok!(Futarchy::set_featured(Origin::root(), market_id, true));
+        assert_eq!(Futarchy::featured_markets(), vec![market_id]);
+        System::assert_last_event(Event::Futarchy(RawEvent::FeaturedSet(market_id, true)));
+
+        assert_ok!(Futarchy::set_featured(Origin::root(), market_id, false));
+        assert!(Futarchy::featured_markets().is_empty());
+
+        // Settled markets cannot be featured
+        assert_ok!(Futarchy::force_cancel_market(Origin::root(), market_id));
+        assert_noop!(
+            Futarchy::set_featured(Origin::root(), market_id, true),
+            Error::<Test>::MarketSettled
+        );
+    });
+}
+
+#[test]
+fn featured_markets_drop_out_once_settled() {
+    new_test_ext().execute_with(|| {
+        let resolved = create_active_binary(ALICE);
+        let cancelled = create_binary(ALICE);
+        assert_ok!(Futarchy::set_featured(Origin::root(), resolved, true));
+        assert_ok!(Futarchy::set_featured(Origin::root(), cancelled, true));
+
+        assert_ok!(Futarchy::cancel_market(Origin::signed(ALICE), cancelled));
+        assert_eq!(Futarchy::featured_markets(), vec![resolved]);
+
+        // Still featured while the reported outcome can be disputed
+        System::set_block_number(TRADING_END);
+        assert_ok!(Futarchy::resolve_market(Origin::signed(ALICE), resolved, MarketOutcome::Binary(true)));
+        assert_eq!(Futarchy::featured_markets(), vec![resolved]);
+        System::set_block_number(TRADING_END + DisputePeriod::get());
+        assert_ok!(Futarchy::finalize_market(Origin::signed(BOB), resolved));
+        assert!(Futarchy::featured_markets().is_empty());
+    });
+}
+
+#[test]
+fn split_collateral_mints_complete_sets() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+        let prices = Futarchy::outcome_prices(market_id).unwrap();
+
+        assert_ok!(Futarchy::split_collateral(Origin::signed(BOB), market_id, 100));
+
+        assert_eq!(Balances::free_balance(BOB), INITIAL_BALANCE - 100);
+        let position = Futarchy::positions(market_id, BOB);
+        assert_eq!((position.balance_of(0), position.balance_of(1)), (100, 100));
+        // Complete sets leave prices where they were
+        assert_eq!(Futarchy::outcome_prices(market_id).unwrap(), prices);
+        System::assert_last_event(Event::Futarchy(RawEvent::CollateralSplit(BOB, market_id, 100)));
+    });
+}
+
+#[test]
+fn split_then_merge_costs_only_fees() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+
+        assert_ok!(Futarchy::split_collateral(Origin::signed(BOB), market_id, 1_000));
+        assert_ok!(Futarchy::merge_shares(Origin::signed(BOB), market_id, 1_000));
+
+        let fee = TradingFee::get().mul_floor(1_000);
+        assert_eq!(Balances::free_balance(BOB), INITIAL_BALANCE - fee);
+        assert!(Futarchy::positions(market_id, BOB).is_empty());
+        assert_eq!(Futarchy::collected_fees(market_id), fee);
+        System::assert_last_event(Event::Futarchy(RawEvent::SharesMerged(BOB, market_id, 1_000, fee)));
+    });
+}
+
+#[test]
+fn merge_shares_requires_every_outcome() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+        assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), market_id, 1, 100, None));
+
+        assert_noop!(
+            Futarchy::merge_shares(Origin::signed(BOB), market_id, 50),
+            Error::<Test>::InsufficientShares
+        );
+    });
+}
+
+// Create an active binary market from ALICE reported YES by CHARLIE
+fn create_reported_by_charlie() -> H256 {
+    assert_ok!(create_binary_with_oracle(ALICE, Some(CHARLIE)));
+    let market_id = *Futarchy::markets_by_creator(ALICE).last().unwrap();
+    assert_ok!(Futarchy::activate_market(Origin::signed(ALICE), market_id));
+    System::set_block_number(TRADING_END);
+    assert_ok!(Futarchy::resolve_market(Origin::signed(CHARLIE), market_id, MarketOutcome::Binary(true)));
+    market_id
+}
+
+#[test]
+fn resolve_market_reserves_resolution_bond() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_reported_by_charlie();
+
+        assert_eq!(Balances::reserved_balance(CHARLIE), ResolutionBond::get());
+        assert_eq!(Futarchy::markets(market_id).unwrap().resolution_bond, ResolutionBond::get());
+    });
+}
+
+#[test]
+fn resolution_bond_released_after_uncontested_window() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_reported_by_charlie();
+
+        System::set_block_number(TRADING_END + DisputePeriod::get());
+        assert_ok!(Futarchy::finalize_market(Origin::signed(DAVE), market_id));
+
+        assert_eq!(Balances::reserved_balance(CHARLIE), 0);
+        assert_eq!(Balances::free_balance(CHARLIE), INITIAL_BALANCE);
+        assert_eq!(Futarchy::markets(market_id).unwrap().resolution_bond, 0);
+
+        // The creator gets the creation deposit back as well
+        assert_eq!(Balances::reserved_balance(ALICE), 0);
+        assert!(Futarchy::markets(market_id).unwrap().deposit_refunded);
+        System::assert_has_event(Event::Futarchy(RawEvent::DepositRefunded(ALICE, MarketCreationDeposit::get())));
+    });
+}
+
+#[test]
+fn dispute_market_bonds_disputer_and_emits_event() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_reported_by_charlie();
+        assert_ok!(Futarchy::dispute_market(Origin::signed(DAVE), market_id));
+
+        let market = Futarchy::markets(market_id).unwrap();
+        assert_eq!(market.status, MarketStatus::Disputed);
+        assert_eq!(market.dispute_bond, MinDisputeBond::get());
+        assert_eq!(Balances::reserved_balance(DAVE), MinDisputeBond::get());
+        System::assert_last_event(Event::Futarchy(RawEvent::MarketDisputed(DAVE, market_id)));
+
+        // A disputed market cannot be disputed a second time
+        assert_noop!(
+            Futarchy::dispute_market(Origin::signed(BOB), market_id),
+            Error::<Test>::MarketNotDisputable
+        );
+    });
+}
+
+#[test]
+fn resolution_bond_slashed_when_dispute_overturns_report() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_reported_by_charlie();
+        assert_ok!(Futarchy::dispute_market(Origin::signed(DAVE), market_id));
+
+        assert_ok!(Futarchy::resolve_dispute(Origin::root(), market_id, MarketOutcome::Binary(false)));
+
+        assert_eq!(Balances::reserved_balance(CHARLIE), 0);
+        assert_eq!(Balances::free_balance(CHARLIE), INITIAL_BALANCE - ResolutionBond::get());
+
+        // An overturned market forfeits the creation deposit instead
+        assert_eq!(Balances::reserved_balance(ALICE), 0);
+        assert!(!System::events().iter().any(|record| {
+            matches!(record.event, Event::Futarchy(RawEvent::DepositRefunded(..)))
+        }));
+    });
+}
+
+#[test]
+fn resolution_bond_released_when_dispute_upholds_report() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_reported_by_charlie();
+        assert_ok!(Futarchy::dispute_market(Origin::signed(DAVE), market_id));
+
+        assert_ok!(Futarchy::resolve_dispute(Origin::root(), market_id, MarketOutcome::Binary(true)));
+
+        assert_eq!(Balances::free_balance(CHARLIE), INITIAL_BALANCE);
+    });
+}
+
+#[test]
+fn dispute_bond_slashed_when_report_stands() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_reported_by_charlie();
+        assert_ok!(Futarchy::dispute_market(Origin::signed(DAVE), market_id));
+        let bond = Futarchy::markets(market_id).unwrap().dispute_bond;
+
+        assert_ok!(Futarchy::resolve_dispute(Origin::root(), market_id, MarketOutcome::Binary(true)));
+
+        assert_eq!(Balances::reserved_balance(DAVE), 0);
+        assert_eq!(Balances::free_balance(DAVE), INITIAL_BALANCE - bond);
+        let market = Futarchy::markets(market_id).unwrap();
+        assert_eq!(market.status, MarketStatus::Resolved);
+        assert_eq!(market.resolved_outcome, Some(MarketOutcome::Binary(true)));
+        System::assert_has_event(Event::Futarchy(RawEvent::DisputeResolved(market_id, MarketOutcome::Binary(true), true)));
+    });
+}
+
+#[test]
+fn dispute_bond_refunded_when_report_overturned() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_reported_by_charlie();
+        assert_ok!(Futarchy::dispute_market(Origin::signed(DAVE), market_id));
+
+        assert_ok!(Futarchy::resolve_dispute(Origin::root(), market_id, MarketOutcome::Binary(false)));
+
+        assert_eq!(Balances::reserved_balance(DAVE), 0);
+        assert_eq!(Balances::free_balance(DAVE), INITIAL_BALANCE);
+        let market = Futarchy::markets(market_id).unwrap();
+        assert_eq!(market.status, MarketStatus::Resolved);
+        assert_eq!(market.resolved_outcome, Some(MarketOutcome::Binary(false)));
+        System::assert_has_event(Event::Futarchy(RawEvent::DisputeResolved(market_id, MarketOutcome::Binary(false), false)));
+    });
+}
+
+#[test]
+fn only_yes_holders_claim_a_yes_market() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+        assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), market_id, 1, 100, None));
+        assert_ok!(Futarchy::buy_shares(Origin::signed(CHARLIE), market_id, 0, 100, None));
+
+        System::set_block_number(TRADING_END);
+        assert_ok!(Futarchy::resolve_market(Origin::signed(ALICE), market_id, MarketOutcome::Binary(true)));
+        System::set_block_number(TRADING_END + DisputePeriod::get());
+
+        // YES pays one unit per share
+        let before = Balances::free_balance(BOB);
+        assert_ok!(Futarchy::claim_winnings(Origin::signed(BOB), market_id));
+        assert_eq!(Balances::free_balance(BOB) - before, 100);
+        System::assert_last_event(Event::Futarchy(RawEvent::WinningsClaimed(BOB, market_id, 100)));
+        assert!(Futarchy::positions(market_id, BOB).is_empty());
+
+        // Neither a second claim nor the NO holder gets anything
+        assert_noop!(
+            Futarchy::claim_winnings(Origin::signed(BOB), market_id),
+            Error::<Test>::NothingToClaim
+        );
+        assert_noop!(
+            Futarchy::claim_winnings(Origin::signed(CHARLIE), market_id),
+            Error::<Test>::NothingToClaim
+        );
+        assert_eq!(Futarchy::positions(market_id, CHARLIE).balance_of(0), 100);
+    });
+}
+
+#[test]
+fn winnings_preview_matches_claim() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+        assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), market_id, 1, 120, None));
+        assert_eq!(Futarchy::winnings_preview(&market_id, &BOB), None);
+
+        System::set_block_number(TRADING_END);
+        assert_ok!(Futarchy::resolve_market(Origin::signed(ALICE), market_id, MarketOutcome::Binary(true)));
+        // Still open to disputes
+        assert_eq!(Futarchy::winnings_preview(&market_id, &BOB), None);
+
+        System::set_block_number(TRADING_END + DisputePeriod::get());
+        let preview = Futarchy::winnings_preview(&market_id, &BOB).unwrap();
+        assert_eq!(preview, 120);
+        assert_eq!(Futarchy::winnings_preview(&market_id, &CHARLIE), Some(0));
+
+        let before = Balances::free_balance(BOB);
+        assert_ok!(Futarchy::claim_winnings(Origin::signed(BOB), market_id));
+        assert_eq!(Balances::free_balance(BOB) - before, preview);
+    });
+}
+
+#[test]
+fn markets_are_listed_under_their_category() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_binary(ALICE);
+        let specs = vec![binary_spec(TRADING_END); 2];
+        assert_ok!(Futarchy::create_markets_batch(Origin::signed(BOB), specs.try_into().unwrap()));
+
+        assert_eq!(Futarchy::markets_by_category(MarketCategory::Other).into_inner(), vec![market_id]);
+        assert_eq!(
+            Futarchy::markets_by_category(MarketCategory::Sports).into_inner(),
+            Futarchy::markets_by_creator(BOB).into_inner()
+        );
+        assert!(Futarchy::markets_by_category(MarketCategory::Politics).is_empty());
+    });
+}
+
+#[test]
+fn cancelled_market_leaves_its_category() {
+    new_test_ext().execute_with(|| {
+        let cancelled = create_binary(ALICE);
+        let kept = create_binary(BOB);
+
+        assert_ok!(Futarchy::cancel_market(Origin::signed(ALICE), cancelled));
+        assert_eq!(Futarchy::markets_by_category(MarketCategory::Other).into_inner(), vec![kept]);
+    });
+}
+
+#[test]
+fn full_category_rejects_new_markets() {
+    new_test_ext().execute_with(|| {
+        let specs = vec![binary_spec(TRADING_END); MaxPerCategory::get() as usize];
+        assert_ok!(Futarchy::create_markets_batch(Origin::signed(ALICE), specs.try_into().unwrap()));
+
+        assert_noop!(
+            Futarchy::create_markets_batch(Origin::signed(BOB), vec![binary_spec(TRADING_END)].try_into().unwrap()),
+            Error::<Test>::TooManyInCategory
+        );
+    });
+}
+
+#[test]
+fn creator_index_caps_markets_per_creator() {
+    new_test_ext().execute_with(|| {
+        // Spread ALICE's markets over every category to stay under `MaxPerCategory`
+        let categories = [MarketCategory::Sports, MarketCategory::Politics, MarketCategory::Crypto, MarketCategory::Other];
+        for category in categories {
+            let spec = MarketSpecOf::<Test> { category, ..binary_spec(TRADING_END) };
+            let specs = vec![spec; MaxPerCategory::get() as usize];
+            assert_ok!(Futarchy::create_markets_batch(Origin::signed(ALICE), specs.try_into().unwrap()));
+        }
+        assert_eq!(Futarchy::markets_by_creator(ALICE).len() as u32, MaxMarketsPerCreator::get());
+
+        assert_noop!(
+            Futarchy::create_markets_batch(Origin::signed(ALICE), vec![binary_spec(TRADING_END)].try_into().unwrap()),
+            Error::<Test>::TooManyMarkets
+        );
+    });
+}
+
+// Create an active binary market from ALICE resolved by a committee of BOB,
+// CHARLIE and DAVE with the given weights and threshold
+fn create_committee_binary(weights: [u32; 3], threshold: u32) -> H256 {
+    let market_id = create_binary(ALICE);
+    let oracles = vec![(BOB, weights[0]), (CHARLIE, weights[1]), (DAVE, weights[2])];
+    assert_ok!(Futarchy::set_oracle_committee(
+        Origin::signed(ALICE),
+        market_id,
+        oracles.try_into().unwrap(),
+        threshold
+    ));
+    assert_ok!(Futarchy::activate_market(Origin::signed(ALICE), market_id));
+    System::set_block_number(TRADING_END);
+    market_id
+}
+
+#[test]
+fn committee_resolves_once_quorum_is_reached() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_committee_binary([1, 1, 1], 2);
+
+        assert_ok!(Futarchy::submit_resolution(Origin::signed(BOB), market_id, MarketOutcome::Binary(true)));
+        assert_eq!(Futarchy::markets(market_id).unwrap().status, MarketStatus::Active);
+        assert_eq!(Futarchy::resolution_votes(market_id, BOB), Some(MarketOutcome::Binary(true)));
+
+        assert_ok!(Futarchy::submit_resolution(Origin::signed(CHARLIE), market_id, MarketOutcome::Binary(true)));
+        let market = Futarchy::markets(market_id).unwrap();
+        assert_eq!(market.status, MarketStatus::Resolved);
+        assert_eq!(market.resolved_outcome, Some(MarketOutcome::Binary(true)));
+        assert_eq!(Balances::reserved_balance(ALICE), 0);
+        assert_eq!(Futarchy::resolution_votes(market_id, BOB), None);
+        System::assert_last_event(Event::Futarchy(RawEvent::MarketFinalized(market_id)));
+    });
+}
+
+#[test]
+fn committee_rejects_duplicates_and_outsiders() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_committee_binary([1, 1, 1], 2);
+
+        assert_ok!(Futarchy::submit_resolution(Origin::signed(BOB), market_id, MarketOutcome::Binary(true)));
+        assert_noop!(
+            Futarchy::submit_resolution(Origin::signed(BOB), market_id, MarketOutcome::Binary(false)),
+            Error::<Test>::DuplicateSubmission
+        );
+        assert_noop!(
+            Futarchy::submit_resolution(Origin::signed(ALICE), market_id, MarketOutcome::Binary(true)),
+            Error::<Test>::NotAuthorizedOracle
+        );
+        // The single oracle steps aside for the committee
+        assert_noop!(
+            Futarchy::resolve_market(Origin::signed(ALICE), market_id, MarketOutcome::Binary(true)),
+            Error::<Test>::NotAuthorizedOracle
+        );
+    });
+}
+
+#[test]
+fn committee_tie_goes_to_lowest_outcome() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_committee_binary([1, 1, 1], 2);
+
+        assert_ok!(Futarchy::submit_resolution(Origin::signed(BOB), market_id, MarketOutcome::Binary(true)));
+        assert_ok!(Futarchy::submit_resolution(Origin::signed(CHARLIE), market_id, MarketOutcome::Binary(false)));
+        assert_eq!(
+            Futarchy::markets(market_id).unwrap().resolved_outcome,
+            Some(MarketOutcome::Binary(false))
+        );
+    });
+}
+
+#[test]
+fn committee_plurality_is_weighted() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_committee_binary([3, 1, 1], 3);
+
+        assert_ok!(Futarchy::submit_resolution(Origin::signed(CHARLIE), market_id, MarketOutcome::Binary(false)));
+        assert_ok!(Futarchy::submit_resolution(Origin::signed(DAVE), market_id, MarketOutcome::Binary(false)));
+        assert_ok!(Futarchy::submit_resolution(Origin::signed(BOB), market_id, MarketOutcome::Binary(true)));
+        assert_eq!(
+            Futarchy::markets(market_id).unwrap().resolved_outcome,
+            Some(MarketOutcome::Binary(true))
+        );
+    });
+}
+
+#[test]
+fn scalar_votes_aggregate_to_weighted_median() {
+    let votes = vec![
+        (MarketOutcome::Scalar(90), 1),
+        (MarketOutcome::Scalar(10), 1),
+        (MarketOutcome::Scalar(50), 1),
+    ];
+    assert_eq!(Futarchy::aggregate_votes(votes), Some(MarketOutcome::Scalar(50)));
+
+    let votes = vec![(MarketOutcome::Scalar(10), 1), (MarketOutcome::Scalar(90), 3)];
+    assert_eq!(Futarchy::aggregate_votes(votes), Some(MarketOutcome::Scalar(90)));
+}
+
+#[test]
+fn set_oracle_committee_rejects_invalid_threshold() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_binary(ALICE);
+        for threshold in [0, 3] {
+            assert_noop!(
+                Futarchy::set_oracle_committee(
+                    Origin::signed(ALICE),
+                    market_id,
+                    vec![(BOB, 1), (CHARLIE, 1)].try_into().unwrap(),
+                    threshold
+                ),
+                Error::<Test>::InvalidCommittee
+            );
+        }
+    });
+}
+
+// Create a smoothstep scalar market on [0, 3] resolving to 1, where LONG pays
+// 3 * 7/27 = 7/9 and SHORT the remaining 20/9 of the set's 3, with ten LONG
+// shares held by BOB and CHARLIE each and ten SHORT shares held by DAVE, and
+// move past its dispute window
+fn create_dusty_scalar() -> H256 {
+    assert_ok!(Futarchy::create_market(
+        Origin::signed(ALICE),
+        MarketType::Scalar,
+        MarketMechanism::Lmsr,
+        TRADING_END,
+        LIQUIDITY,
+        0,
+        Some((0, 3)),
+        None,
+        Vec::new(),
+        None,
+        None,
+        MarketCategory::Other,
+        Vec::new(),
+        Vec::new()
+    ));
+    let market_id = *Futarchy::markets_by_creator(ALICE).last().unwrap();
+    assert_ok!(Futarchy::set_payout_curve(Origin::signed(ALICE), market_id, ScalarCurve::Sigmoid));
+    assert_ok!(Futarchy::activate_market(Origin::signed(ALICE), market_id));
+    for (who, outcome) in [(BOB, 1), (CHARLIE, 1), (DAVE, 0)] {
+        assert_ok!(Futarchy::buy_shares(Origin::signed(who), market_id, outcome, 10, None));
+    }
+    System::set_block_number(TRADING_END);
+    assert_ok!(Futarchy::resolve_market(Origin::signed(ALICE), market_id, MarketOutcome::Scalar(1)));
+    System::set_block_number(TRADING_END + DisputePeriod::get());
+    market_id
+}
+
+// Claim for every holder in turn, returning what each of them received
+fn claim_all(market_id: H256) -> Vec<Balance> {
+    [BOB, CHARLIE, DAVE]
+        .iter()
+        .map(|who| {
+            let before = Balances::free_balance(who);
+            assert_ok!(Futarchy::claim_winnings(Origin::signed(*who), market_id));
+            Balances::free_balance(who) - before
+        })
+        .collect()
+}
+
+#[test]
+fn payout_dust_goes_to_treasury() {
+    new_test_ext().execute_with(|| {
+        set_payout_rounding(PayoutRounding::DustToTreasury);
+        let market_id = create_dusty_scalar();
+        let treasury = Balances::free_balance(TREASURY);
+
+        // 70/9 and 200/9 round down, a whole unit of dust is left over
+        let claims = claim_all(market_id);
+        assert_eq!(claims, vec![7, 7, 22]);
+        assert_eq!(Balances::free_balance(TREASURY), treasury + 1);
+        assert_eq!(Futarchy::payout_dust(market_id), FixedU128::from_inner(0));
+        System::assert_has_event(Event::Futarchy(RawEvent::PayoutDustReleased(market_id, TREASURY, 1)));
+
+        // Conservation: nothing beyond the exact 340/9 left the pot, and the
+        // liquidity providers can still be paid in full
+        let paid: Balance = claims.iter().sum::<Balance>() + 1;
+        assert!(9 * paid <= 340);
+        let lp = Futarchy::lp_shares(market_id, ALICE);
+        assert_ok!(Futarchy::remove_liquidity(Origin::signed(ALICE), market_id, lp));
+    });
+}
+
+#[test]
+fn payout_dust_goes_to_last_claimant() {
+    new_test_ext().execute_with(|| {
+        set_payout_rounding(PayoutRounding::DustToLastClaimant);
+        let market_id = create_dusty_scalar();
+        let treasury = Balances::free_balance(TREASURY);
+
+        assert_ok!(Futarchy::claim_winnings(Origin::signed(BOB), market_id));
+        assert_ok!(Futarchy::claim_winnings(Origin::signed(CHARLIE), market_id));
+        assert_eq!(Futarchy::winnings_preview(&market_id, &DAVE), Some(23));
+
+        let before = Balances::free_balance(DAVE);
+        assert_ok!(Futarchy::claim_winnings(Origin::signed(DAVE), market_id));
+        assert_eq!(Balances::free_balance(DAVE) - before, 23);
+        assert_eq!(Balances::free_balance(TREASURY), treasury);
+
+        let lp = Futarchy::lp_shares(market_id, ALICE);
+        assert_ok!(Futarchy::remove_liquidity(Origin::signed(ALICE), market_id, lp));
+    });
+}
+
+#[test]
+fn pause_freezes_trading_until_resumed() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+        assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), market_id, 1, 50, None));
+
+        assert_noop!(Futarchy::set_paused(Origin::signed(ALICE), true), sp_runtime::DispatchError::BadOrigin);
+        assert_ok!(Futarchy::set_paused(Origin::root(), true));
+        System::assert_last_event(Event::Futarchy(RawEvent::PausedSet(true)));
+
+        assert_noop!(
+            Futarchy::buy_shares(Origin::signed(BOB), market_id, 1, 50, None),
+            Error::<Test>::Paused
+        );
+        assert_noop!(
+            Futarchy::sell_shares(Origin::signed(BOB), market_id, 1, 50, None),
+            Error::<Test>::Paused
+        );
+        assert_noop!(
+            Futarchy::transfer_shares(Origin::signed(BOB), market_id, CHARLIE, 1, 10),
+            Error::<Test>::Paused
+        );
+        assert_noop!(Futarchy::split_collateral(Origin::signed(BOB), market_id, 10), Error::<Test>::Paused);
+        assert_noop!(Futarchy::merge_shares(Origin::signed(BOB), market_id, 10), Error::<Test>::Paused);
+        assert_noop!(Futarchy::add_liquidity(Origin::signed(BOB), market_id, 100), Error::<Test>::Paused);
+        assert_noop!(Futarchy::remove_liquidity(Origin::signed(ALICE), market_id, 100), Error::<Test>::Paused);
+
+        assert_ok!(Futarchy::set_paused(Origin::root(), false));
+        assert_ok!(Futarchy::sell_shares(Origin::signed(BOB), market_id, 1, 50, None));
+    });
+}
+
+#[test]
+fn pause_leaves_resolution_and_claims_open() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+        assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), market_id, 1, 50, None));
+        assert_ok!(Futarchy::set_paused(Origin::root(), true));
+
+        System::set_block_number(TRADING_END);
+        assert_ok!(Futarchy::resolve_market(Origin::signed(ALICE), market_id, MarketOutcome::Binary(true)));
+        System::set_block_number(TRADING_END + DisputePeriod::get());
+        assert_ok!(Futarchy::claim_winnings(Origin::signed(BOB), market_id));
+    });
+}
+
+#[test]
+fn failed_payment_mints_no_shares() {
+    new_test_ext().execute_with(|| {
+        setup_usd();
+        assert_ok!(Futarchy::create_market(
+            Origin::signed(ALICE),
+            MarketType::Binary,
+            MarketMechanism::Lmsr,
+            TRADING_END,
+            LIQUIDITY,
+            0,
+            None,
+            None,
+            Vec::new(),
+            None,
+            Some(USD),
+            MarketCategory::Other,
+            Vec::new(),
+            Vec::new()
+        ));
+        let market_id = *Futarchy::markets_by_creator(ALICE).last().unwrap();
+        assert_ok!(Futarchy::activate_market(Origin::signed(ALICE), market_id));
+        let liquidity = Futarchy::markets(market_id).unwrap().total_liquidity;
+
+        // CHARLIE holds no USD, so the payment is the step that fails
+        assert!(Futarchy::buy_shares(Origin::signed(CHARLIE), market_id, 1, 50, None).is_err());
+        assert!(Futarchy::positions(market_id, CHARLIE).is_empty());
+        assert!(Futarchy::total_shares(market_id).is_empty());
+        assert!(!HeldMarkets::<Test>::contains_key(CHARLIE, market_id));
+        assert_eq!(Futarchy::cost_basis(market_id, CHARLIE), 0);
+        assert_eq!(Futarchy::collected_fees(market_id), 0);
+        assert!(Futarchy::price_history(market_id).is_empty());
+        assert_eq!(Futarchy::markets(market_id).unwrap().total_liquidity, liquidity);
+    });
+}
+
+#[test]
+fn trades_below_minimum_are_rejected() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+        let min = MinTradeAmount::get();
+
+        assert_noop!(
+            Futarchy::buy_shares(Origin::signed(BOB), market_id, 1, min - 1, None),
+            Error::<Test>::TradeTooSmall
+        );
+        assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), market_id, 1, min, None));
+        assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), market_id, 1, min + 1, None));
+
+        assert_noop!(
+            Futarchy::sell_shares(Origin::signed(BOB), market_id, 1, min - 1, None),
+            Error::<Test>::TradeTooSmall
+        );
+        assert_ok!(Futarchy::sell_shares(Origin::signed(BOB), market_id, 1, min + 1, None));
+        assert_ok!(Futarchy::sell_shares(Origin::signed(BOB), market_id, 1, min, None));
+        assert!(Futarchy::positions(market_id, BOB).is_empty());
+    });
+}
+
+#[test]
+fn clone_market_reruns_resolved_question() {
+    new_test_ext().execute_with(|| {
+        let source_id = create_active_binary(ALICE);
+        assert_noop!(
+            Futarchy::clone_market(Origin::signed(ALICE), source_id, TRADING_END + 20),
+            Error::<Test>::MarketNotResolved
+        );
+        System::set_block_number(TRADING_END);
+        assert_ok!(Futarchy::resolve_market(Origin::signed(ALICE), source_id, MarketOutcome::Binary(true)));
+        System::set_block_number(TRADING_END + DisputePeriod::get());
+        assert_ok!(Futarchy::finalize_market(Origin::signed(ALICE), source_id));
+
+        assert_noop!(
+            Futarchy::clone_market(Origin::signed(BOB), source_id, TRADING_END + 40),
+            Error::<Test>::NotMarketCreator
+        );
+        let reserved = Balances::reserved_balance(ALICE);
+        assert_ok!(Futarchy::clone_market(Origin::signed(ALICE), source_id, TRADING_END + 40));
+
+        let clone_id = *Futarchy::markets_by_creator(ALICE).last().unwrap();
+        assert_ne!(clone_id, source_id);
+        let source = Futarchy::markets(source_id).unwrap();
+        let clone = Futarchy::markets(clone_id).unwrap();
+        assert_eq!(clone.market_type, source.market_type);
+        assert_eq!(clone.metadata, source.metadata);
+        assert_eq!(clone.scalar_bounds, source.scalar_bounds);
+        assert_eq!(clone.category, source.category);
+        assert_eq!(clone.oracle, source.oracle);
+        assert_eq!(clone.status, MarketStatus::Created);
+        assert_eq!(clone.trading_end, TRADING_END + 40);
+        assert_eq!(clone.cloned_from, Some(source_id));
+        assert_eq!(Balances::reserved_balance(ALICE), reserved + MarketCreationDeposit::get());
+        System::assert_last_event(Event::Futarchy(RawEvent::MarketCloned(ALICE, source_id, clone_id)));
+    });
+}
+
+// Create and activate a scalar market on 0 to 10 degrees reporting at
+// `scale`, where BOB holds 100 LONG and CHARLIE 100 SHORT shares
+fn create_scaled_scalar(scale: u8) -> H256 {
+    assert_ok!(Futarchy::create_market(
+        Origin::signed(ALICE),
+        MarketType::Scalar,
+        MarketMechanism::Lmsr,
+        TRADING_END,
+        LIQUIDITY,
+        0,
+        Some((0, 10)),
+        None,
+        Vec::new(),
+        None,
+        None,
+        MarketCategory::Other,
+        Vec::new(),
+        Vec::new()
+    ));
+    let market_id = *Futarchy::markets_by_creator(ALICE).last().unwrap();
+    assert_ok!(Futarchy::set_scalar_scale(Origin::signed(ALICE), market_id, scale));
+    assert_ok!(Futarchy::activate_market(Origin::signed(ALICE), market_id));
+    assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), market_id, 1, 100, None));
+    assert_ok!(Futarchy::buy_shares(Origin::signed(CHARLIE), market_id, 0, 100, None));
+    market_id
+}
+
+// What BOB's LONG and CHARLIE's SHORT shares of a market from
+// `create_scaled_scalar` pay once it resolves to `value`
+fn scaled_payouts(market_id: H256, value: i128) -> (Option<Balance>, Option<Balance>) {
+    System::set_block_number(TRADING_END);
+    assert_ok!(Futarchy::resolve_market(Origin::signed(ALICE), market_id, MarketOutcome::Scalar(value)));
+    System::set_block_number(TRADING_END + DisputePeriod::get());
+    (Futarchy::winnings_preview(&market_id, &BOB), Futarchy::winnings_preview(&market_id, &CHARLIE))
+}
+
+#[test]
+fn scalar_scale_interprets_values_as_decimals() {
+    new_test_ext().execute_with(|| {
+        // 3.33 degrees: LONG pays 3.33 of each set's 10, SHORT the other 6.67
+        let market_id = create_scaled_scalar(2);
+        System::assert_has_event(Event::Futarchy(RawEvent::ScalarScaleSet(market_id, 2)));
+        assert_eq!(scaled_payouts(market_id, 333), (Some(333), Some(667)));
+    });
+    new_test_ext().execute_with(|| {
+        // 2.5 degrees at one decimal place
+        let market_id = create_scaled_scalar(1);
+        assert_eq!(scaled_payouts(market_id, 25), (Some(250), Some(750)));
+    });
+    new_test_ext().execute_with(|| {
+        // Reports are clamped to the bounds at the market's scale: 10.01 is
+        // past the top of the range, while 333 whole degrees would be too
+        let market_id = create_scaled_scalar(2);
+        assert_eq!(scaled_payouts(market_id, 1_001), (Some(1_000), Some(0)));
+    });
+    new_test_ext().execute_with(|| {
+        let market_id = create_scaled_scalar(0);
+        assert_eq!(scaled_payouts(market_id, 333), (Some(1_000), Some(0)));
+    });
+}
+
+#[test]
+fn scalar_scale_must_fit_the_bounds() {
+    new_test_ext().execute_with(|| {
+        let edge = i128::MAX / 1_000;
+        assert_ok!(Futarchy::create_market(
+            Origin::signed(ALICE),
+            MarketType::Scalar,
+            MarketMechanism::Lmsr,
+            TRADING_END,
+            LIQUIDITY,
+            0,
+            Some((edge - 10, edge)),
+            None,
+            Vec::new(),
+            None,
+            None,
+            MarketCategory::Other,
+            Vec::new(),
+            Vec::new()
+        ));
+        let market_id = *Futarchy::markets_by_creator(ALICE).last().unwrap();
+
+        assert_noop!(
+            Futarchy::set_scalar_scale(Origin::signed(ALICE), market_id, MaxScalarScale::get() + 1),
+            Error::<Test>::ScaleTooLarge
+        );
+
+        // Three places fit the upper bound, four would overflow it
+        assert_ok!(Futarchy::set_scalar_scale(Origin::signed(ALICE), market_id, 3));
+        assert_noop!(
+            Futarchy::set_scalar_scale(Origin::signed(ALICE), market_id, 4),
+            Error::<Test>::ScaleTooLarge
+        );
+    });
+}
+
+#[test]
+fn scalar_scale_only_applies_to_scalar_markets() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_binary(ALICE);
+
+        assert_noop!(
+            Futarchy::set_scalar_scale(Origin::signed(ALICE), market_id, 2),
+            Error::<Test>::OutcomeTypeMismatch
+        );
+    });
+}
+
+#[test]
+fn subsidy_deepens_market() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+        assert_ok!(Balances::transfer(Origin::signed(ALICE), TREASURY, 10_000));
+        let treasury = Balances::free_balance(TREASURY);
+        let quote = buy_quote(market_id, 1, 100);
+
+        assert_noop!(
+            Futarchy::subsidize_market(Origin::signed(ALICE), market_id, 693),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        // 693 covers b * ln(2) for roughly another thousand of `b`
+        assert_ok!(Futarchy::subsidize_market(Origin::root(), market_id, 693));
+        System::assert_last_event(Event::Futarchy(RawEvent::MarketSubsidized(market_id, 693)));
+
+        let market = Futarchy::markets(market_id).unwrap();
+        assert_eq!(market.liquidity_parameter, LIQUIDITY + 999);
+        assert_eq!(Futarchy::subsidies(market_id), 693);
+        assert_eq!(Balances::free_balance(TREASURY), treasury - 693);
+        assert!(buy_quote(market_id, 1, 100) < quote);
+    });
+}
+
+#[test]
+fn subsidy_leftover_returns_to_treasury_on_resolution() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+        assert_ok!(Balances::transfer(Origin::signed(ALICE), TREASURY, 10_000));
+        let treasury = Balances::free_balance(TREASURY);
+        assert_ok!(Futarchy::subsidize_market(Origin::root(), market_id, 693));
+        assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), market_id, 1, 100, None));
+
+        System::set_block_number(TRADING_END);
+        assert_ok!(Futarchy::resolve_market(Origin::signed(ALICE), market_id, MarketOutcome::Binary(true)));
+        System::set_block_number(TRADING_END + DisputePeriod::get());
+        assert_ok!(Futarchy::finalize_market(Origin::signed(ALICE), market_id));
+
+        // The pot covers the winners, so the whole subsidy comes back
+        assert_eq!(Balances::free_balance(TREASURY), treasury);
+        assert_eq!(Futarchy::subsidies(market_id), 0);
+        System::assert_has_event(Event::Futarchy(RawEvent::SubsidyReturned(market_id, 693)));
+
+        assert_ok!(Futarchy::claim_winnings(Origin::signed(BOB), market_id));
+        let lp = Futarchy::lp_shares(market_id, ALICE);
+        assert_ok!(Futarchy::remove_liquidity(Origin::signed(ALICE), market_id, lp));
+    });
+}
+
+#[test]
+fn live_lmsr_market_keeps_cost_function_locked() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+        assert_ok!(Futarchy::add_liquidity(Origin::signed(ALICE), market_id, 500));
+        assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), market_id, 1, 300, None));
+
+        // Withdrawing every LP share leaves C(q) behind: what BOB paid in
+        // plus the worst-case loss b * ln(2)
+        let cost = crate::lmsr::cost(&[0, 300], LIQUIDITY as u128).unwrap() as Balance;
+        let total = Futarchy::markets(market_id).unwrap().total_liquidity;
+        let fees = CollectedFees::<Test>::get(market_id);
+        let before = Balances::free_balance(ALICE);
+        let lp = Futarchy::lp_shares(market_id, ALICE);
+        assert_ok!(Futarchy::remove_liquidity(Origin::signed(ALICE), market_id, lp));
+        assert_eq!(Balances::free_balance(ALICE) - before, total - cost + fees);
+        assert_eq!(Futarchy::markets(market_id).unwrap().total_liquidity, cost);
+
+        // so BOB is still paid in full once YES wins
+        System::set_block_number(TRADING_END);
+        assert_ok!(Futarchy::resolve_market(Origin::signed(ALICE), market_id, MarketOutcome::Binary(true)));
+        System::set_block_number(TRADING_END + DisputePeriod::get());
+        assert_ok!(Futarchy::finalize_market(Origin::signed(ALICE), market_id));
+        let before = Balances::free_balance(BOB);
+        assert_ok!(Futarchy::claim_winnings(Origin::signed(BOB), market_id));
+        assert_eq!(Balances::free_balance(BOB) - before, 300);
+    });
+}
+
+#[test]
+fn lp_withdrawal_waits_out_delay_after_resolution() {
+    new_test_ext().execute_with(|| {
+        set_lp_withdrawal_delay(20);
+        let market_id = create_active_binary(ALICE);
+        assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), market_id, 1, 100, None));
+
+        System::set_block_number(TRADING_END);
+        assert_ok!(Futarchy::resolve_market(Origin::signed(ALICE), market_id, MarketOutcome::Binary(true)));
+        let settled = TRADING_END + DisputePeriod::get();
+        System::set_block_number(settled);
+        assert_ok!(Futarchy::finalize_market(Origin::signed(ALICE), market_id));
+        assert_eq!(Futarchy::settled_at(market_id), Some(settled));
+
+        // Winners get the delay to claim first
+        let lp = Futarchy::lp_shares(market_id, ALICE);
+        System::set_block_number(settled + 19);
+        assert_noop!(
+            Futarchy::remove_liquidity(Origin::signed(ALICE), market_id, lp),
+            Error::<Test>::LiquidityLocked
+        );
+
+        // Afterwards the LPs take the residual, leaving BOB's winnings behind
+        System::set_block_number(settled + 20);
+        let winnings = Futarchy::winnings_preview(&market_id, &BOB).unwrap();
+        let residual = Futarchy::markets(market_id).unwrap().total_liquidity - winnings;
+        let fees = CollectedFees::<Test>::get(market_id);
+        let before = Balances::free_balance(ALICE);
+        assert_ok!(Futarchy::remove_liquidity(Origin::signed(ALICE), market_id, lp));
+        assert_eq!(Balances::free_balance(ALICE) - before, residual + fees);
+
+        let before = Balances::free_balance(BOB);
+        assert_ok!(Futarchy::claim_winnings(Origin::signed(BOB), market_id));
+        assert_eq!(Balances::free_balance(BOB) - before, winnings);
+    });
+}
+
+#[test]
+fn last_lps_out_empty_a_resolved_pot() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+        assert_ok!(Futarchy::add_liquidity(Origin::signed(CHARLIE), market_id, 500));
+        assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), market_id, 1, 300, None));
+        assert_ok!(Futarchy::buy_shares(Origin::signed(DAVE), market_id, 0, 100, None));
+
+        System::set_block_number(TRADING_END);
+        assert_ok!(Futarchy::resolve_market(Origin::signed(ALICE), market_id, MarketOutcome::Binary(true)));
+        System::set_block_number(TRADING_END + DisputePeriod::get());
+        assert_ok!(Futarchy::finalize_market(Origin::signed(ALICE), market_id));
+        assert_ok!(Futarchy::claim_winnings(Origin::signed(BOB), market_id));
+        assert_ok!(Futarchy::settle_position(Origin::signed(DAVE), market_id));
+
+        // Between them the two providers take everything that is left
+        let pot = Futarchy::market_account(market_id);
+        let left = Balances::free_balance(pot);
+        assert_eq!(left, Futarchy::markets(market_id).unwrap().total_liquidity + CollectedFees::<Test>::get(market_id));
+        let (alice, charlie) = (Balances::free_balance(ALICE), Balances::free_balance(CHARLIE));
+        assert_ok!(Futarchy::remove_liquidity(Origin::signed(ALICE), market_id, Futarchy::lp_shares(market_id, ALICE)));
+        assert_ok!(Futarchy::remove_liquidity(Origin::signed(CHARLIE), market_id, Futarchy::lp_shares(market_id, CHARLIE)));
+        let paid = (Balances::free_balance(ALICE) - alice) + (Balances::free_balance(CHARLIE) - charlie);
+        assert_eq!(paid, left);
+
+        assert_eq!(Futarchy::markets(market_id).unwrap().total_liquidity, 0);
+        assert_eq!(CollectedFees::<Test>::get(market_id), 0);
+        assert_eq!(Futarchy::lp_issuance(market_id), 0);
+        assert_eq!(Balances::free_balance(pot), 0);
+    });
+}
+
+// Create a binary market through `create_market` from any origin
+fn create_binary_from(origin: Origin) -> frame_support::dispatch::DispatchResult {
+    Futarchy::create_market(
+        origin,
+        MarketType::Binary,
+        MarketMechanism::Lmsr,
+        TRADING_END,
+        LIQUIDITY,
+        0,
+        None,
+        None,
+        Vec::new(),
+        Some(BOB),
+        None,
+        MarketCategory::Other,
+        Vec::new(),
+        Vec::new()
+    )
+}
+
+#[test]
+fn permissionless_policy_lets_anyone_create() {
+    new_test_ext().execute_with(|| {
+        set_creation_policy(CreationPolicy::Permissionless);
+
+        assert_ok!(create_binary_from(Origin::signed(ALICE)));
+        assert_eq!(Futarchy::markets_by_creator(ALICE).len(), 1);
+    });
+}
+
+#[test]
+fn whitelist_policy_requires_approval() {
+    new_test_ext().execute_with(|| {
+        set_creation_policy(CreationPolicy::WhitelistOnly);
+        assert_noop!(create_binary_from(Origin::signed(ALICE)), Error::<Test>::NotAuthorizedCreator);
+
+        assert_noop!(
+            Futarchy::add_approved_creator(Origin::signed(ALICE), ALICE),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_ok!(Futarchy::add_approved_creator(Origin::root(), ALICE));
+        System::assert_last_event(Event::Futarchy(RawEvent::CreatorApproved(ALICE)));
+        assert_ok!(create_binary_from(Origin::signed(ALICE)));
+
+        // Removal stops new markets but leaves existing ones alone
+        assert_ok!(Futarchy::remove_approved_creator(Origin::root(), ALICE));
+        System::set_block_number(2);
+        assert_noop!(create_binary_from(Origin::signed(ALICE)), Error::<Test>::NotAuthorizedCreator);
+        assert_eq!(Futarchy::markets_by_creator(ALICE).len(), 1);
+    });
+}
+
+#[test]
+fn root_only_policy_creates_for_treasury() {
+    new_test_ext().execute_with(|| {
+        set_creation_policy(CreationPolicy::RootOnly);
+        assert_ok!(Balances::transfer(Origin::signed(ALICE), TREASURY, 10_000));
+
+        assert_noop!(create_binary_from(Origin::signed(ALICE)), Error::<Test>::NotAuthorizedCreator);
+        assert_ok!(create_binary_from(Origin::root()));
+        let market_id = *Futarchy::markets_by_creator(TREASURY).last().unwrap();
+        assert_eq!(Futarchy::markets(market_id).unwrap().creator, TREASURY);
+    });
+}
+
+// Create and open a shallow binary market from ALICE seeded with `seed`
+fn create_and_activate_shallow(seed: Balance) -> frame_support::dispatch::DispatchResult {
+    Futarchy::create_and_activate_market(
+        Origin::signed(ALICE),
+        MarketType::Binary,
+        MarketMechanism::Lmsr,
+        TRADING_END,
+        100,
+        seed,
+        None,
+        None,
+        Vec::new(),
+        None,
+        None,
+        MarketCategory::Other,
+        Vec::new(),
+        Vec::new()
+    )
+}
+
+#[test]
+fn create_and_activate_market_opens_in_one_call() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_and_activate_shallow(MinActivationLiquidity::get()));
+
+        let market_id = *Futarchy::markets_by_creator(ALICE).last().unwrap();
+        let market = Futarchy::markets(market_id).unwrap();
+        assert_eq!(market.status, MarketStatus::Active);
+        assert!(market.total_liquidity >= MinActivationLiquidity::get());
+        assert!(crate::ActiveMarkets::<Test>::contains_key(market_id));
+        System::assert_has_event(Event::Futarchy(RawEvent::MarketCreated(ALICE, market_id, MarketType::Binary, TRADING_END, Vec::new())));
+        System::assert_last_event(Event::Futarchy(RawEvent::MarketActivated(ALICE, market_id)));
+    });
+}
+
+#[test]
+fn create_and_activate_market_rolls_back_shallow_market() {
+    new_test_ext().execute_with(|| {
+        // The subsidy of b = 100 plus this seed falls short of the minimum
+        assert_noop!(create_and_activate_shallow(100), Error::<Test>::InsufficientLiquidity);
+        assert_eq!(Futarchy::market_count(), 0);
+        assert!(Futarchy::markets_by_creator(ALICE).is_empty());
+        assert_eq!(Balances::free_balance(ALICE), INITIAL_BALANCE);
+    });
+}
+
+#[test]
+fn genesis_markets_are_created_and_queryable() {
+    let markets = vec![
+        (ALICE, MarketType::Binary, MarketMechanism::Lmsr, TRADING_END, LIQUIDITY, None, None),
+        (BOB, MarketType::Categorical, MarketMechanism::ConstantProduct, TRADING_END, LIQUIDITY, None, Some(3)),
+    ];
+    new_test_ext_with_markets(markets).execute_with(|| {
+        assert_eq!(Futarchy::market_count(), 2);
+
+        let alice_market = Futarchy::genesis_market_id(0, &ALICE);
+        let bob_market = Futarchy::genesis_market_id(1, &BOB);
+        let market = Futarchy::markets(alice_market).unwrap();
+        assert_eq!(market.creator, ALICE);
+        assert_eq!(market.market_type, MarketType::Binary);
+        assert_eq!(market.status, MarketStatus::Created);
+        assert_eq!(Futarchy::markets(bob_market).unwrap().outcome_count, 3);
+        assert_eq!(Futarchy::markets_by_creator(BOB).into_inner(), vec![bob_market]);
+
+        // Deposits are reserved as for a regular creation
+        assert_eq!(Balances::reserved_balance(ALICE), MarketCreationDeposit::get());
+        assert_eq!(Balances::reserved_balance(BOB), MarketCreationDeposit::get());
+    });
+}
+
+#[test]
+fn invalid_market_refunds_every_trader() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+        assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), market_id, 1, 100, None));
+        assert_ok!(Futarchy::buy_shares(Origin::signed(CHARLIE), market_id, 0, 300, None));
+        let bob_basis = Futarchy::cost_basis(market_id, BOB);
+        let charlie_basis = Futarchy::cost_basis(market_id, CHARLIE);
+
+        run_to_block(TRADING_END);
+        assert_ok!(Futarchy::resolve_market(Origin::signed(ALICE), market_id, MarketOutcome::Invalid));
+        run_to_block(TRADING_END + DisputePeriod::get() + 1);
+
+        assert_eq!(Futarchy::winnings_preview(&market_id, &BOB), Some(bob_basis));
+        assert_ok!(Futarchy::claim_winnings(Origin::signed(BOB), market_id));
+        assert_ok!(Futarchy::claim_winnings(Origin::signed(CHARLIE), market_id));
+        System::assert_last_event(Event::Futarchy(RawEvent::PositionRefunded(CHARLIE, market_id, charlie_basis)));
+
+        // Both get their stake back, only the trading fee is kept
+        let fee = |basis: Balance| TradingFee::get().mul_floor(basis);
+        assert_eq!(Balances::free_balance(BOB), INITIAL_BALANCE - fee(bob_basis));
+        assert_eq!(Balances::free_balance(CHARLIE), INITIAL_BALANCE - fee(charlie_basis));
+        assert!(Futarchy::positions(market_id, BOB).is_empty());
+        assert_eq!(Futarchy::total_cost_basis(market_id), 0);
+        assert_noop!(
+            Futarchy::claim_winnings(Origin::signed(BOB), market_id),
+            Error::<Test>::NothingToClaim
+        );
+    });
+}
+
+#[test]
+fn final_outcome_notifies_resolution_hook() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+        run_to_block(TRADING_END);
+        assert_ok!(Futarchy::resolve_market(Origin::signed(ALICE), market_id, MarketOutcome::Binary(true)));
+
+        // A report alone is not final
+        assert!(resolved_markets().is_empty());
+
+        run_to_block(TRADING_END + DisputePeriod::get() + 1);
+        assert_ok!(Futarchy::finalize_market(Origin::signed(BOB), market_id));
+        assert_eq!(resolved_markets(), vec![(market_id, MarketOutcome::Binary(true))]);
+    });
+}
+
+// Create a binary market from ALICE taking a 2% creator fee and open it
+fn create_binary_with_creator_fee() -> H256 {
+    let market_id = create_binary(ALICE);
+    assert_ok!(Futarchy::set_creator_fee(Origin::signed(ALICE), market_id, Permill::from_percent(2)));
+    assert_ok!(Futarchy::activate_market(Origin::signed(ALICE), market_id));
+    market_id
+}
+
+#[test]
+fn creator_fee_accrues_on_trades() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_binary_with_creator_fee();
+        assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), market_id, 1, 200, None));
+        let cost = Futarchy::cost_basis(market_id, BOB);
+        let bought = Permill::from_percent(2).mul_floor(cost);
+        assert_eq!(Futarchy::creator_fees(market_id), bought);
+
+        // Sellers pay it out of their proceeds
+        let before = Balances::free_balance(BOB);
+        assert_ok!(Futarchy::sell_shares(Origin::signed(BOB), market_id, 1, 100, None));
+        let sold = Futarchy::creator_fees(market_id) - bought;
+        assert!(sold > 0);
+        let received = Balances::free_balance(BOB) - before;
+        let proceeds = cost - Futarchy::cost_basis(market_id, BOB);
+        assert_eq!(received, proceeds - TradingFee::get().mul_floor(proceeds) - sold);
+    });
+}
+
+#[test]
+fn trades_add_exactly_the_trading_fee_to_collected_fees() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+
+        let market = Futarchy::markets(market_id).unwrap();
+        let (cost, _) = Futarchy::quote_buy(market_id, &market, 1, 300).unwrap();
+        assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), market_id, 1, 300, None));
+        let bought = TradingFee::get().mul_floor(cost);
+        assert!(bought > 0);
+        assert_eq!(Futarchy::collected_fees(market_id), bought);
+        System::assert_has_event(Event::Futarchy(RawEvent::SharesBought(BOB, market_id, 1, 300, cost, bought)));
+
+        let market = Futarchy::markets(market_id).unwrap();
+        let (proceeds, _) = Futarchy::quote_sell(market_id, &market, 1, 300).unwrap();
+        assert_ok!(Futarchy::sell_shares(Origin::signed(BOB), market_id, 1, 300, None));
+        let sold = TradingFee::get().mul_floor(proceeds);
+        assert_eq!(Futarchy::collected_fees(market_id), bought + sold);
+        System::assert_has_event(Event::Futarchy(RawEvent::SharesSold(BOB, market_id, 1, 300, proceeds, sold)));
+    });
+}
+
+#[test]
+fn protocol_fee_goes_to_treasury() {
+    new_test_ext().execute_with(|| {
+        set_protocol_fee(Permill::from_percent(5));
+        let market_id = create_active_binary(ALICE);
+        let treasury = Balances::free_balance(TREASURY);
+
+        assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), market_id, 1, 200, None));
+        let cost = Futarchy::cost_basis(market_id, BOB);
+        let cut = Permill::from_percent(5).mul_floor(cost);
+        assert!(cut > 0);
+        assert_eq!(Balances::free_balance(TREASURY), treasury + cut);
+        System::assert_has_event(Event::Futarchy(RawEvent::ProtocolFeeCollected(market_id, cut)));
+        // LPs only collect the trading fee
+        assert_eq!(Futarchy::collected_fees(market_id), TradingFee::get().mul_floor(cost));
+    });
+}
+
+#[test]
+fn creator_fee_is_capped() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_binary(ALICE);
+        assert_noop!(
+            Futarchy::set_creator_fee(Origin::signed(ALICE), market_id, Permill::from_percent(6)),
+            Error::<Test>::CreatorFeeTooHigh
+        );
+        assert_noop!(
+            Futarchy::set_creator_fee(Origin::signed(BOB), market_id, Permill::from_percent(1)),
+            Error::<Test>::NotMarketCreator
+        );
+        assert_ok!(Futarchy::set_creator_fee(Origin::signed(ALICE), market_id, MaxCreatorFee::get()));
+        System::assert_last_event(Event::Futarchy(RawEvent::CreatorFeeSet(market_id, MaxCreatorFee::get())));
+    });
+}
+
+#[test]
+fn creator_withdraws_fees_after_dispute_window() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_binary_with_creator_fee();
+        assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), market_id, 1, 200, None));
+        let fees = Futarchy::creator_fees(market_id);
+
+        run_to_block(TRADING_END);
+        assert_ok!(Futarchy::resolve_market(Origin::signed(ALICE), market_id, MarketOutcome::Binary(true)));
+        assert_noop!(
+            Futarchy::claim_creator_fees(Origin::signed(ALICE), market_id),
+            Error::<Test>::DisputeWindowOpen
+        );
+
+        run_to_block(TRADING_END + DisputePeriod::get() + 1);
+        assert_noop!(
+            Futarchy::claim_creator_fees(Origin::signed(BOB), market_id),
+            Error::<Test>::NotMarketCreator
+        );
+        let before = Balances::free_balance(ALICE);
+        assert_ok!(Futarchy::claim_creator_fees(Origin::signed(ALICE), market_id));
+        System::assert_last_event(Event::Futarchy(RawEvent::CreatorFeesClaimed(ALICE, market_id, fees)));
+        assert_eq!(Futarchy::markets(market_id).unwrap().status, MarketStatus::Resolved);
+        assert_eq!(Futarchy::creator_fees(market_id), 0);
+        // The claim also released the creation deposit and resolution bond
+        assert_eq!(
+            Balances::free_balance(ALICE),
+            before + fees + MarketCreationDeposit::get() + ResolutionBond::get()
+        );
+        assert_noop!(
+            Futarchy::claim_creator_fees(Origin::signed(ALICE), market_id),
+            Error::<Test>::NothingToClaim
+        );
+    });
+}
+
+#[test]
+fn same_type_markets_in_one_block_get_distinct_ids() {
+    new_test_ext().execute_with(|| {
+        let first = create_binary(ALICE);
+        let second = create_binary(ALICE);
+        assert_ne!(first, second);
+        assert_eq!(Futarchy::market_count(), 2);
+        assert!(Futarchy::markets(first).is_some());
+        assert!(Futarchy::markets(second).is_some());
+        assert_eq!(Futarchy::creation_nonce(ALICE), 2);
+    });
+}
+
+#[test]
+fn market_stats_follow_status_transitions() {
+    new_test_ext().execute_with(|| {
+        let count = |status: MarketStatus| Futarchy::status_count(status);
+        let market_id = create_binary(ALICE);
+        let idle = create_binary(BOB);
+        assert_eq!(count(MarketStatus::Created), 2);
+
+        assert_ok!(Futarchy::activate_market(Origin::signed(ALICE), market_id));
+        assert_eq!(count(MarketStatus::Created), 1);
+        assert_eq!(count(MarketStatus::Active), 1);
+
+        assert_ok!(Futarchy::buy_shares(Origin::signed(CHARLIE), market_id, 1, 100, None));
+        run_to_block(TRADING_END);
+        assert_ok!(Futarchy::resolve_market(Origin::signed(ALICE), market_id, MarketOutcome::Binary(true)));
+        assert_eq!(count(MarketStatus::Active), 0);
+        assert_eq!(count(MarketStatus::Proposed), 1);
+
+        run_to_block(TRADING_END + DisputePeriod::get() + 1);
+        assert_ok!(Futarchy::finalize_market(Origin::signed(BOB), market_id));
+        assert_eq!(count(MarketStatus::Proposed), 0);
+        assert_eq!(count(MarketStatus::Resolved), 1);
+
+        // Liquidity is summed over every market still stored
+        let stats = Futarchy::market_stats();
+        let liquidity = Futarchy::markets(market_id).unwrap().total_liquidity
+            + Futarchy::markets(idle).unwrap().total_liquidity;
+        assert_eq!(stats.total_liquidity, liquidity);
+        assert!(stats.status_counts.contains(&(MarketStatus::Resolved, 1)));
+        assert!(stats.status_counts.contains(&(MarketStatus::Created, 1)));
+        assert!(stats.status_counts.contains(&(MarketStatus::Cancelled, 0)));
+    });
+}
+
+// Open a committee market where BOB holds 100 YES shares, then split the
+// committee's vote evenly between YES and NO
+fn resolve_with_tied_committee() -> H256 {
+    let market_id = create_binary(ALICE);
+    let oracles = vec![(BOB, 1), (CHARLIE, 1), (DAVE, 1)];
+    assert_ok!(Futarchy::set_oracle_committee(Origin::signed(ALICE), market_id, oracles.try_into().unwrap(), 2));
+    assert_ok!(Futarchy::activate_market(Origin::signed(ALICE), market_id));
+    assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), market_id, 1, 100, None));
+    System::set_block_number(TRADING_END);
+    assert_ok!(Futarchy::submit_resolution(Origin::signed(BOB), market_id, MarketOutcome::Binary(true)));
+    assert_ok!(Futarchy::submit_resolution(Origin::signed(CHARLIE), market_id, MarketOutcome::Binary(false)));
+    assert_eq!(Futarchy::markets(market_id).unwrap().status, MarketStatus::Resolved);
+    market_id
+}
+
+#[test]
+fn tied_committee_picks_lowest_outcome_by_default() {
+    new_test_ext().execute_with(|| {
+        let market_id = resolve_with_tied_committee();
+        let market = Futarchy::markets(market_id).unwrap();
+        assert_eq!(market.resolved_outcome, Some(MarketOutcome::Binary(false)));
+        assert_noop!(
+            Futarchy::claim_winnings(Origin::signed(BOB), market_id),
+            Error::<Test>::NothingToClaim
+        );
+    });
+}
+
+#[test]
+fn tied_committee_can_refund_traders() {
+    new_test_ext().execute_with(|| {
+        set_tie_break(TieBreak::Refund);
+        let market_id = resolve_with_tied_committee();
+        assert_eq!(Futarchy::markets(market_id).unwrap().resolved_outcome, Some(MarketOutcome::Invalid));
+
+        let basis = Futarchy::cost_basis(market_id, BOB);
+        assert_ok!(Futarchy::claim_winnings(Origin::signed(BOB), market_id));
+        System::assert_last_event(Event::Futarchy(RawEvent::PositionRefunded(BOB, market_id, basis)));
+    });
+}
+
+#[test]
+fn tied_committee_can_split_payout() {
+    new_test_ext().execute_with(|| {
+        set_tie_break(TieBreak::SplitEqually);
+        let market_id = resolve_with_tied_committee();
+        assert_eq!(Futarchy::markets(market_id).unwrap().resolved_outcome, Some(MarketOutcome::Tie(vec![0, 1])));
+
+        // Each YES share pays half a unit
+        assert_ok!(Futarchy::claim_winnings(Origin::signed(BOB), market_id));
+        System::assert_last_event(Event::Futarchy(RawEvent::WinningsClaimed(BOB, market_id, 50)));
+    });
+}
+
+#[test]
+fn oracles_cannot_report_a_tie() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_committee_binary([1, 1, 1], 2);
+        assert_noop!(
+            Futarchy::submit_resolution(Origin::signed(BOB), market_id, MarketOutcome::Tie(vec![0, 1])),
+            Error::<Test>::InvalidOutcome
+        );
+    });
+}
+
+#[test]
+fn offchain_worker_flags_stale_market() {
+    let mut ext = new_test_ext();
+    let (pool, state) = TestTransactionPoolExt::new();
+    ext.register_extension(TransactionPoolExt::new(pool));
+    ext.execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+
+        // Nothing is stale within the grace period
+        System::set_block_number(TRADING_END + AutoResolveGrace::get());
+        Futarchy::offchain_worker(System::block_number());
+        assert!(state.read().transactions.is_empty());
+
+        // Skip the block hook so the market is not expired on schedule
+        System::set_block_number(TRADING_END + AutoResolveGrace::get() + 1);
+        Futarchy::offchain_worker(System::block_number());
+        let tx = state.write().transactions.pop().unwrap();
+        assert!(state.read().transactions.is_empty());
+        let tx = frame_system::mocking::MockUncheckedExtrinsic::<Test>::decode(&mut &*tx).unwrap();
+        assert!(tx.signature.is_none());
+        let call = crate::Call::flag_stale_market(market_id);
+        assert_eq!(tx.function, Call::Futarchy(call.clone()));
+        assert!(<Futarchy as ValidateUnsigned>::validate_unsigned(TransactionSource::Local, &call).is_ok());
+
+        assert_ok!(Futarchy::flag_stale_market(Origin::none(), market_id));
+        System::assert_last_event(Event::Futarchy(RawEvent::MarketStale(market_id)));
+
+        // Flagged markets are neither accepted nor submitted again
+        assert!(<Futarchy as ValidateUnsigned>::validate_unsigned(TransactionSource::Local, &call).is_err());
+        Futarchy::offchain_worker(System::block_number());
+        assert!(state.read().transactions.is_empty());
+    });
+}
+
+#[test]
+fn offchain_worker_flags_closed_market_left_unreported() {
+    let mut ext = new_test_ext();
+    let (pool, state) = TestTransactionPoolExt::new();
+    ext.register_extension(TransactionPoolExt::new(pool));
+    ext.execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+        assert_ok!(Futarchy::close_market(Origin::signed(ALICE), market_id));
+        assert!(!crate::ActiveMarkets::<Test>::contains_key(market_id));
+
+        // Closing leaves the market awaiting a report, which the scan still sees
+        System::set_block_number(TRADING_END + AutoResolveGrace::get() + 1);
+        Futarchy::offchain_worker(System::block_number());
+        let tx = state.write().transactions.pop().unwrap();
+        let tx = frame_system::mocking::MockUncheckedExtrinsic::<Test>::decode(&mut &*tx).unwrap();
+        assert_eq!(tx.function, Call::Futarchy(crate::Call::flag_stale_market(market_id)));
+        assert_ok!(Futarchy::flag_stale_market(Origin::none(), market_id));
+        System::assert_last_event(Event::Futarchy(RawEvent::MarketStale(market_id)));
+    });
+}
+
+// Create and activate a three-outcome LMSR market from ALICE, who is its oracle
+fn create_active_three_way() -> H256 {
+    assert_ok!(Futarchy::create_market(
+        Origin::signed(ALICE),
+        MarketType::Categorical,
+        MarketMechanism::Lmsr,
+        TRADING_END,
+        LIQUIDITY,
+        0,
+        None,
+        Some(3),
+        Vec::new(),
+        None,
+        None,
+        MarketCategory::Other,
+        Vec::new(),
+        Vec::new()
+    ));
+    let market_id = *Futarchy::markets_by_creator(ALICE).last().unwrap();
+    assert_ok!(Futarchy::activate_market(Origin::signed(ALICE), market_id));
+    market_id
+}
+
+#[test]
+fn eliminated_outcome_is_priced_at_zero_and_frozen() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_three_way();
+        assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), market_id, 0, 100, None));
+        assert_noop!(
+            Futarchy::eliminate_outcome(Origin::signed(BOB), market_id, 0),
+            Error::<Test>::NotAuthorizedOracle
+        );
+
+        assert_ok!(Futarchy::eliminate_outcome(Origin::signed(ALICE), market_id, 0));
+        System::assert_last_event(Event::Futarchy(RawEvent::OutcomeEliminated(market_id, 0)));
+
+        // The survivors share the whole probability mass
+        let prices = Futarchy::outcome_prices(market_id).unwrap();
+        assert_eq!(prices[0], FixedU128::zero());
+        assert_eq!(prices[1], prices[2]);
+        let total = prices[1] + prices[2];
+        assert!(FixedU128::one() - total < FixedU128::from_inner(1_000));
+
+        assert_noop!(
+            Futarchy::buy_shares(Origin::signed(CHARLIE), market_id, 0, 10, None),
+            Error::<Test>::OutcomeEliminated
+        );
+        assert_noop!(
+            Futarchy::sell_shares(Origin::signed(BOB), market_id, 0, 10, None),
+            Error::<Test>::OutcomeEliminated
+        );
+        assert_noop!(
+            Futarchy::eliminate_outcome(Origin::signed(ALICE), market_id, 0),
+            Error::<Test>::OutcomeEliminated
+        );
+    });
+}
+
+#[test]
+fn final_resolution_chooses_among_survivors() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_three_way();
+        assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), market_id, 2, 100, None));
+        assert_ok!(Futarchy::eliminate_outcome(Origin::signed(ALICE), market_id, 0));
+        assert_ok!(Futarchy::eliminate_outcome(Origin::signed(ALICE), market_id, 1));
+
+        // The last survivor cannot be eliminated
+        assert_noop!(
+            Futarchy::eliminate_outcome(Origin::signed(ALICE), market_id, 2),
+            Error::<Test>::OutcomeEliminated
+        );
+
+        run_to_block(TRADING_END);
+        assert_noop!(
+            Futarchy::resolve_market(Origin::signed(ALICE), market_id, MarketOutcome::Categorical(1)),
+            Error::<Test>::OutcomeEliminated
+        );
+        assert_ok!(Futarchy::resolve_market(Origin::signed(ALICE), market_id, MarketOutcome::Categorical(2)));
+        run_to_block(TRADING_END + DisputePeriod::get() + 1);
+        assert_ok!(Futarchy::claim_winnings(Origin::signed(BOB), market_id));
+        System::assert_last_event(Event::Futarchy(RawEvent::WinningsClaimed(BOB, market_id, 100)));
+    });
+}
+
+#[test]
+fn collateral_cap_rejects_deposits_once_reached() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_binary(ALICE);
+        let funded = Futarchy::markets(market_id).unwrap().total_liquidity;
+        let cap = funded + 1_000;
+        assert_noop!(
+            Futarchy::set_collateral_cap(Origin::signed(BOB), market_id, Some(cap)),
+            Error::<Test>::NotMarketCreator
+        );
+        assert_ok!(Futarchy::set_collateral_cap(Origin::signed(ALICE), market_id, Some(cap)));
+        System::assert_last_event(Event::Futarchy(RawEvent::CollateralCapSet(market_id, Some(cap))));
+        assert_ok!(Futarchy::activate_market(Origin::signed(ALICE), market_id));
+
+        // Fill the pot up to the cap, then top it off exactly with liquidity
+        assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), market_id, 1, 300, None));
+        let room = cap - Futarchy::markets(market_id).unwrap().total_liquidity;
+        assert_noop!(
+            Futarchy::add_liquidity(Origin::signed(CHARLIE), market_id, room + 1),
+            Error::<Test>::CollateralCapReached
+        );
+        assert_ok!(Futarchy::add_liquidity(Origin::signed(CHARLIE), market_id, room));
+        assert_eq!(Futarchy::markets(market_id).unwrap().total_liquidity, cap);
+
+        assert_noop!(
+            Futarchy::buy_shares(Origin::signed(BOB), market_id, 1, 10, None),
+            Error::<Test>::CollateralCapReached
+        );
+        assert_noop!(
+            Futarchy::split_collateral(Origin::signed(BOB), market_id, 10),
+            Error::<Test>::CollateralCapReached
+        );
+
+        // Selling takes collateral out, so it is still allowed
+        assert_ok!(Futarchy::sell_shares(Origin::signed(BOB), market_id, 1, 100, None));
+        assert!(Futarchy::markets(market_id).unwrap().total_liquidity < cap);
+    });
+}
+
+#[test]
+fn share_cap_bounds_buys_and_mints() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_binary(ALICE);
+        assert_noop!(
+            Futarchy::set_share_cap(Origin::signed(BOB), market_id, Some(300)),
+            Error::<Test>::NotMarketCreator
+        );
+        assert_ok!(Futarchy::set_share_cap(Origin::signed(ALICE), market_id, Some(300)));
+        System::assert_last_event(Event::Futarchy(RawEvent::ShareCapSet(market_id, Some(300))));
+        assert_ok!(Futarchy::activate_market(Origin::signed(ALICE), market_id));
+        assert_noop!(
+            Futarchy::set_share_cap(Origin::signed(ALICE), market_id, None),
+            Error::<Test>::MarketNotActivatable
+        );
+
+        // Buys count against the outcome bought, whoever holds the shares
+        assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), market_id, 1, 250, None));
+        assert_noop!(
+            Futarchy::buy_shares(Origin::signed(CHARLIE), market_id, 1, 51, None),
+            Error::<Test>::ShareCapReached
+        );
+        assert_ok!(Futarchy::buy_shares(Origin::signed(CHARLIE), market_id, 1, 50, None));
+
+        // Complete sets count against the largest outcome
+        assert_noop!(
+            Futarchy::split_collateral(Origin::signed(DAVE), market_id, 2),
+            Error::<Test>::ShareCapReached
+        );
+
+        // Selling makes room again
+        assert_ok!(Futarchy::sell_shares(Origin::signed(BOB), market_id, 1, 100, None));
+        assert_noop!(
+            Futarchy::split_collateral(Origin::signed(DAVE), market_id, 101),
+            Error::<Test>::ShareCapReached
+        );
+        assert_ok!(Futarchy::split_collateral(Origin::signed(DAVE), market_id, 100));
+        assert_eq!(Futarchy::total_shares(market_id).balance_of(1), 300);
+    });
+}
+
+#[test]
+fn delegate_trades_from_owner_account() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+        assert_ok!(Futarchy::add_trading_delegate(Origin::signed(BOB), CHARLIE));
+        System::assert_last_event(Event::Futarchy(RawEvent::TradingDelegateAdded(BOB, CHARLIE)));
+        assert_noop!(
+            Futarchy::add_trading_delegate(Origin::signed(BOB), CHARLIE),
+            Error::<Test>::InvalidDelegate
+        );
+
+        let quote = buy_quote(market_id, 1, 50);
+        assert_ok!(Futarchy::buy_shares_for(Origin::signed(CHARLIE), BOB, market_id, 1, 50, Some(quote)));
+        // The owner pays and holds the shares, the delegate is untouched
+        assert_eq!(Balances::free_balance(BOB), INITIAL_BALANCE - quote);
+        assert_eq!(Balances::free_balance(CHARLIE), INITIAL_BALANCE);
+        assert_eq!(Futarchy::positions(market_id, BOB).balance_of(1), 50);
+        assert!(Futarchy::positions(market_id, CHARLIE).is_empty());
+
+        assert_ok!(Futarchy::sell_shares_for(Origin::signed(CHARLIE), BOB, market_id, 1, 50, None));
+        assert!(Futarchy::positions(market_id, BOB).is_empty());
+        assert_eq!(Balances::free_balance(CHARLIE), INITIAL_BALANCE);
+        assert!(Balances::free_balance(BOB) > INITIAL_BALANCE - quote);
+    });
+}
+
+#[test]
+fn unapproved_delegate_cannot_trade() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+        assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), market_id, 1, 50, None));
+
+        assert_noop!(
+            Futarchy::buy_shares_for(Origin::signed(CHARLIE), BOB, market_id, 1, 50, None),
+            Error::<Test>::NotTradingDelegate
+        );
+        assert_noop!(
+            Futarchy::sell_shares_for(Origin::signed(CHARLIE), BOB, market_id, 1, 50, None),
+            Error::<Test>::NotTradingDelegate
+        );
+        // Approval runs one way only
+        assert_ok!(Futarchy::add_trading_delegate(Origin::signed(CHARLIE), BOB));
+        assert_noop!(
+            Futarchy::sell_shares_for(Origin::signed(CHARLIE), BOB, market_id, 1, 50, None),
+            Error::<Test>::NotTradingDelegate
+        );
+        assert_noop!(
+            Futarchy::add_trading_delegate(Origin::signed(BOB), BOB),
+            Error::<Test>::InvalidDelegate
+        );
+    });
+}
+
+#[test]
+fn removed_delegate_loses_access() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+        assert_ok!(Futarchy::add_trading_delegate(Origin::signed(BOB), CHARLIE));
+        assert_ok!(Futarchy::remove_trading_delegate(Origin::signed(BOB), CHARLIE));
+        System::assert_last_event(Event::Futarchy(RawEvent::TradingDelegateRemoved(BOB, CHARLIE)));
+
+        assert_noop!(
+            Futarchy::buy_shares_for(Origin::signed(CHARLIE), BOB, market_id, 1, 50, None),
+            Error::<Test>::NotTradingDelegate
+        );
+        assert_noop!(
+            Futarchy::remove_trading_delegate(Origin::signed(BOB), CHARLIE),
+            Error::<Test>::NotTradingDelegate
+        );
+    });
+}
+
+#[test]
+fn dispute_bond_floors_at_minimum_for_small_market() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_reported_by_charlie();
+        let pot = Futarchy::markets(market_id).unwrap().total_liquidity;
+        assert!(DisputeBondPercent::get().mul_floor(pot) < MinDisputeBond::get());
+
+        assert_ok!(Futarchy::dispute_market(Origin::signed(DAVE), market_id));
+
+        assert_eq!(Balances::reserved_balance(DAVE), MinDisputeBond::get());
+        assert_eq!(Futarchy::markets(market_id).unwrap().dispute_bond, MinDisputeBond::get());
+    });
+}
+
+#[test]
+fn dispute_bond_scales_with_large_market() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_binary_with_oracle(ALICE, Some(CHARLIE)));
+        let market_id = *Futarchy::markets_by_creator(ALICE).last().unwrap();
+        assert_ok!(Futarchy::activate_market(Origin::signed(ALICE), market_id));
+        assert_ok!(Futarchy::add_liquidity(Origin::signed(BOB), market_id, 100_000));
+        System::set_block_number(TRADING_END);
+        assert_ok!(Futarchy::resolve_market(Origin::signed(CHARLIE), market_id, MarketOutcome::Binary(true)));
+
+        let pot = Futarchy::markets(market_id).unwrap().total_liquidity;
+        let bond = DisputeBondPercent::get().mul_floor(pot);
+        assert!(bond > MinDisputeBond::get());
+        assert_ok!(Futarchy::dispute_market(Origin::signed(DAVE), market_id));
+        assert_eq!(Balances::reserved_balance(DAVE), bond);
+        assert_eq!(Futarchy::markets(market_id).unwrap().dispute_bond, bond);
+
+        // A dispute that fails loses the full scaled bond
+        assert_ok!(Futarchy::resolve_dispute(Origin::root(), market_id, MarketOutcome::Binary(true)));
+        assert_eq!(Balances::reserved_balance(DAVE), 0);
+        assert_eq!(Balances::free_balance(DAVE), INITIAL_BALANCE - bond);
+        assert_eq!(Futarchy::markets(market_id).unwrap().dispute_bond, 0);
+    });
+}
+
+#[test]
+fn price_impact_matches_executed_trade() {
+    new_test_ext().execute_with(|| {
+        let lmsr = create_active_binary(ALICE);
+        let pool = create_pool_with_seed(ALICE, 10_000);
+        assert_ok!(Futarchy::activate_market(Origin::signed(ALICE), pool));
+
+        for market_id in [lmsr, pool] {
+            let (before, after) = Futarchy::price_impact(market_id, 1, 300).unwrap();
+            assert_eq!(before, Futarchy::outcome_prices(market_id).unwrap()[1]);
+            assert!(after > before);
+            // Querying leaves the market untouched
+            assert_eq!(Futarchy::outcome_prices(market_id).unwrap()[1], before);
+
+            assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), market_id, 1, 300, None));
+            assert_eq!(Futarchy::outcome_prices(market_id).unwrap()[1], after);
+        }
+        assert_eq!(Futarchy::price_impact(lmsr, 2, 300), None);
+    });
+}
+
+#[test]
+fn blocks_until_close_counts_down_to_trading_end() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+        let now = System::block_number();
+        assert_eq!(Futarchy::blocks_until_close(market_id), Some(TRADING_END - now));
+
+        System::set_block_number(TRADING_END - 1);
+        assert_eq!(Futarchy::blocks_until_close(market_id), Some(1));
+
+        System::set_block_number(TRADING_END);
+        assert_eq!(Futarchy::blocks_until_close(market_id), None);
+        assert_eq!(Futarchy::blocks_until_close(H256::repeat_byte(1)), None);
+    });
+}
+
+#[test]
+fn blocks_until_close_ends_when_market_closes() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+        assert!(Futarchy::blocks_until_close(market_id).is_some());
+
+        assert_ok!(Futarchy::close_market(Origin::signed(ALICE), market_id));
+        assert_eq!(Futarchy::blocks_until_close(market_id), None);
+    });
+}
+
+#[test]
+fn activation_blocked_at_active_market_cap() {
+    new_test_ext().execute_with(|| {
+        set_max_active_markets(2);
+        let first = create_active_binary(ALICE);
+        create_active_binary(ALICE);
+        let third = create_binary(ALICE);
+
+        assert_noop!(
+            Futarchy::activate_market(Origin::signed(ALICE), third),
+            Error::<Test>::TooManyActiveMarkets
+        );
+
+        // Resolving one market frees its slot
+        System::set_block_number(TRADING_END);
+        assert_ok!(Futarchy::resolve_market(Origin::signed(ALICE), first, MarketOutcome::Binary(true)));
+        assert_eq!(Futarchy::status_count(MarketStatus::Active), 1);
+        assert_ok!(Futarchy::activate_market(Origin::signed(ALICE), third));
+        assert_eq!(Futarchy::status_count(MarketStatus::Active), 2);
+    });
+}
+
+#[test]
+fn collateral_inflow_capped_per_block() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+        let other = create_active_binary(ALICE);
+
+        // Budget exactly the two trades below
+        let first = buy_quote(market_id, 1, 100);
+        assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), market_id, 1, 100, None));
+        set_max_collateral_inflow(first + buy_quote(market_id, 1, 100));
+        assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), market_id, 1, 100, None));
+
+        // The budget is shared by all markets
+        assert_noop!(
+            Futarchy::buy_shares(Origin::signed(CHARLIE), other, 1, 10, None),
+            Error::<Test>::InflowLimitReached
+        );
+        assert_noop!(
+            Futarchy::split_collateral(Origin::signed(CHARLIE), other, 10),
+            Error::<Test>::InflowLimitReached
+        );
+
+        // A new block reopens it
+        run_to_block(System::block_number() + 1);
+        assert_eq!(Futarchy::block_inflow(), 0);
+        assert_ok!(Futarchy::buy_shares(Origin::signed(CHARLIE), other, 1, 10, None));
+    });
+}
+
+#[test]
+fn creator_cooldown_rejects_quick_succession() {
+    new_test_ext().execute_with(|| {
+        set_creator_cooldown(5);
+        create_binary(ALICE);
+        System::set_block_number(System::block_number() + 4);
+
+        assert_noop!(
+            Futarchy::create_market(
+                Origin::signed(ALICE),
+                MarketType::Binary,
+                MarketMechanism::Lmsr,
+                TRADING_END,
+                LIQUIDITY,
+                0,
+                None,
+                None,
+                b"Will it rain tomorrow?".to_vec(),
+                None,
+                None,
+                MarketCategory::Other,
+                Vec::new(),
+                Vec::new()
+            ),
+            Error::<Test>::CreationCooldown
+        );
+        // Other accounts are not held back
+        create_binary(BOB);
+    });
+}
+
+#[test]
+fn creator_cooldown_lapses() {
+    new_test_ext().execute_with(|| {
+        set_creator_cooldown(5);
+        let first = create_binary(ALICE);
+        System::set_block_number(System::block_number() + 5);
+
+        let second = create_binary(ALICE);
+        assert_ne!(first, second);
+        assert_eq!(Futarchy::last_creation(ALICE), Some(System::block_number()));
+    });
+}
+
+#[test]
+fn creator_updates_metadata_before_activation() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_binary(ALICE);
+
+        assert_ok!(Futarchy::update_metadata(Origin::signed(ALICE), market_id, b"Will it snow tomorrow?".to_vec()));
+        assert_eq!(Futarchy::markets(market_id).unwrap().metadata.into_inner(), b"Will it snow tomorrow?".to_vec());
+        System::assert_last_event(Event::Futarchy(RawEvent::MetadataUpdated(market_id)));
+
+        assert_noop!(
+            Futarchy::update_metadata(Origin::signed(BOB), market_id, b"Hijacked".to_vec()),
+            Error::<Test>::NotMarketCreator
+        );
+        assert_noop!(
+            Futarchy::update_metadata(Origin::signed(ALICE), market_id, vec![0; MaxMetadataLen::get() as usize + 1]),
+            Error::<Test>::MetadataTooLong
+        );
+    });
+}
+
+#[test]
+fn metadata_locked_once_trading_opens() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+        assert_noop!(
+            Futarchy::update_metadata(Origin::signed(ALICE), market_id, b"Changed".to_vec()),
+            Error::<Test>::MarketLocked
+        );
+
+        System::set_block_number(TRADING_END);
+        assert_ok!(Futarchy::resolve_market(Origin::signed(ALICE), market_id, MarketOutcome::Binary(true)));
+        System::set_block_number(TRADING_END + DisputePeriod::get());
+        assert_ok!(Futarchy::finalize_market(Origin::signed(BOB), market_id));
+        assert_noop!(
+            Futarchy::update_metadata(Origin::signed(ALICE), market_id, b"Changed".to_vec()),
+            Error::<Test>::MarketLocked
+        );
+    });
+}
+
+// What 100 LONG shares of a 0 to 100 scalar market pay when it resolves to
+// `value` under `curve`
+fn scalar_long_payout(curve: ScalarCurve, value: i128) -> Balance {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Futarchy::create_market(
+            Origin::signed(ALICE),
+            MarketType::Scalar,
+            MarketMechanism::Lmsr,
+            TRADING_END,
+            LIQUIDITY,
+            0,
+            Some((0, 100)),
+            None,
+            Vec::new(),
+            None,
+            None,
+            MarketCategory::Other,
+            Vec::new(),
+            Vec::new()
+        ));
+        let market_id = *Futarchy::markets_by_creator(ALICE).last().unwrap();
+        assert_ok!(Futarchy::set_payout_curve(Origin::signed(ALICE), market_id, curve));
+        assert_ok!(Futarchy::activate_market(Origin::signed(ALICE), market_id));
+        assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), market_id, 1, 100, None));
+
+        System::set_block_number(TRADING_END);
+        assert_ok!(Futarchy::resolve_market(Origin::signed(ALICE), market_id, MarketOutcome::Scalar(value)));
+        System::set_block_number(TRADING_END + DisputePeriod::get());
+        Futarchy::winnings_preview(&market_id, &BOB).unwrap()
+    })
+}
+
+#[test]
+fn scalar_payout_curves_shape_long_payouts() {
+    // 30 of the way through the range of 100: linear pays 0.3 of it, clamped
+    // 2 * 0.3 - 0.5 and smoothstep 3 * 0.09 - 2 * 0.027
+    assert_eq!(scalar_long_payout(ScalarCurve::Linear, 30), 3_000);
+    assert_eq!(scalar_long_payout(ScalarCurve::Clamped, 30), 1_000);
+    assert_eq!(scalar_long_payout(ScalarCurve::Sigmoid, 30), 2_160);
+
+    for curve in [ScalarCurve::Linear, ScalarCurve::Clamped, ScalarCurve::Sigmoid] {
+        let payouts: Vec<Balance> = [0, 20, 40, 60, 80, 100]
+            .into_iter()
+            .map(|value| scalar_long_payout(curve, value))
+            .collect();
+        assert!(payouts.windows(2).all(|pair| pair[0] <= pair[1]), "{:?} not monotonic", curve);
+        assert_eq!((payouts[0], payouts[5]), (0, 10_000));
+    }
+}
+
+// What BOB's 1000 LONG and CHARLIE's 1000 SHORT shares of a complete set of
+// 1000 minted on a scalar market over -50..=150 claim when it reports
+// `value`, and the rounding dust the treasury takes
+fn scalar_set_payouts(curve: ScalarCurve, value: i128) -> (Balance, Balance, Balance) {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Futarchy::create_market(
+            Origin::signed(ALICE),
+            MarketType::Scalar,
+            MarketMechanism::Lmsr,
+            TRADING_END,
+            LIQUIDITY,
+            0,
+            Some((-50, 150)),
+            None,
+            Vec::new(),
+            None,
+            None,
+            MarketCategory::Other,
+            Vec::new(),
+            Vec::new()
+        ));
+        let market_id = *Futarchy::markets_by_creator(ALICE).last().unwrap();
+        assert_ok!(Futarchy::set_payout_curve(Origin::signed(ALICE), market_id, curve));
+        assert_ok!(Futarchy::activate_market(Origin::signed(ALICE), market_id));
+
+        // Minting the sets takes the width of the range for each
+        let bob = Balances::free_balance(BOB);
+        assert_ok!(Futarchy::split_collateral(Origin::signed(BOB), market_id, 1000));
+        assert_eq!(bob - Balances::free_balance(BOB), 1000 * 200);
+
+        // BOB keeps LONG and hands SHORT to CHARLIE
+        let short = scalar_outcome_index(false) as u32;
+        assert_ok!(Futarchy::transfer_shares(Origin::signed(BOB), market_id, CHARLIE, short, 1000));
+        assert_eq!(Futarchy::positions(market_id, BOB).long_balance(), 1000);
+        assert_eq!(Futarchy::positions(market_id, BOB).short_balance(), 0);
+        assert_eq!(Futarchy::positions(market_id, CHARLIE).short_balance(), 1000);
+
+        System::set_block_number(TRADING_END);
+        assert_ok!(Futarchy::resolve_market(Origin::signed(ALICE), market_id, MarketOutcome::Scalar(value)));
+        System::set_block_number(TRADING_END + DisputePeriod::get());
+        let treasury = Balances::free_balance(TREASURY);
+        let claimed = |who: AccountId| {
+            let before = Balances::free_balance(who);
+            // A side paying nothing has nothing to claim
+            let _ = Futarchy::claim_winnings(Origin::signed(who), market_id);
+            Balances::free_balance(who) - before
+        };
+        let (long, short) = (claimed(BOB), claimed(CHARLIE));
+        (long, short, Balances::free_balance(TREASURY) - treasury)
+    })
+}
+
+#[test]
+fn scalar_long_and_short_split_the_range() {
+    // LONG pays value - lower per share and SHORT upper - value, with
+    // reports outside the range clamped to its bounds
+    assert_eq!(scalar_set_payouts(ScalarCurve::Linear, -50), (0, 200_000, 0));
+    assert_eq!(scalar_set_payouts(ScalarCurve::Linear, 0), (50_000, 150_000, 0));
+    assert_eq!(scalar_set_payouts(ScalarCurve::Linear, 90), (140_000, 60_000, 0));
+    assert_eq!(scalar_set_payouts(ScalarCurve::Linear, 150), (200_000, 0, 0));
+    assert_eq!(scalar_set_payouts(ScalarCurve::Linear, 400), (200_000, 0, 0));
+
+    // Whatever the curve, the sets pay out exactly the 200 each was minted
+    // for, rounding dust included
+    for curve in [ScalarCurve::Linear, ScalarCurve::Clamped, ScalarCurve::Sigmoid] {
+        for value in [-80, -50, 10, 37, 50, 130, 150] {
+            let (long, short, dust) = scalar_set_payouts(curve, value);
+            assert_eq!(long + short + dust, 1000 * 200, "{:?} at {} does not conserve the sets", curve, value);
+        }
+    }
+}
+
+#[test]
+fn scalar_reports_outside_the_range_are_clamped() {
+    // Below the range LONG pays nothing and SHORT everything
+    assert_eq!(scalar_set_payouts(ScalarCurve::Linear, -51), (0, 200_000, 0));
+    assert_eq!(scalar_set_payouts(ScalarCurve::Linear, i128::MIN), (0, 200_000, 0));
+    // inside it the set is split linearly
+    assert_eq!(scalar_set_payouts(ScalarCurve::Linear, 50), (100_000, 100_000, 0));
+    assert_eq!(scalar_set_payouts(ScalarCurve::Linear, 149), (199_000, 1_000, 0));
+    // and above it LONG takes everything
+    assert_eq!(scalar_set_payouts(ScalarCurve::Linear, 151), (200_000, 0, 0));
+    assert_eq!(scalar_set_payouts(ScalarCurve::Linear, i128::MAX), (200_000, 0, 0));
+}
+
+#[test]
+fn payout_curve_only_applies_to_scalar_markets() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_binary(ALICE);
+
+        assert_noop!(
+            Futarchy::set_payout_curve(Origin::signed(ALICE), market_id, ScalarCurve::Sigmoid),
+            Error::<Test>::OutcomeTypeMismatch
+        );
+    });
+}
+
+// Reserved balance across every test account
+fn total_reserved() -> Balance {
+    [ALICE, BOB, CHARLIE, DAVE].iter().map(|who| Balances::reserved_balance(who)).sum()
+}
+
+#[test]
+fn reserved_deposits_match_live_markets() {
+    new_test_ext().execute_with(|| {
+        // A fixed-seed linear congruential generator drives a reproducible
+        // mix of lifecycle calls, most of which may legitimately fail
+        let mut state: u64 = 0x5eed;
+        let mut next = |bound: u64| {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (state >> 33) % bound
+        };
+        let accounts = [ALICE, BOB, CHARLIE];
+        let mut markets: Vec<(AccountId, H256)> = Vec::new();
+
+        for step in 0..200 {
+            let roll = next(8);
+            if roll == 0 || markets.is_empty() {
+                let who = accounts[next(3) as usize];
+                let _ = Futarchy::create_market(
+                    Origin::signed(who),
+                    MarketType::Binary,
+                    MarketMechanism::Lmsr,
+                    System::block_number() + TRADING_END,
+                    LIQUIDITY,
+                    0,
+                    None,
+                    None,
+                    Vec::new(),
+                    None,
+                    None,
+                    MarketCategory::Other,
+                    Vec::new(),
+                    Vec::new()
+                );
+                if let Some(market_id) = Futarchy::markets_by_creator(who).last() {
+                    if !markets.contains(&(who, *market_id)) {
+                        markets.push((who, *market_id));
+                    }
+                }
+            } else {
+                let (who, market_id) = markets[next(markets.len() as u64) as usize];
+                let _ = match roll {
+                    1 => Futarchy::activate_market(Origin::signed(who), market_id),
+                    2 => Futarchy::cancel_market(Origin::signed(who), market_id),
+                    3 => Futarchy::force_cancel_market(Origin::root(), market_id),
+                    4 => Futarchy::resolve_market(Origin::signed(who), market_id, MarketOutcome::Binary(next(2) == 0)),
+                    5 => Futarchy::dispute_market(Origin::signed(DAVE), market_id),
+                    6 => Futarchy::resolve_dispute(Origin::root(), market_id, MarketOutcome::Binary(next(2) == 0)),
+                    _ => Futarchy::finalize_market(Origin::signed(DAVE), market_id),
+                };
+            }
+            if step % 5 == 0 {
+                System::set_block_number(System::block_number() + 3);
+            }
+
+            assert_eq!(total_reserved(), Futarchy::total_reserved_for_markets(), "step {}", step);
+        }
+    });
+}
+
+// DAVE's balance change from buying 100 YES of a market conditioned on two
+// others resolving YES, when the second resolves `second`
+fn claim_on_two_conditions(second: bool) -> (Balance, Balance) {
+    new_test_ext().execute_with(|| {
+        let first_id = create_active_binary(ALICE);
+        let second_id = create_active_binary(ALICE);
+        let child = create_binary(ALICE);
+        let conditions = vec![(first_id, true), (second_id, true)];
+        assert_ok!(Futarchy::set_parent_conditions(Origin::signed(ALICE), child, conditions.try_into().unwrap()));
+        System::assert_last_event(Event::Futarchy(RawEvent::ParentConditionsSet(child)));
+        assert_ok!(Futarchy::activate_market(Origin::signed(ALICE), child));
+        assert_ok!(Futarchy::buy_shares(Origin::signed(DAVE), child, 1, 100, None));
+        let basis = Futarchy::cost_basis(child, DAVE);
+        let after_buy = Balances::free_balance(DAVE);
+
+        System::set_block_number(TRADING_END);
+        assert_ok!(Futarchy::resolve_market(Origin::signed(ALICE), child, MarketOutcome::Binary(true)));
+        assert_ok!(Futarchy::resolve_market(Origin::signed(ALICE), first_id, MarketOutcome::Binary(true)));
+        System::set_block_number(TRADING_END + DisputePeriod::get());
+        assert_ok!(Futarchy::finalize_market(Origin::signed(BOB), first_id));
+
+        // The second condition is still open
+        assert_noop!(Futarchy::claim_winnings(Origin::signed(DAVE), child), Error::<Test>::ConditionsPending);
+
+        assert_ok!(Futarchy::resolve_market(Origin::signed(ALICE), second_id, MarketOutcome::Binary(second)));
+        System::set_block_number(TRADING_END + 2 * DisputePeriod::get());
+        assert_ok!(Futarchy::finalize_market(Origin::signed(BOB), second_id));
+        assert_ok!(Futarchy::claim_winnings(Origin::signed(DAVE), child));
+        (Balances::free_balance(DAVE) - after_buy, basis)
+    })
+}
+
+#[test]
+fn combinatorial_market_pays_only_when_all_conditions_hold() {
+    // Both conditions held: the YES shares pay in full
+    let (paid, _) = claim_on_two_conditions(true);
+    assert_eq!(paid, 100);
+
+    // The second failed: DAVE only gets back what the shares cost
+    let (refunded, basis) = claim_on_two_conditions(false);
+    assert_eq!(refunded, basis);
+    assert!(refunded < 100);
+}
+
+#[test]
+fn parent_conditions_must_name_other_binary_markets() {
+    new_test_ext().execute_with(|| {
+        let child = create_binary(ALICE);
+        let missing = H256::repeat_byte(9);
+
+        assert_noop!(
+            Futarchy::set_parent_conditions(Origin::signed(ALICE), child, vec![(missing, true)].try_into().unwrap()),
+            Error::<Test>::MarketDoesNotExist
+        );
+        assert_noop!(
+            Futarchy::set_parent_conditions(Origin::signed(ALICE), child, vec![(child, true)].try_into().unwrap()),
+            Error::<Test>::InvalidCondition
+        );
+        let parent = create_binary(ALICE);
+        assert_noop!(
+            Futarchy::set_parent_conditions(
+                Origin::signed(ALICE),
+                child,
+                vec![(parent, true), (parent, false)].try_into().unwrap()
+            ),
+            Error::<Test>::InvalidCondition
+        );
+    });
+}
+
+// Active binary market reported on by CHARLIE, with BOB then DAVE as backups
+fn create_with_backup_oracles() -> H256 {
+    assert_ok!(create_binary_with_oracle(ALICE, Some(CHARLIE)));
+    let market_id = *Futarchy::markets_by_creator(ALICE).last().unwrap();
+    assert_ok!(Futarchy::set_backup_oracles(Origin::signed(ALICE), market_id, vec![BOB, DAVE].try_into().unwrap()));
+    System::assert_last_event(Event::Futarchy(RawEvent::BackupOraclesSet(market_id, vec![BOB, DAVE])));
+    assert_ok!(Futarchy::activate_market(Origin::signed(ALICE), market_id));
+    market_id
+}
+
+#[test]
+fn backup_oracle_takes_over_after_sla_window() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_with_backup_oracles();
+
+        // Within its window only the primary may report
+        System::set_block_number(TRADING_END + OracleSlaWindow::get() - 1);
+        assert_noop!(
+            Futarchy::resolve_market(Origin::signed(BOB), market_id, MarketOutcome::Binary(true)),
+            Error::<Test>::NotAuthorizedOracle
+        );
+
+        // Once it lapses the first backup is in charge and the primary is out
+        System::set_block_number(TRADING_END + OracleSlaWindow::get());
+        assert_noop!(
+            Futarchy::resolve_market(Origin::signed(CHARLIE), market_id, MarketOutcome::Binary(true)),
+            Error::<Test>::NotAuthorizedOracle
+        );
+        assert_ok!(Futarchy::resolve_market(Origin::signed(BOB), market_id, MarketOutcome::Binary(true)));
+        System::assert_has_event(Event::Futarchy(RawEvent::OracleRotated(market_id, BOB)));
+
+        let market = Futarchy::markets(market_id).unwrap();
+        assert_eq!(market.oracle, BOB);
+        assert_eq!(market.status, MarketStatus::Proposed);
+        assert_eq!(Balances::reserved_balance(BOB), ResolutionBond::get());
+    });
+}
+
+#[test]
+fn last_backup_oracle_keeps_reporting_duty() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_with_backup_oracles();
+
+        System::set_block_number(TRADING_END + 4 * OracleSlaWindow::get());
+        assert_noop!(
+            Futarchy::resolve_market(Origin::signed(BOB), market_id, MarketOutcome::Binary(true)),
+            Error::<Test>::NotAuthorizedOracle
+        );
+        assert_ok!(Futarchy::resolve_market(Origin::signed(DAVE), market_id, MarketOutcome::Binary(false)));
+        assert_eq!(Futarchy::markets(market_id).unwrap().oracle, DAVE);
+    });
+}
+
+#[test]
+fn redeem_all_claims_every_resolved_market() {
+    new_test_ext().execute_with(|| {
+        let markets: Vec<H256> = (0..3).map(|_| create_active_binary(ALICE)).collect();
+        for market_id in &markets {
+            assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), *market_id, 1, 100, None));
+        }
+        // Still trading, so it has nothing to pay out yet
+        let open = create_active_binary(ALICE);
+        assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), open, 1, 100, None));
+
+        System::set_block_number(TRADING_END);
+        for market_id in &markets {
+            assert_ok!(Futarchy::resolve_market(Origin::signed(ALICE), *market_id, MarketOutcome::Binary(true)));
+        }
+        System::set_block_number(TRADING_END + DisputePeriod::get());
+
+        let before = Balances::free_balance(BOB);
+        let batch = vec![markets[0], open, markets[1], markets[2]];
+        assert_ok!(Futarchy::redeem_all(Origin::signed(BOB), batch.try_into().unwrap()));
+
+        assert_eq!(Balances::free_balance(BOB), before + 300);
+        System::assert_last_event(Event::Futarchy(RawEvent::BatchRedeemed(BOB, 300)));
+        for market_id in &markets {
+            assert!(Futarchy::positions(market_id, BOB).is_empty());
+        }
+        assert_eq!(Futarchy::positions(open, BOB).balance_of(1), 100);
+
+        // A second sweep has nothing left
+        assert_noop!(
+            Futarchy::redeem_all(Origin::signed(BOB), markets.try_into().unwrap()),
+            Error::<Test>::NothingToClaim
+        );
+    });
+}
+
+#[test]
+fn redeem_all_of_losing_markets_changes_nothing() {
+    new_test_ext().execute_with(|| {
+        let markets: Vec<H256> = (0..2).map(|_| create_active_binary(ALICE)).collect();
+        for market_id in &markets {
+            assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), *market_id, 0, 100, None));
+        }
+        System::set_block_number(TRADING_END);
+        for market_id in &markets {
+            assert_ok!(Futarchy::resolve_market(Origin::signed(ALICE), *market_id, MarketOutcome::Binary(true)));
+        }
+        System::set_block_number(TRADING_END + DisputePeriod::get());
+
+        // Neither market is finalized nor BOB's shares touched
+        assert_noop!(
+            Futarchy::redeem_all(Origin::signed(BOB), markets.clone().try_into().unwrap()),
+            Error::<Test>::NothingToClaim
+        );
+        for market_id in &markets {
+            assert_eq!(Futarchy::markets(market_id).unwrap().status, MarketStatus::Proposed);
+            assert_eq!(Futarchy::positions(market_id, BOB).balance_of(0), 100);
+        }
+    });
+}
+
+// Status transitions reported for a market, in order
+fn status_changes(market_id: H256) -> Vec<(MarketStatus, MarketStatus)> {
+    System::events()
+        .into_iter()
+        .filter_map(|record| match record.event {
+            Event::Futarchy(RawEvent::MarketStatusChanged(id, old, new)) if id == market_id => Some((old, new)),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn every_status_transition_is_reported() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_binary(ALICE);
+        // Creation is not a transition
+        assert!(status_changes(market_id).is_empty());
+
+        assert_ok!(Futarchy::activate_market(Origin::signed(ALICE), market_id));
+        assert_eq!(status_changes(market_id).last(), Some(&(MarketStatus::Created, MarketStatus::Active)));
+        assert_ok!(Futarchy::close_market(Origin::signed(ALICE), market_id));
+        assert_eq!(status_changes(market_id).last(), Some(&(MarketStatus::Active, MarketStatus::Closed)));
+        assert_ok!(Futarchy::resolve_market(Origin::signed(ALICE), market_id, MarketOutcome::Binary(true)));
+        assert_eq!(status_changes(market_id).last(), Some(&(MarketStatus::Closed, MarketStatus::Proposed)));
+        assert_ok!(Futarchy::dispute_market(Origin::signed(DAVE), market_id));
+        assert_eq!(status_changes(market_id).last(), Some(&(MarketStatus::Proposed, MarketStatus::Disputed)));
+        assert_ok!(Futarchy::resolve_dispute(Origin::root(), market_id, MarketOutcome::Binary(false)));
+        System::assert_has_event(Event::Futarchy(RawEvent::MarketStatusChanged(
+            market_id,
+            MarketStatus::Disputed,
+            MarketStatus::Resolved
+        )));
+        assert_eq!(status_changes(market_id).len(), 5);
+
+        // A cancellation is reported too
+        let cancelled = create_binary(ALICE);
+        assert_ok!(Futarchy::cancel_market(Origin::signed(ALICE), cancelled));
+        assert_eq!(status_changes(cancelled), vec![(MarketStatus::Created, MarketStatus::Cancelled)]);
+    });
+}
+
+#[test]
+fn finalizing_an_undisputed_report_is_reported() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+        System::set_block_number(TRADING_END);
+        assert_ok!(Futarchy::resolve_market(Origin::signed(ALICE), market_id, MarketOutcome::Binary(true)));
+        System::set_block_number(TRADING_END + DisputePeriod::get());
+
+        assert_ok!(Futarchy::finalize_market(Origin::signed(BOB), market_id));
+        assert_eq!(
+            status_changes(market_id),
+            vec![
+                (MarketStatus::Created, MarketStatus::Active),
+                (MarketStatus::Active, MarketStatus::Proposed),
+                (MarketStatus::Proposed, MarketStatus::Resolved),
+            ]
+        );
+    });
+}
+
+#[test]
+fn activation_waits_for_min_created_period() {
+    new_test_ext().execute_with(|| {
+        set_min_created_period(5);
+        let market_id = create_binary(ALICE);
+        let created = Futarchy::markets(market_id).unwrap().creation_block;
+
+        System::set_block_number(created + 4);
+        assert_noop!(
+            Futarchy::activate_market(Origin::signed(ALICE), market_id),
+            Error::<Test>::ActivationTooEarly
+        );
+
+        System::set_block_number(created + 5);
+        assert_ok!(Futarchy::activate_market(Origin::signed(ALICE), market_id));
+        assert_eq!(Futarchy::markets(market_id).unwrap().status, MarketStatus::Active);
+    });
+}
+
+#[test]
+fn activation_open_after_min_created_period() {
+    new_test_ext().execute_with(|| {
+        set_min_created_period(5);
+        let market_id = create_binary(ALICE);
+        let created = Futarchy::markets(market_id).unwrap().creation_block;
+
+        System::set_block_number(created + 6);
+        assert_ok!(Futarchy::activate_market(Origin::signed(ALICE), market_id));
+    });
+}
+
+// Small xorshift generator, so randomized tests replay the same sequences
+struct Xorshift(u64);
+
+impl Xorshift {
+    fn below(&mut self, n: u64) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0 % n
+    }
+}
+
+#[test]
+fn random_trading_keeps_markets_solvent() {
+    for seed in 1..=12u64 {
+        new_test_ext().execute_with(|| {
+            let mut rng = Xorshift(seed);
+            let market_id = match seed % 3 {
+                0 => create_active_binary(ALICE),
+                1 => create_active_three_way(),
+                _ => {
+                    let market_id = create_pool_with_seed(ALICE, 1_000);
+                    assert_ok!(Futarchy::activate_market(Origin::signed(ALICE), market_id));
+                    market_id
+                }
+            };
+            let outcomes = Futarchy::markets(market_id).unwrap().outcome_count;
+
+            for _ in 0..60 {
+                let who = Origin::signed([BOB, CHARLIE, DAVE][rng.below(3) as usize]);
+                let outcome = rng.below(outcomes as u64) as u32;
+                let amount = 2 + rng.below(50) as Balance;
+                // Trades the pallet rejects are part of the sequence too
+                let _ = match rng.below(4) {
+                    0 => Futarchy::buy_shares(who, market_id, outcome, amount, None),
+                    1 => Futarchy::sell_shares(who, market_id, outcome, amount, None),
+                    2 => Futarchy::split_collateral(who, market_id, amount),
+                    _ => Futarchy::merge_shares(who, market_id, amount),
+                };
+
+                Futarchy::debug_assert_solvent(market_id);
+                let totals = Futarchy::total_shares(market_id);
+                let largest = (0..outcomes as usize).map(|i| totals.balance_of(i)).max().unwrap();
+                let held = Balances::free_balance(Futarchy::market_account(market_id));
+                assert!(held >= largest);
+                assert_eq!(
+                    held,
+                    Futarchy::markets(market_id).unwrap().total_liquidity
+                        + CollectedFees::<Test>::get(market_id)
+                        + crate::CreatorFees::<Test>::get(market_id)
+                        + ReapIncentive::get()
+                );
+            }
+        });
+    }
+}
+
+#[test]
+fn market_events_are_indexed_by_market() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+        let created = System::events()
+            .into_iter()
+            .find(|record| matches!(record.event, Event::Futarchy(RawEvent::MarketCreated(..))))
+            .unwrap();
+        assert_eq!(created.topics, vec![market_id]);
+
+        assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), market_id, 1, 100, None));
+        let bought = System::events().pop().unwrap();
+        assert!(matches!(bought.event, Event::Futarchy(RawEvent::SharesBought(..))));
+        assert_eq!(bought.topics, vec![market_id]);
+
+        System::set_block_number(TRADING_END);
+        assert_ok!(Futarchy::resolve_market(Origin::signed(ALICE), market_id, MarketOutcome::Binary(true)));
+        let resolved = System::events().pop().unwrap();
+        assert!(matches!(resolved.event, Event::Futarchy(RawEvent::MarketResolved(..))));
+        assert_eq!(resolved.topics, vec![market_id]);
+
+        // Other events stay untagged
+        assert!(System::events()
+            .iter()
+            .filter(|record| matches!(record.event, Event::Futarchy(RawEvent::MarketActivated(..))))
+            .all(|record| record.topics.is_empty()));
+    });
+}
+
+#[test]
+fn close_and_resolve_reports_without_a_trading_gap() {
+    new_test_ext().execute_with(|| {
+        let market_id = create_active_binary(ALICE);
+        assert_ok!(Futarchy::buy_shares(Origin::signed(BOB), market_id, 1, 100, None));
+        let report = |who, outcome| Futarchy::close_and_resolve(Origin::signed(who), market_id, outcome);
+
+        assert_noop!(report(ALICE, MarketOutcome::Binary(true)), Error::<Test>::TradingStillOpen);
+        System::set_block_number(TRADING_END);
+        assert_noop!(report(BOB, MarketOutcome::Binary(true)), Error::<Test>::NotAuthorizedOracle);
+
+        // A rejected report rolls the close back too
+        assert_noop!(report(ALICE, MarketOutcome::Categorical(0)), Error::<Test>::OutcomeTypeMismatch);
+        assert_eq!(Futarchy::markets(market_id).unwrap().status, MarketStatus::Active);
+
+        assert_ok!(report(ALICE, MarketOutcome::Binary(true)));
+        assert_eq!(Futarchy::markets(market_id).unwrap().status, MarketStatus::Proposed);
+
+        // The close and the report land back to back, with no trade between
+        let events: Vec<_> = System::events().into_iter().map(|record| record.event).collect();
+        let closed = events
+            .iter()
+            .position(|event| *event == Event::Futarchy(RawEvent::MarketClosed(ALICE, market_id)))
+            .unwrap();
+        assert!(events[closed..]
+            .iter()
+            .all(|event| !matches!(event, Event::Futarchy(RawEvent::SharesBought(..) | RawEvent::SharesSold(..)))));
+        let changes = status_changes(market_id);
+        assert_eq!(
+            changes[changes.len() - 2..].to_vec(),
+            vec![
+                (MarketStatus::Active, MarketStatus::Closed),
+                (MarketStatus::Closed, MarketStatus::Proposed)
+            ]
+        );
+        assert_noop!(
+            Futarchy::buy_shares(Origin::signed(BOB), market_id, 1, 100, None),
+            Error::<Test>::MarketNotActive
+        );
+    });
+}
@@ -0,0 +1,734 @@
+// Weights for pallet_futarchy
+//
+// Placeholder estimates derived from the storage accesses of each extrinsic.
+// Regenerate on reference hardware with:
+//
+//     ./target/release/node benchmark \
+//         --chain=dev \
+//         --steps=50 \
+//         --repeat=20 \
+//         --pallet=pallet_futarchy \
+//         --extrinsic='*' \
+//         --execution=wasm \
+//         --wasm-execution=compiled \
+//         --output=./pallets/futarchy/src/weights.rs
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::weights::{Weight, constants::RocksDbWeight};
+use sp_std::marker::PhantomData;
+
+// Weight functions needed for pallet_futarchy
+pub trait WeightInfo {
+    fn create_market(o: u32) -> Weight;
+    fn activate_market() -> Weight;
+    fn set_oracle() -> Weight;
+    fn cancel_market() -> Weight;
+    fn buy_shares(o: u32) -> Weight;
+    fn sell_shares(o: u32) -> Weight;
+    fn add_liquidity(o: u32) -> Weight;
+    fn remove_liquidity(o: u32) -> Weight;
+    fn resolve_market() -> Weight;
+    fn dispute_market() -> Weight;
+    fn resolve_dispute() -> Weight;
+    fn finalize_market() -> Weight;
+    fn claim_winnings(o: u32) -> Weight;
+    fn settle_position(o: u32) -> Weight;
+    fn create_decision_market() -> Weight;
+    fn settle_decision() -> Weight;
+    fn execute_decision() -> Weight;
+    fn create_markets_batch(n: u32) -> Weight;
+    fn extend_trading() -> Weight;
+    fn expire_markets(n: u32) -> Weight;
+    fn claim_refund() -> Weight;
+    fn transfer_shares() -> Weight;
+    fn close_market() -> Weight;
+    fn reap_market() -> Weight;
+    fn set_position_cap() -> Weight;
+    fn force_cancel_market() -> Weight;
+    fn split_collateral(o: u32) -> Weight;
+    fn merge_shares(o: u32) -> Weight;
+    fn set_oracle_committee(n: u32) -> Weight;
+    fn submit_resolution(n: u32) -> Weight;
+    fn set_paused() -> Weight;
+    fn clone_market(o: u32) -> Weight;
+    fn set_scalar_scale() -> Weight;
+    fn subsidize_market() -> Weight;
+    fn add_approved_creator() -> Weight;
+    fn remove_approved_creator() -> Weight;
+    fn create_and_activate_market(o: u32) -> Weight;
+    fn set_creator_fee() -> Weight;
+    fn claim_creator_fees() -> Weight;
+    fn flag_stale_market() -> Weight;
+    fn eliminate_outcome() -> Weight;
+    fn set_collateral_cap() -> Weight;
+    fn add_trading_delegate() -> Weight;
+    fn remove_trading_delegate() -> Weight;
+    fn buy_shares_for(o: u32) -> Weight;
+    fn sell_shares_for(o: u32) -> Weight;
+    fn update_metadata(b: u32) -> Weight;
+    fn set_payout_curve() -> Weight;
+    fn set_parent_conditions(c: u32) -> Weight;
+    fn set_backup_oracles(n: u32) -> Weight;
+    fn redeem_all(n: u32) -> Weight;
+    fn migrate_market(h: u32, p: u32, d: u32) -> Weight;
+    fn process_refunds(n: u32) -> Weight;
+    fn set_featured() -> Weight;
+    fn set_auto_activate() -> Weight;
+    fn close_and_resolve() -> Weight;
+    fn set_share_cap() -> Weight;
+}
+
+// Weights for pallet_futarchy using the Substrate node and recommended hardware
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+    fn create_market(o: u32) -> Weight {
+        (95_000_000 as Weight)
+            .saturating_add((1_200_000 as Weight).saturating_mul(o as Weight))
+            .saturating_add(T::DbWeight::get().reads(7 as Weight))
+            .saturating_add(T::DbWeight::get().writes(11 as Weight))
+    }
+    fn activate_market() -> Weight {
+        (30_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().writes(2 as Weight))
+    }
+    fn set_oracle() -> Weight {
+        (28_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn cancel_market() -> Weight {
+        (110_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(8 as Weight))
+            .saturating_add(T::DbWeight::get().writes(12 as Weight))
+    }
+    fn buy_shares(o: u32) -> Weight {
+        (120_000_000 as Weight)
+            .saturating_add((9_500_000 as Weight).saturating_mul(o as Weight))
+            .saturating_add(T::DbWeight::get().reads(8 as Weight))
+            .saturating_add(T::DbWeight::get().writes(8 as Weight))
+    }
+    fn sell_shares(o: u32) -> Weight {
+        (125_000_000 as Weight)
+            .saturating_add((9_500_000 as Weight).saturating_mul(o as Weight))
+            .saturating_add(T::DbWeight::get().reads(10 as Weight))
+            .saturating_add(T::DbWeight::get().writes(10 as Weight))
+    }
+    fn add_liquidity(o: u32) -> Weight {
+        (90_000_000 as Weight)
+            .saturating_add((2_500_000 as Weight).saturating_mul(o as Weight))
+            .saturating_add(T::DbWeight::get().reads(7 as Weight))
+            .saturating_add(T::DbWeight::get().writes(9 as Weight))
+    }
+    fn remove_liquidity(o: u32) -> Weight {
+        (95_000_000 as Weight)
+            .saturating_add((2_500_000 as Weight).saturating_mul(o as Weight))
+            .saturating_add(T::DbWeight::get().reads(9 as Weight))
+            .saturating_add(T::DbWeight::get().writes(8 as Weight))
+    }
+    fn resolve_market() -> Weight {
+        (35_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().writes(3 as Weight))
+    }
+    fn dispute_market() -> Weight {
+        (45_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().writes(3 as Weight))
+    }
+    fn resolve_dispute() -> Weight {
+        (60_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(4 as Weight))
+            .saturating_add(T::DbWeight::get().writes(5 as Weight))
+    }
+    fn finalize_market() -> Weight {
+        (40_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().writes(3 as Weight))
+    }
+    fn claim_winnings(o: u32) -> Weight {
+        (85_000_000 as Weight)
+            .saturating_add((1_500_000 as Weight).saturating_mul(o as Weight))
+            .saturating_add(T::DbWeight::get().reads(7 as Weight))
+            .saturating_add(T::DbWeight::get().writes(7 as Weight))
+    }
+    fn settle_position(o: u32) -> Weight {
+        (60_000_000 as Weight)
+            .saturating_add((1_000_000 as Weight).saturating_mul(o as Weight))
+            .saturating_add(T::DbWeight::get().reads(9 as Weight))
+            .saturating_add(T::DbWeight::get().writes(9 as Weight))
+    }
+    fn create_decision_market() -> Weight {
+        (210_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(11 as Weight))
+            .saturating_add(T::DbWeight::get().writes(21 as Weight))
+    }
+    fn settle_decision() -> Weight {
+        (55_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(5 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn execute_decision() -> Weight {
+        (45_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn create_markets_batch(n: u32) -> Weight {
+        (20_000_000 as Weight)
+            .saturating_add((100_000_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().reads((6 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes((10 as Weight).saturating_mul(n as Weight)))
+    }
+    fn extend_trading() -> Weight {
+        (30_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn expire_markets(n: u32) -> Weight {
+        (10_000_000 as Weight)
+            .saturating_add((60_000_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().reads((6 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes((10 as Weight).saturating_mul(n as Weight)))
+    }
+    fn claim_refund() -> Weight {
+        (45_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(3 as Weight))
+            .saturating_add(T::DbWeight::get().writes(5 as Weight))
+    }
+    fn transfer_shares() -> Weight {
+        (40_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(4 as Weight))
+            .saturating_add(T::DbWeight::get().writes(4 as Weight))
+    }
+    fn close_market() -> Weight {
+        (25_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(2 as Weight))
+    }
+    fn reap_market() -> Weight {
+        (60_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(9 as Weight))
+            .saturating_add(T::DbWeight::get().writes(16 as Weight))
+    }
+    fn set_position_cap() -> Weight {
+        (25_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn force_cancel_market() -> Weight {
+        (80_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(8 as Weight))
+            .saturating_add(T::DbWeight::get().writes(12 as Weight))
+    }
+    fn split_collateral(o: u32) -> Weight {
+        (45_000_000 as Weight)
+            .saturating_add((2_000_000 as Weight).saturating_mul(o as Weight))
+            .saturating_add(T::DbWeight::get().reads(5 as Weight))
+            .saturating_add(T::DbWeight::get().writes(6 as Weight))
+    }
+    fn merge_shares(o: u32) -> Weight {
+        (55_000_000 as Weight)
+            .saturating_add((2_000_000 as Weight).saturating_mul(o as Weight))
+            .saturating_add(T::DbWeight::get().reads(5 as Weight))
+            .saturating_add(T::DbWeight::get().writes(7 as Weight))
+    }
+    fn set_oracle_committee(n: u32) -> Weight {
+        (28_000_000 as Weight)
+            .saturating_add((1_500_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn submit_resolution(n: u32) -> Weight {
+        (60_000_000 as Weight)
+            .saturating_add((3_000_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(T::DbWeight::get().reads(3 as Weight))
+            .saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(T::DbWeight::get().writes(5 as Weight))
+    }
+    fn set_paused() -> Weight {
+        (12_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn clone_market(o: u32) -> Weight {
+        (100_000_000 as Weight)
+            .saturating_add((1_200_000 as Weight).saturating_mul(o as Weight))
+            .saturating_add(T::DbWeight::get().reads(8 as Weight))
+            .saturating_add(T::DbWeight::get().writes(11 as Weight))
+    }
+    fn set_scalar_scale() -> Weight {
+        (25_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn subsidize_market() -> Weight {
+        (50_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(3 as Weight))
+            .saturating_add(T::DbWeight::get().writes(4 as Weight))
+    }
+    fn add_approved_creator() -> Weight {
+        (15_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn remove_approved_creator() -> Weight {
+        (15_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn create_and_activate_market(o: u32) -> Weight {
+        (100_000_000 as Weight)
+            .saturating_add((1_200_000 as Weight).saturating_mul(o as Weight))
+            .saturating_add(T::DbWeight::get().reads(9 as Weight))
+            .saturating_add(T::DbWeight::get().writes(12 as Weight))
+    }
+    fn set_creator_fee() -> Weight {
+        (25_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn claim_creator_fees() -> Weight {
+        (60_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(3 as Weight))
+            .saturating_add(T::DbWeight::get().writes(3 as Weight))
+    }
+    fn flag_stale_market() -> Weight {
+        (25_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn eliminate_outcome() -> Weight {
+        (30_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn set_collateral_cap() -> Weight {
+        (25_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn add_trading_delegate() -> Weight {
+        (20_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn remove_trading_delegate() -> Weight {
+        (20_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn buy_shares_for(o: u32) -> Weight {
+        (125_000_000 as Weight)
+            .saturating_add((9_500_000 as Weight).saturating_mul(o as Weight))
+            .saturating_add(T::DbWeight::get().reads(9 as Weight))
+            .saturating_add(T::DbWeight::get().writes(8 as Weight))
+    }
+    fn sell_shares_for(o: u32) -> Weight {
+        (130_000_000 as Weight)
+            .saturating_add((9_500_000 as Weight).saturating_mul(o as Weight))
+            .saturating_add(T::DbWeight::get().reads(11 as Weight))
+            .saturating_add(T::DbWeight::get().writes(10 as Weight))
+    }
+    fn update_metadata(b: u32) -> Weight {
+        (25_000_000 as Weight)
+            .saturating_add((2_000 as Weight).saturating_mul(b as Weight))
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn set_payout_curve() -> Weight {
+        (25_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn set_parent_conditions(c: u32) -> Weight {
+        (25_000_000 as Weight)
+            .saturating_add((4_000_000 as Weight).saturating_mul(c as Weight))
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().reads((3 as Weight).saturating_mul(c as Weight)))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes((2 as Weight).saturating_mul(c as Weight)))
+    }
+    fn set_backup_oracles(n: u32) -> Weight {
+        (25_000_000 as Weight)
+            .saturating_add((500_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn redeem_all(n: u32) -> Weight {
+        (10_000_000 as Weight)
+            .saturating_add((90_000_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(T::DbWeight::get().reads((7 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(T::DbWeight::get().writes((7 as Weight).saturating_mul(n as Weight)))
+    }
+    fn migrate_market(h: u32, p: u32, d: u32) -> Weight {
+        (150_000_000 as Weight)
+            .saturating_add((20_000_000 as Weight).saturating_mul(h as Weight))
+            .saturating_add((8_000_000 as Weight).saturating_mul(p as Weight))
+            .saturating_add((25_000_000 as Weight).saturating_mul(d as Weight))
+            .saturating_add(T::DbWeight::get().reads(16 as Weight))
+            .saturating_add(T::DbWeight::get().reads((2 as Weight).saturating_mul(h as Weight)))
+            .saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(p as Weight)))
+            .saturating_add(T::DbWeight::get().reads((2 as Weight).saturating_mul(d as Weight)))
+            .saturating_add(T::DbWeight::get().writes(32 as Weight))
+            .saturating_add(T::DbWeight::get().writes((6 as Weight).saturating_mul(h as Weight)))
+            .saturating_add(T::DbWeight::get().writes((2 as Weight).saturating_mul(p as Weight)))
+            .saturating_add(T::DbWeight::get().writes((3 as Weight).saturating_mul(d as Weight)))
+    }
+    fn process_refunds(n: u32) -> Weight {
+        (20_000_000 as Weight)
+            .saturating_add((45_000_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(T::DbWeight::get().reads(6 as Weight))
+            .saturating_add(T::DbWeight::get().reads((2 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes((4 as Weight).saturating_mul(n as Weight)))
+    }
+    fn set_featured() -> Weight {
+        (20_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn set_auto_activate() -> Weight {
+        (25_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn close_and_resolve() -> Weight {
+        (55_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().writes(5 as Weight))
+    }
+    fn set_share_cap() -> Weight {
+        (25_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+    fn create_market(o: u32) -> Weight {
+        (95_000_000 as Weight)
+            .saturating_add((1_200_000 as Weight).saturating_mul(o as Weight))
+            .saturating_add(RocksDbWeight::get().reads(7 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(11 as Weight))
+    }
+    fn activate_market() -> Weight {
+        (30_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(2 as Weight))
+    }
+    fn set_oracle() -> Weight {
+        (28_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn cancel_market() -> Weight {
+        (110_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(8 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(12 as Weight))
+    }
+    fn buy_shares(o: u32) -> Weight {
+        (120_000_000 as Weight)
+            .saturating_add((9_500_000 as Weight).saturating_mul(o as Weight))
+            .saturating_add(RocksDbWeight::get().reads(8 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(8 as Weight))
+    }
+    fn sell_shares(o: u32) -> Weight {
+        (125_000_000 as Weight)
+            .saturating_add((9_500_000 as Weight).saturating_mul(o as Weight))
+            .saturating_add(RocksDbWeight::get().reads(10 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(10 as Weight))
+    }
+    fn add_liquidity(o: u32) -> Weight {
+        (90_000_000 as Weight)
+            .saturating_add((2_500_000 as Weight).saturating_mul(o as Weight))
+            .saturating_add(RocksDbWeight::get().reads(7 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(9 as Weight))
+    }
+    fn remove_liquidity(o: u32) -> Weight {
+        (95_000_000 as Weight)
+            .saturating_add((2_500_000 as Weight).saturating_mul(o as Weight))
+            .saturating_add(RocksDbWeight::get().reads(9 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(8 as Weight))
+    }
+    fn resolve_market() -> Weight {
+        (35_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(3 as Weight))
+    }
+    fn dispute_market() -> Weight {
+        (45_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(3 as Weight))
+    }
+    fn resolve_dispute() -> Weight {
+        (60_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(4 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(5 as Weight))
+    }
+    fn finalize_market() -> Weight {
+        (40_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(3 as Weight))
+    }
+    fn claim_winnings(o: u32) -> Weight {
+        (85_000_000 as Weight)
+            .saturating_add((1_500_000 as Weight).saturating_mul(o as Weight))
+            .saturating_add(RocksDbWeight::get().reads(7 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(7 as Weight))
+    }
+    fn settle_position(o: u32) -> Weight {
+        (60_000_000 as Weight)
+            .saturating_add((1_000_000 as Weight).saturating_mul(o as Weight))
+            .saturating_add(RocksDbWeight::get().reads(9 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(9 as Weight))
+    }
+    fn create_decision_market() -> Weight {
+        (210_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(11 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(21 as Weight))
+    }
+    fn settle_decision() -> Weight {
+        (55_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(5 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn execute_decision() -> Weight {
+        (45_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn create_markets_batch(n: u32) -> Weight {
+        (20_000_000 as Weight)
+            .saturating_add((100_000_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((6 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes((10 as Weight).saturating_mul(n as Weight)))
+    }
+    fn extend_trading() -> Weight {
+        (30_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn expire_markets(n: u32) -> Weight {
+        (10_000_000 as Weight)
+            .saturating_add((60_000_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((6 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes((10 as Weight).saturating_mul(n as Weight)))
+    }
+    fn claim_refund() -> Weight {
+        (45_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(3 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(5 as Weight))
+    }
+    fn transfer_shares() -> Weight {
+        (40_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(4 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(4 as Weight))
+    }
+    fn close_market() -> Weight {
+        (25_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(2 as Weight))
+    }
+    fn reap_market() -> Weight {
+        (60_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(9 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(16 as Weight))
+    }
+    fn set_position_cap() -> Weight {
+        (25_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn force_cancel_market() -> Weight {
+        (80_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(8 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(12 as Weight))
+    }
+    fn split_collateral(o: u32) -> Weight {
+        (45_000_000 as Weight)
+            .saturating_add((2_000_000 as Weight).saturating_mul(o as Weight))
+            .saturating_add(RocksDbWeight::get().reads(5 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(6 as Weight))
+    }
+    fn merge_shares(o: u32) -> Weight {
+        (55_000_000 as Weight)
+            .saturating_add((2_000_000 as Weight).saturating_mul(o as Weight))
+            .saturating_add(RocksDbWeight::get().reads(5 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(7 as Weight))
+    }
+    fn set_oracle_committee(n: u32) -> Weight {
+        (28_000_000 as Weight)
+            .saturating_add((1_500_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn submit_resolution(n: u32) -> Weight {
+        (60_000_000 as Weight)
+            .saturating_add((3_000_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(RocksDbWeight::get().reads(3 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(RocksDbWeight::get().writes(5 as Weight))
+    }
+    fn set_paused() -> Weight {
+        (12_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn clone_market(o: u32) -> Weight {
+        (100_000_000 as Weight)
+            .saturating_add((1_200_000 as Weight).saturating_mul(o as Weight))
+            .saturating_add(RocksDbWeight::get().reads(8 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(11 as Weight))
+    }
+    fn set_scalar_scale() -> Weight {
+        (25_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn subsidize_market() -> Weight {
+        (50_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(3 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(4 as Weight))
+    }
+    fn add_approved_creator() -> Weight {
+        (15_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn remove_approved_creator() -> Weight {
+        (15_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn create_and_activate_market(o: u32) -> Weight {
+        (100_000_000 as Weight)
+            .saturating_add((1_200_000 as Weight).saturating_mul(o as Weight))
+            .saturating_add(RocksDbWeight::get().reads(9 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(12 as Weight))
+    }
+    fn set_creator_fee() -> Weight {
+        (25_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn claim_creator_fees() -> Weight {
+        (60_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(3 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(3 as Weight))
+    }
+    fn flag_stale_market() -> Weight {
+        (25_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn eliminate_outcome() -> Weight {
+        (30_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn set_collateral_cap() -> Weight {
+        (25_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn add_trading_delegate() -> Weight {
+        (20_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn remove_trading_delegate() -> Weight {
+        (20_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn buy_shares_for(o: u32) -> Weight {
+        (125_000_000 as Weight)
+            .saturating_add((9_500_000 as Weight).saturating_mul(o as Weight))
+            .saturating_add(RocksDbWeight::get().reads(9 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(8 as Weight))
+    }
+    fn sell_shares_for(o: u32) -> Weight {
+        (130_000_000 as Weight)
+            .saturating_add((9_500_000 as Weight).saturating_mul(o as Weight))
+            .saturating_add(RocksDbWeight::get().reads(11 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(10 as Weight))
+    }
+    fn update_metadata(b: u32) -> Weight {
+        (25_000_000 as Weight)
+            .saturating_add((2_000 as Weight).saturating_mul(b as Weight))
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn set_payout_curve() -> Weight {
+        (25_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn set_parent_conditions(c: u32) -> Weight {
+        (25_000_000 as Weight)
+            .saturating_add((4_000_000 as Weight).saturating_mul(c as Weight))
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((3 as Weight).saturating_mul(c as Weight)))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes((2 as Weight).saturating_mul(c as Weight)))
+    }
+    fn set_backup_oracles(n: u32) -> Weight {
+        (25_000_000 as Weight)
+            .saturating_add((500_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn redeem_all(n: u32) -> Weight {
+        (10_000_000 as Weight)
+            .saturating_add((90_000_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(RocksDbWeight::get().reads((7 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(RocksDbWeight::get().writes((7 as Weight).saturating_mul(n as Weight)))
+    }
+    fn migrate_market(h: u32, p: u32, d: u32) -> Weight {
+        (150_000_000 as Weight)
+            .saturating_add((20_000_000 as Weight).saturating_mul(h as Weight))
+            .saturating_add((8_000_000 as Weight).saturating_mul(p as Weight))
+            .saturating_add((25_000_000 as Weight).saturating_mul(d as Weight))
+            .saturating_add(RocksDbWeight::get().reads(16 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((2 as Weight).saturating_mul(h as Weight)))
+            .saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(p as Weight)))
+            .saturating_add(RocksDbWeight::get().reads((2 as Weight).saturating_mul(d as Weight)))
+            .saturating_add(RocksDbWeight::get().writes(32 as Weight))
+            .saturating_add(RocksDbWeight::get().writes((6 as Weight).saturating_mul(h as Weight)))
+            .saturating_add(RocksDbWeight::get().writes((2 as Weight).saturating_mul(p as Weight)))
+            .saturating_add(RocksDbWeight::get().writes((3 as Weight).saturating_mul(d as Weight)))
+    }
+    fn process_refunds(n: u32) -> Weight {
+        (20_000_000 as Weight)
+            .saturating_add((45_000_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(RocksDbWeight::get().reads(6 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((2 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes((4 as Weight).saturating_mul(n as Weight)))
+    }
+    fn set_featured() -> Weight {
+        (20_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn set_auto_activate() -> Weight {
+        (25_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn close_and_resolve() -> Weight {
+        (55_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(5 as Weight))
+    }
+    fn set_share_cap() -> Weight {
+        (25_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+}